@@ -6,6 +6,7 @@ use crate::{
     failpoint::fail_point,
     metrics::metrics,
     param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam},
+    proof::{self, ProofQuery},
     version::Version,
 };
 
@@ -17,6 +18,7 @@ use aptos_types::{
     account_config::AccountResource,
     account_state::AccountState,
     event::{EventHandle, EventKey},
+    ledger_info::LedgerInfoWithSignatures,
 };
 
 use anyhow::Result;
@@ -34,6 +36,7 @@ use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam)
         .and(warp::get())
+        .and(warp::query::<ProofQuery>())
         .and(context.filter())
         .and_then(handle_get_account)
         .with(metrics("get_account"))
@@ -68,10 +71,11 @@ pub fn get_account_modules(context: Context) -> BoxedFilter<(impl Reply,)> {
 
 async fn handle_get_account(
     address: AddressParam,
+    proof: ProofQuery,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account")?;
-    Ok(Account::new(None, address, context)?.account()?)
+    Ok(Account::new(None, address, context)?.account(proof)?)
 }
 
 async fn handle_get_account_resources(
@@ -96,6 +100,8 @@ pub(crate) struct Account {
     ledger_version: u64,
     address: Address,
     latest_ledger_info: LedgerInfo,
+    /// The signed ledger info of `latest_ledger_info`, which the state proofs are made up to.
+    latest_ledger_info_with_sigs: LedgerInfoWithSignatures,
     context: Context,
 }
 
@@ -105,7 +111,9 @@ impl Account {
         address: AddressParam,
         context: Context,
     ) -> Result<Self, Error> {
-        let latest_ledger_info = context.get_latest_ledger_info()?;
+        let latest_ledger_info_with_sigs = context.get_latest_ledger_info_with_signatures()?;
+        let latest_ledger_info =
+            LedgerInfo::new(&context.chain_id(), &latest_ledger_info_with_sigs);
         let ledger_version = ledger_version
             .map(|v| v.parse("ledger version"))
             .unwrap_or_else(|| Ok(latest_ledger_info.version()))?;
@@ -122,11 +130,12 @@ impl Account {
             ledger_version,
             address: address.parse("account address")?,
             latest_ledger_info,
+            latest_ledger_info_with_sigs,
             context,
         })
     }
 
-    pub fn account(self) -> Result<impl Reply, Error> {
+    pub fn account(self, proof: ProofQuery) -> Result<impl Reply, Error> {
         let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
             self.address.into(),
             AccountResource::struct_tag(),
@@ -143,8 +152,20 @@ impl Account {
             .ok_or_else(|| self.resource_not_found(&AccountResource::struct_tag()))?;
 
         let account: AccountData = account_resource.into();
+        let proof = proof
+            .known_version(self.ledger_version)?
+            .map(|known_version| {
+                proof::prove_state_value(
+                    &self.context,
+                    &state_key,
+                    self.ledger_version,
+                    known_version,
+                    self.latest_ledger_info_with_sigs.clone(),
+                )
+            })
+            .transpose()?;
 
-        Response::new(self.latest_ledger_info, &account)
+        proof::render(self.latest_ledger_info, &account, proof)
     }
 
     pub fn resources(self) -> Result<impl Reply, Error> {
@@ -0,0 +1,204 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    param::{BlockHashParam, BlockHeightParam, Param, TransactionVersionParam},
+};
+
+use aptos_api_types::{AsConverter, Block, Error, HashValue, LedgerInfo, Response, TransactionId};
+use aptos_types::transaction::Transaction;
+
+use anyhow::Result;
+use serde::Deserialize;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+/// Max number of transactions loaded from the DB at a time when rendering a block.
+const MAX_TRANSACTIONS_PER_FETCH: u16 = 1000;
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockQuery {
+    with_transactions: Option<Param<bool>>,
+}
+
+impl BlockQuery {
+    fn with_transactions(self) -> Result<bool, Error> {
+        self.with_transactions
+            .map(|v| v.parse("with_transactions"))
+            .unwrap_or(Ok(false))
+    }
+}
+
+// GET /blocks/by_height/{height}?with_transactions={bool}
+pub fn get_block_by_height(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("blocks" / "by_height" / BlockHeightParam)
+        .and(warp::get())
+        .and(warp::query::<BlockQuery>())
+        .and(context.filter())
+        .and_then(handle_get_block_by_height)
+        .with(metrics("get_block_by_height"))
+        .boxed()
+}
+
+// GET /blocks/by_version/{version}?with_transactions={bool}
+pub fn get_block_by_version(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("blocks" / "by_version" / TransactionVersionParam)
+        .and(warp::get())
+        .and(warp::query::<BlockQuery>())
+        .and(context.filter())
+        .and_then(handle_get_block_by_version)
+        .with(metrics("get_block_by_version"))
+        .boxed()
+}
+
+// GET /blocks/by_hash/{hash}?with_transactions={bool}
+pub fn get_block_by_hash(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("blocks" / "by_hash" / BlockHashParam)
+        .and(warp::get())
+        .and(warp::query::<BlockQuery>())
+        .and(context.filter())
+        .and_then(handle_get_block_by_hash)
+        .with(metrics("get_block_by_hash"))
+        .boxed()
+}
+
+async fn handle_get_block_by_height(
+    height: BlockHeightParam,
+    query: BlockQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_block_by_height")?;
+    Ok(Blocks::new(context)?
+        .get_by_height(height.parse("block height")?, query.with_transactions()?)?)
+}
+
+async fn handle_get_block_by_version(
+    version: TransactionVersionParam,
+    query: BlockQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_block_by_version")?;
+    Ok(Blocks::new(context)?.get_by_version(
+        version.parse("transaction version")?,
+        query.with_transactions()?,
+    )?)
+}
+
+async fn handle_get_block_by_hash(
+    hash: BlockHashParam,
+    query: BlockQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_block_by_hash")?;
+    Ok(Blocks::new(context)?.get_by_hash(hash.parse("block hash")?, query.with_transactions()?)?)
+}
+
+struct Blocks {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl Blocks {
+    fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info()?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    fn get_by_height(self, height: u64, with_transactions: bool) -> Result<impl Reply, Error> {
+        let block = self.render_block(height, with_transactions)?;
+        Response::new(self.ledger_info, &block)
+    }
+
+    fn get_by_version(self, version: u64, with_transactions: bool) -> Result<impl Reply, Error> {
+        let ledger_version = self.ledger_info.version();
+        if version > ledger_version {
+            return Err(Error::not_found(
+                "transaction",
+                TransactionId::Version(version),
+                ledger_version,
+            ));
+        }
+        let height = self
+            .context
+            .get_block_height_by_version(version, ledger_version)?
+            .ok_or_else(|| {
+                Error::not_found(
+                    "block",
+                    format!("containing version {}", version),
+                    ledger_version,
+                )
+            })?;
+
+        let block = self.render_block(height, with_transactions)?;
+        Response::new(self.ledger_info, &block)
+    }
+
+    fn get_by_hash(self, hash: HashValue, with_transactions: bool) -> Result<impl Reply, Error> {
+        let ledger_version = self.ledger_info.version();
+        let block_hash: aptos_crypto::HashValue = hash.into();
+        // The genesis block isn't started by a block metadata transaction, its hash is zero.
+        let height = if block_hash == aptos_crypto::HashValue::zero() {
+            0
+        } else {
+            self.context
+                .get_block_height_by_hash(block_hash, ledger_version)?
+                .ok_or_else(|| Error::not_found("block", hash, ledger_version))?
+        };
+
+        let block = self.render_block(height, with_transactions)?;
+        Response::new(self.ledger_info, &block)
+    }
+
+    fn render_block(&self, height: u64, with_transactions: bool) -> Result<Block, Error> {
+        let ledger_version = self.ledger_info.version();
+        let (first_version, last_version) = self
+            .context
+            .get_block_version_range_by_height(height, ledger_version)?
+            .ok_or_else(|| Error::not_found("block", height, ledger_version))?;
+
+        let first_txn = self
+            .context
+            .get_transaction_by_version(first_version, ledger_version)?;
+        let metadata = match &first_txn.transaction {
+            Transaction::BlockMetadata(metadata) => Some(metadata),
+            _ => None,
+        };
+        let timestamp = metadata
+            .map(|metadata| metadata.timestamp_usecs())
+            .unwrap_or(0);
+
+        let transactions = if with_transactions {
+            let resolver = self.context.move_resolver()?;
+            let converter = resolver.as_converter();
+            let mut txns = Vec::with_capacity((last_version - first_version + 1) as usize);
+            let mut start_version = first_version;
+            while start_version <= last_version {
+                let limit = (last_version - start_version + 1)
+                    .min(MAX_TRANSACTIONS_PER_FETCH as u64) as u16;
+                for txn in self
+                    .context
+                    .get_transactions(start_version, limit, ledger_version)?
+                {
+                    txns.push(converter.try_into_onchain_transaction(timestamp, txn)?);
+                }
+                start_version += limit as u64;
+            }
+            Some(txns)
+        } else {
+            None
+        };
+
+        Ok(Block::new(
+            height,
+            first_version,
+            last_version,
+            metadata,
+            transactions,
+        ))
+    }
+}
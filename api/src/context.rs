@@ -12,22 +12,28 @@ use aptos_types::{
     contract_event::ContractEvent,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
-    transaction::{SignedTransaction, TransactionWithProof},
+    on_chain_config::{OnChainConfig, VMConfig},
+    proof::{SparseMerkleProof, TransactionInfoWithProof},
+    state_proof::StateProof,
+    transaction::{SignedTransaction, Transaction, TransactionWithProof},
+    write_set::WriteOp,
 };
 use storage_interface::{DbReader, Order};
 
 use anyhow::{ensure, format_err, Result};
 use aptos_state_view::StateView;
 use aptos_types::{
-    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix},
+    state_store::{state_key::StateKey, state_key_prefix::StateKeyPrefix, state_value::StateValue},
     transaction::Version,
 };
 use aptos_vm::data_cache::{IntoMoveResolver, RemoteStorageOwned};
 use futures::{channel::oneshot, SinkExt};
+use move_deps::move_core_types::gas_schedule::GasAlgebra;
 use std::{convert::Infallible, sync::Arc};
 use storage_interface::state_view::{
     DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView,
 };
+use tokio::sync::watch;
 use warp::{filters::BoxedFilter, Filter, Reply};
 
 // Context holds application scope context
@@ -37,6 +43,8 @@ pub struct Context {
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     api_config: ApiConfig,
+    committed_version_sender: Arc<watch::Sender<Version>>,
+    committed_version_receiver: watch::Receiver<Version>,
 }
 
 impl Context {
@@ -46,11 +54,14 @@ impl Context {
         mp_sender: MempoolClientSender,
         api_config: ApiConfig,
     ) -> Self {
+        let (committed_version_sender, committed_version_receiver) = watch::channel(0);
         Self {
             chain_id,
             db,
             mp_sender,
             api_config,
+            committed_version_sender: Arc::new(committed_version_sender),
+            committed_version_receiver,
         }
     }
 
@@ -72,6 +83,25 @@ impl Context {
         self.api_config.content_length_limit()
     }
 
+    pub fn max_view_function_gas(&self) -> u64 {
+        self.api_config.max_view_function_gas()
+    }
+
+    pub fn max_submit_transaction_batch_size(&self) -> usize {
+        self.api_config.max_submit_transaction_batch_size()
+    }
+
+    /// Notifies the open streams that the ledger has been committed up to `version`.
+    pub fn notify_committed(&self, version: Version) {
+        // Never fails, as the context itself holds a receiver.
+        let _ = self.committed_version_sender.send(version);
+    }
+
+    /// Returns a receiver that is marked as changed whenever `notify_committed` is called.
+    pub fn committed_version_receiver(&self) -> watch::Receiver<Version> {
+        self.committed_version_receiver.clone()
+    }
+
     pub fn filter(self) -> impl Filter<Extract = (Context,), Error = Infallible> + Clone {
         warp::any().map(move || self.clone())
     }
@@ -103,6 +133,24 @@ impl Context {
             .get_state_value(state_key)
     }
 
+    pub fn get_state_value_with_proof(
+        &self,
+        state_key: &StateKey,
+        version: u64,
+    ) -> Result<(Option<StateValue>, SparseMerkleProof)> {
+        self.db
+            .get_state_value_with_proof_by_version(state_key, version)
+    }
+
+    pub fn get_state_proof_with_ledger_info(
+        &self,
+        known_version: u64,
+        ledger_info: LedgerInfoWithSignatures,
+    ) -> Result<StateProof> {
+        self.db
+            .get_state_proof_with_ledger_info(known_version, ledger_info)
+    }
+
     pub fn get_account_state(
         &self,
         address: AccountAddress,
@@ -115,10 +163,50 @@ impl Context {
         )
     }
 
+    pub fn get_first_state_key_history_version(&self) -> Result<Option<u64>> {
+        self.db.get_first_state_key_history_version()
+    }
+
+    pub fn get_state_key_history(
+        &self,
+        state_key: &StateKey,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<(u64, WriteOp)>> {
+        self.db
+            .get_state_key_history(state_key, start_version, limit as u64, ledger_version)
+    }
+
     pub fn get_block_timestamp(&self, version: u64) -> Result<u64> {
         self.db.get_block_timestamp(version)
     }
 
+    pub fn get_block_height_by_version(
+        &self,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<Option<u64>> {
+        self.db.get_block_height_by_version(version, ledger_version)
+    }
+
+    pub fn get_block_height_by_hash(
+        &self,
+        block_hash: HashValue,
+        ledger_version: u64,
+    ) -> Result<Option<u64>> {
+        self.db.get_block_height_by_hash(block_hash, ledger_version)
+    }
+
+    pub fn get_block_version_range_by_height(
+        &self,
+        height: u64,
+        ledger_version: u64,
+    ) -> Result<Option<(u64, u64)>> {
+        self.db
+            .get_block_version_range_by_height(height, ledger_version)
+    }
+
     pub fn get_transactions(
         &self,
         start_version: u64,
@@ -208,6 +296,48 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    pub async fn get_mempool_gas_prices(&self, limit: usize) -> Result<Vec<u64>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetGasPrices(limit, req_sender))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    /// Returns the gas unit prices of the user transactions among the last `limit` committed
+    /// transactions up to `ledger_version`.
+    pub fn get_committed_gas_prices(&self, limit: u16, ledger_version: u64) -> Result<Vec<u64>> {
+        let start_version = (ledger_version + 1).saturating_sub(limit as u64);
+        let txns = self.db.get_transactions(
+            start_version,
+            ledger_version + 1 - start_version,
+            ledger_version,
+            false,
+        )?;
+        Ok(txns
+            .transactions
+            .into_iter()
+            .filter_map(|txn| match txn {
+                Transaction::UserTransaction(txn) => Some(txn.gas_unit_price()),
+                _ => None,
+            })
+            .collect())
+    }
+
+    pub fn get_min_gas_unit_price(&self) -> Result<u64> {
+        let vm_config = VMConfig::fetch_config(&self.move_resolver()?)
+            .ok_or_else(|| format_err!("failed to fetch on-chain VMConfig"))?;
+        Ok(vm_config
+            .gas_schedule
+            .gas_constants
+            .min_price_per_gas_unit
+            .get())
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
@@ -220,6 +350,26 @@ impl Context {
         )?)
     }
 
+    pub fn get_transaction_with_proof(
+        &self,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<TransactionWithProof> {
+        self.db
+            .get_transaction_by_version(version, ledger_version, true)
+    }
+
+    pub fn get_transaction_info_with_proof(
+        &self,
+        version: u64,
+        ledger_version: u64,
+    ) -> Result<TransactionInfoWithProof> {
+        Ok(self
+            .db
+            .get_transaction_by_version(version, ledger_version, false)?
+            .proof)
+    }
+
     pub fn get_accumulator_root_hash(&self, version: u64) -> Result<HashValue> {
         self.db.get_accumulator_root_hash(version)
     }
@@ -254,6 +404,17 @@ impl Context {
             .collect::<Vec<_>>())
     }
 
+    /// Returns the sequence number of the next event to be emitted under `event_key`.
+    pub fn get_next_event_sequence_number(&self, event_key: &EventKey) -> Result<u64> {
+        let latest = self
+            .db
+            .get_events(event_key, u64::MAX, Order::Descending, 1)?;
+        Ok(latest
+            .first()
+            .map(|event| event.event.sequence_number() + 1)
+            .unwrap_or(0))
+    }
+
     pub fn health_check_route(&self) -> BoxedFilter<(impl Reply,)> {
         super::health_check::health_check_route(self.db.clone())
     }
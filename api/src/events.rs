@@ -6,32 +6,37 @@ use crate::{
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
-    page::Page,
-    param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam},
+    page::{page, scan, Page},
+    param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam, Param},
 };
 
-use aptos_api_types::{AsConverter, Error, LedgerInfo, Response};
+use aptos_api_types::{AsConverter, Error, LedgerInfo, MoveType};
 
 use anyhow::Result;
-use aptos_types::event::EventKey;
+use aptos_types::{contract_event::ContractEvent, event::EventKey};
+use move_deps::move_core_types::language_storage::TypeTag;
+use serde::Deserialize;
+use std::convert::TryInto;
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
-// GET /events/<event_key>
+// GET /events/<event_key>?start={u64}&limit={u16}&cursor={cursor}&event_type={move_type}
 pub fn get_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("events" / EventKeyParam)
         .and(warp::get())
-        .and(warp::query::<Page>())
+        .and(page())
+        .and(warp::query::<EventFilter>())
         .and(context.filter())
         .and_then(handle_get_events_by_event_key)
         .with(metrics("get_events_by_event_key"))
         .boxed()
 }
 
-// GET /accounts/<address>/events/<event_handle_struct>/<field_name>
+// GET /accounts/<address>/events/<event_handle_struct>/<field_name>?start={u64}&limit={u16}&cursor={cursor}&event_type={move_type}
 pub fn get_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "events" / MoveStructTagParam / MoveIdentifierParam)
         .and(warp::get())
-        .and(warp::query::<Page>())
+        .and(page())
+        .and(warp::query::<EventFilter>())
         .and(context.filter())
         .and_then(handle_get_events_by_event_handle)
         .with(metrics("get_events_by_event_handle"))
@@ -41,10 +46,11 @@ pub fn get_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)
 async fn handle_get_events_by_event_key(
     event_key: EventKeyParam,
     page: Page,
+    filter: EventFilter,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_key")?;
-    Ok(Events::new(event_key.parse("event key")?.into(), context)?.list(page)?)
+    Ok(Events::new(event_key.parse("event key")?.into(), context)?.list(page, filter)?)
 }
 
 async fn handle_get_events_by_event_handle(
@@ -52,12 +58,39 @@ async fn handle_get_events_by_event_handle(
     struct_tag: MoveStructTagParam,
     field_name: MoveIdentifierParam,
     page: Page,
+    filter: EventFilter,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_handle")?;
     let key =
         Account::new(None, address, context.clone())?.find_event_key(struct_tag, field_name)?;
-    Ok(Events::new(key, context)?.list(page)?)
+    Ok(Events::new(key, context)?.list(page, filter)?)
+}
+
+/// Filters the listed events by their type.
+#[derive(Clone, Debug, Deserialize)]
+struct EventFilter {
+    event_type: Option<Param<MoveType>>,
+}
+
+impl EventFilter {
+    fn matcher(self) -> Result<impl Fn(&ContractEvent) -> bool, Error> {
+        let event_type: Option<TypeTag> = self
+            .event_type
+            .map(|v| {
+                let event_type = v.parse("event_type")?;
+                event_type
+                    .clone()
+                    .try_into()
+                    .map_err(|_| Error::invalid_param("event_type", event_type))
+            })
+            .transpose()?;
+        Ok(move |event: &ContractEvent| {
+            event_type
+                .as_ref()
+                .map_or(true, |event_type| event.type_tag() == event_type)
+        })
+    }
 }
 
 struct Events {
@@ -76,16 +109,21 @@ impl Events {
         })
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
-        let contract_events = self.context.get_events(
-            &self.key,
+    pub fn list(self, page: Page, filter: EventFilter) -> Result<impl Reply, Error> {
+        let (contract_events, next) = scan(
             page.start(0, u64::MAX)?,
             page.limit()?,
-            self.ledger_info.version(),
+            |start, limit| {
+                Ok(self
+                    .context
+                    .get_events(&self.key, start, limit, self.ledger_info.version())?)
+            },
+            |event| event.sequence_number(),
+            filter.matcher()?,
         )?;
 
         let resolver = self.context.move_resolver()?;
         let events = resolver.as_converter().try_into_events(&contract_events)?;
-        Response::new(self.ledger_info, &events)
+        page.render(self.ledger_info, &events, next)
     }
 }
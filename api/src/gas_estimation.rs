@@ -0,0 +1,83 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{context::Context, failpoint::fail_point, metrics::metrics};
+
+use aptos_api_types::{Error, GasEstimation, LedgerInfo, Response};
+
+use anyhow::Result;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+/// Number of most recently committed transactions sampled for gas prices.
+const COMMITTED_TXNS_SAMPLE_SIZE: u16 = 1000;
+/// Number of highest ranked mempool transactions sampled for gas prices.
+const MEMPOOL_TXNS_SAMPLE_SIZE: usize = 1000;
+
+const LOW_PERCENTILE: usize = 10;
+const MEDIAN_PERCENTILE: usize = 50;
+const HIGH_PERCENTILE: usize = 90;
+
+// GET /estimate_gas_price
+pub fn estimate_gas_price(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("estimate_gas_price")
+        .and(warp::get())
+        .and(context.filter())
+        .and_then(handle_estimate_gas_price)
+        .with(metrics("estimate_gas_price"))
+        .boxed()
+}
+
+async fn handle_estimate_gas_price(context: Context) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_estimate_gas_price")?;
+    Ok(GasEstimator::new(context)?.estimate().await?)
+}
+
+struct GasEstimator {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl GasEstimator {
+    fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info()?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    async fn estimate(self) -> Result<impl Reply, Error> {
+        let min_gas_unit_price = self.context.get_min_gas_unit_price()?;
+        let mut gas_prices = self
+            .context
+            .get_committed_gas_prices(COMMITTED_TXNS_SAMPLE_SIZE, self.ledger_info.version())?;
+        gas_prices.extend(
+            self.context
+                .get_mempool_gas_prices(MEMPOOL_TXNS_SAMPLE_SIZE)
+                .await?,
+        );
+
+        let estimation = estimate_from_samples(gas_prices, min_gas_unit_price);
+        Response::new(self.ledger_info, &estimation)
+    }
+}
+
+/// Picks the low, median and high gas unit prices out of the sampled prices. None of them is
+/// lower than the minimum price accepted on chain, which is also the estimation when there are
+/// no samples at all.
+fn estimate_from_samples(mut gas_prices: Vec<u64>, min_gas_unit_price: u64) -> GasEstimation {
+    gas_prices.sort_unstable();
+    let percentile = |p: usize| {
+        gas_prices
+            .get(gas_prices.len().saturating_sub(1) * p / 100)
+            .copied()
+            .unwrap_or(min_gas_unit_price)
+            .max(min_gas_unit_price)
+    };
+
+    GasEstimation {
+        low: percentile(LOW_PERCENTILE).into(),
+        median: percentile(MEDIAN_PERCENTILE).into(),
+        high: percentile(HIGH_PERCENTILE).into(),
+    }
+}
@@ -2,13 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    accounts,
+    accounts, blocks,
     context::Context,
     events,
     failpoint::fail_point,
-    log,
+    gas_estimation, log,
     metrics::{metrics, status_metrics},
-    state, transactions,
+    state, streaming, transactions, view_function,
 };
 use aptos_api_types::{Error, Response};
 
@@ -36,12 +36,25 @@ pub fn routes(context: Context) -> impl Filter<Extract = impl Reply, Error = Inf
         .or(transactions::get_account_transactions(context.clone()))
         .or(transactions::submit_bcs_transactions(context.clone()))
         .or(transactions::submit_json_transactions(context.clone()))
+        .or(transactions::submit_bcs_transaction_batch(context.clone()))
+        .or(transactions::submit_json_transaction_batch(context.clone()))
+        .or(transactions::simulate_bcs_transactions(context.clone()))
+        .or(transactions::simulate_json_transactions(context.clone()))
         .or(transactions::create_signing_message(context.clone()))
+        .or(blocks::get_block_by_height(context.clone()))
+        .or(blocks::get_block_by_version(context.clone()))
+        .or(blocks::get_block_by_hash(context.clone()))
         .or(events::get_events_by_event_key(context.clone()))
         .or(events::get_events_by_event_handle(context.clone()))
         .or(state::get_account_resource(context.clone()))
+        .or(state::get_account_resource_history(context.clone()))
         .or(state::get_account_module(context.clone()))
         .or(state::get_table_item(context.clone()))
+        .or(gas_estimation::estimate_gas_price(context.clone()))
+        .or(view_function::view_function(context.clone()))
+        .or(streaming::stream_transactions(context.clone()))
+        .or(streaming::stream_events_by_event_key(context.clone()))
+        .or(streaming::stream_events_by_event_handle(context.clone()))
         .or(context.health_check_route().with(metrics("health_check")))
         .with(
             warp::cors()
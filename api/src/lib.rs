@@ -2,18 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod accounts;
+mod blocks;
 pub mod context;
 mod events;
+mod gas_estimation;
 mod health_check;
 mod index;
 pub(crate) mod log;
 mod metrics;
 mod page;
 pub mod param;
+mod proof;
 pub mod runtime;
 mod state;
+mod streaming;
 mod transactions;
 pub(crate) mod version;
+mod view_function;
 
 mod failpoint;
 #[cfg(any(test))]
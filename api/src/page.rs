@@ -3,23 +3,58 @@
 
 use crate::param::{Param, TransactionVersionParam};
 
-use aptos_api_types::{Error, TransactionId};
+use aptos_api_types::{Error, HexEncodedBytes, LedgerInfo, Response, TransactionId};
 
 use anyhow::Result;
-use serde::Deserialize;
-use std::num::NonZeroU16;
+use serde::{Deserialize, Serialize};
+use std::{fmt, num::NonZeroU16, str::FromStr};
+use warp::{filters::path::FullPath, Filter, Rejection};
 
 const DEFAULT_PAGE_SIZE: u16 = 25;
 const MAX_PAGE_SIZE: u16 = 1000;
+/// The max number of pages of items scanned for a filtered page, so that a filter matching few
+/// items can't make a request scan the whole ledger.
+const MAX_SCANNED_PAGES: usize = 10;
 
 #[derive(Clone, Debug, Deserialize)]
 pub(crate) struct Page {
     start: Option<TransactionVersionParam>,
     limit: Option<Param<NonZeroU16>>,
+    cursor: Option<Param<Cursor>>,
+    /// The request path and query string, linking to the next page.
+    #[serde(skip)]
+    path: String,
+    #[serde(skip)]
+    query: String,
+}
+
+/// Extracts the [`Page`] from the query string, along with the request path and query string
+/// needed to link to the next page.
+pub(crate) fn page() -> impl Filter<Extract = (Page,), Error = Rejection> + Clone {
+    warp::path::full()
+        .and(warp::query::raw().or(warp::any().map(String::new)).unify())
+        .and(warp::query::<Page>())
+        .map(|path: FullPath, query: String, page: Page| Page {
+            path: path.as_str().to_owned(),
+            query,
+            ..page
+        })
 }
 
 impl Page {
+    /// Returns the position to start the page from: the `cursor` if given, the `start` otherwise.
+    ///
+    /// A cursor may point right after the last item, to poll for the items added since, so it is
+    /// not checked against `max`.
     pub fn start(&self, default: u64, max: u64) -> Result<u64, Error> {
+        if let Some(cursor) = self.cursor.clone() {
+            if self.start.is_some() {
+                return Err(Error::bad_request(
+                    "start and cursor can not be used together",
+                ));
+            }
+            return Ok(cursor.parse("cursor")?.0);
+        }
         let version = self
             .start
             .clone()
@@ -50,4 +85,118 @@ impl Page {
         }
         Ok(limit)
     }
+
+    /// Renders the `items` of the page, with the cursor continuing from the position `next` in
+    /// the `X-Aptos-Cursor` header, and the link to the next page in the `Link` header.
+    pub fn render<T: Serialize>(
+        &self,
+        ledger_info: LedgerInfo,
+        items: &T,
+        next: u64,
+    ) -> Result<Response, Error> {
+        let cursor = Cursor(next).to_string();
+        let link = format!("<{}?{}>; rel=\"next\"", self.path, self.next_query(&cursor));
+        Ok(Response::new(ledger_info, items)?.with_cursor(cursor, link))
+    }
+
+    /// The query string of the current page, with the start replaced by `cursor`.
+    fn next_query(&self, cursor: &str) -> String {
+        let cursor = format!("cursor={}", cursor);
+        self.query
+            .split('&')
+            .filter(|pair| {
+                let name = pair.split('=').next().unwrap_or_default();
+                !name.is_empty() && name != "start" && name != "cursor"
+            })
+            .chain(std::iter::once(cursor.as_str()))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// An opaque position in a paginated listing, e.g. a version or a sequence number, returned to
+/// continue the listing from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct Cursor(u64);
+
+impl FromStr for Cursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bytes: HexEncodedBytes = s.parse()?;
+        Ok(Self(bcs::from_bytes(bytes.inner())?))
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = bcs::to_bytes(&self.0).map_err(|_| fmt::Error)?;
+        write!(f, "{}", HexEncodedBytes::from(bytes))
+    }
+}
+
+/// Scans the items from position `start` with `fetch`, keeping the ones matching `filter` until
+/// `limit` of them are found, there are no more items or `MAX_SCANNED_PAGES` pages are scanned.
+///
+/// Returns the items found, and the position to continue the scan from. Without filter, only one
+/// page is fetched.
+pub(crate) fn scan<T>(
+    start: u64,
+    limit: u16,
+    mut fetch: impl FnMut(u64, u16) -> Result<Vec<T>, Error>,
+    position: impl Fn(&T) -> u64,
+    filter: impl Fn(&T) -> bool,
+) -> Result<(Vec<T>, u64), Error> {
+    let mut items = vec![];
+    let mut next = start;
+    for _ in 0..MAX_SCANNED_PAGES {
+        let batch = fetch(next, limit)?;
+        let fetched = batch.len();
+        for item in batch {
+            next = position(&item) + 1;
+            if filter(&item) {
+                items.push(item);
+                if items.len() == limit as usize {
+                    return Ok((items, next));
+                }
+            }
+        }
+        if fetched < limit as usize {
+            break;
+        }
+    }
+    Ok((items, next))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scan, Cursor};
+    use aptos_api_types::Error;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor(42);
+        assert_eq!(cursor.to_string().parse::<Cursor>().unwrap(), cursor);
+        assert!("0x2a".parse::<Cursor>().is_err());
+    }
+
+    #[test]
+    fn test_scan_with_filter() {
+        let fetch = |start: u64, limit: u16| -> Result<Vec<u64>, Error> {
+            Ok((start..(start + limit as u64).min(100)).collect())
+        };
+        let (items, next) = scan(0, 5, fetch, |i| *i, |i| i % 7 == 0).unwrap();
+        assert_eq!(items, vec![0, 7, 14, 21, 28]);
+        assert_eq!(next, 29);
+
+        // stops at the end of the items
+        let (items, next) = scan(90, 5, fetch, |i| *i, |i| i % 7 == 0).unwrap();
+        assert_eq!(items, vec![91, 98]);
+        assert_eq!(next, 100);
+
+        // stops after scanning the max number of pages
+        let (items, next) = scan(0, 2, fetch, |i| *i, |i| i % 50 == 1).unwrap();
+        assert_eq!(items, vec![1]);
+        assert_eq!(next, 20);
+    }
 }
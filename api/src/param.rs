@@ -1,7 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_api_types::{Address, Error, EventKey, MoveStructTag, TransactionId};
+use aptos_api_types::{Address, Error, EventKey, HashValue, MoveStructTag, TransactionId};
 use move_deps::move_core_types::identifier::Identifier;
 use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Deserializer};
@@ -9,6 +9,8 @@ use serde::{Deserialize, Deserializer};
 use std::{convert::Infallible, str::FromStr};
 
 pub type AddressParam = Param<Address>;
+pub type BlockHashParam = Param<HashValue>;
+pub type BlockHeightParam = Param<u64>;
 pub type EventKeyParam = Param<EventKey>;
 pub type LedgerVersionParam = Param<u64>;
 pub type MoveStructTagParam = Param<MoveStructTag>;
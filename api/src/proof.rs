@@ -0,0 +1,133 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in proofs of the state and transaction reads, requested with `proof=true`, so that clients
+//! can verify what an untrusted node tells them against a trusted state.
+//!
+//! A proof chains the value up to the latest `LedgerInfoWithSignatures`, along with the epoch
+//! changes since the `known_version` of the client (defaulting to the version of the value).
+//! State values are proven up to the same ledger info as the one the response is rendered at, so
+//! that a value read at the latest ledger version is proven at the version of the ledger info.
+
+use crate::{context::Context, param::Param};
+
+use aptos_api_types::{
+    Error, HexEncodedBytes, LedgerInfo, Response, StateValueProof, TransactionProof, ValueWithProof,
+};
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures, state_proof::StateProof,
+    state_store::state_key::StateKey,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ProofQuery {
+    proof: Option<Param<bool>>,
+    known_version: Option<Param<u64>>,
+}
+
+impl ProofQuery {
+    pub fn requested(&self) -> Result<bool, Error> {
+        Ok(self
+            .proof
+            .clone()
+            .map(|v| v.parse("proof"))
+            .transpose()?
+            .unwrap_or(false))
+    }
+
+    /// Returns the version known by the client that the proof of a value at `version` starts
+    /// from, or `None` if no proof was requested.
+    pub fn known_version(self, version: u64) -> Result<Option<u64>, Error> {
+        if !self.requested()? {
+            return Ok(None);
+        }
+        let known_version = self
+            .known_version
+            .map(|v| v.parse("known_version"))
+            .transpose()?
+            .unwrap_or(version);
+        Ok(Some(known_version))
+    }
+}
+
+/// Renders `value`, wrapped in a [`ValueWithProof`] if a proof is given.
+pub(crate) fn render<T: Serialize>(
+    ledger_info: LedgerInfo,
+    value: &T,
+    proof: Option<HexEncodedBytes>,
+) -> Result<Response, Error> {
+    match proof {
+        Some(proof) => Response::new(ledger_info, &ValueWithProof { value, proof }),
+        None => Response::new(ledger_info, value),
+    }
+}
+
+/// Proves the value of `state_key` at `version` up to `ledger_info` with a BCS serialized
+/// [`StateValueProof`].
+pub(crate) fn prove_state_value(
+    context: &Context,
+    state_key: &StateKey,
+    version: u64,
+    known_version: u64,
+    ledger_info: LedgerInfoWithSignatures,
+) -> Result<HexEncodedBytes, Error> {
+    let state_proof = state_proof(context, known_version, ledger_info)?;
+    let transaction_info_with_proof = context
+        .get_transaction_info_with_proof(version, state_proof.latest_ledger_info().version())?;
+    // the state root hash is only committed at the end of a block
+    if transaction_info_with_proof
+        .transaction_info()
+        .state_checkpoint_hash()
+        .is_none()
+    {
+        return Err(Error::bad_request(format!(
+            "state proof is not available at version {}, which is not a state checkpoint",
+            version
+        )));
+    }
+    let (state_value, sparse_merkle_proof) =
+        context.get_state_value_with_proof(state_key, version)?;
+
+    let proof = StateValueProof {
+        state_proof,
+        version,
+        transaction_info_with_proof,
+        state_value,
+        sparse_merkle_proof,
+    };
+    Ok(bcs::to_bytes(&proof).map_err(anyhow::Error::from)?.into())
+}
+
+/// Proves the committed transaction at `version` with a BCS serialized [`TransactionProof`].
+pub(crate) fn prove_transaction(
+    context: &Context,
+    version: u64,
+    known_version: u64,
+) -> Result<HexEncodedBytes, Error> {
+    let state_proof = state_proof(
+        context,
+        known_version,
+        context.get_latest_ledger_info_with_signatures()?,
+    )?;
+    let transaction_with_proof =
+        context.get_transaction_with_proof(version, state_proof.latest_ledger_info().version())?;
+
+    let proof = TransactionProof {
+        state_proof,
+        transaction_with_proof,
+    };
+    Ok(bcs::to_bytes(&proof).map_err(anyhow::Error::from)?.into())
+}
+
+fn state_proof(
+    context: &Context,
+    known_version: u64,
+    ledger_info: LedgerInfoWithSignatures,
+) -> Result<StateProof, Error> {
+    if known_version > ledger_info.ledger_info().version() {
+        return Err(Error::invalid_param("known_version", known_version));
+    }
+    Ok(context.get_state_proof_with_ledger_info(known_version, ledger_info)?)
+}
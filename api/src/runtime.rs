@@ -6,6 +6,8 @@ use crate::{context::Context, index};
 use aptos_config::config::{ApiConfig, NodeConfig};
 use aptos_mempool::MempoolClientSender;
 use aptos_types::chain_id::ChainId;
+use event_notifications::EventNotificationListener;
+use futures::StreamExt;
 use storage_interface::DbReader;
 use warp::{Filter, Reply};
 
@@ -16,12 +18,15 @@ use tokio::runtime::{Builder, Runtime};
 /// When api and json-rpc are configured with same port, both API will be served for the port.
 /// When api and json-rpc are configured with different port, both API will be served for
 /// both ports.
+/// `commit_notifications` should be subscribed to an event emitted by every block (e.g. the new
+/// block event), it drives the transaction and event streams.
 /// Returns corresponding Tokio runtime
 pub fn bootstrap(
     config: &NodeConfig,
     chain_id: ChainId,
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
+    commit_notifications: Option<EventNotificationListener>,
 ) -> anyhow::Result<Runtime> {
     let runtime = Builder::new_multi_thread()
         .thread_name("api")
@@ -34,6 +39,14 @@ pub fn bootstrap(
 
     runtime.spawn(async move {
         let context = Context::new(chain_id, db, mp_sender, api_config);
+        if let Some(mut commit_notifications) = commit_notifications {
+            let context = context.clone();
+            tokio::spawn(async move {
+                while let Some(notification) = commit_notifications.next().await {
+                    context.notify_committed(notification.version);
+                }
+            });
+        }
         let routes = index::routes(context);
         api.serve(routes).await;
     });
@@ -114,6 +127,7 @@ mod tests {
             ChainId::test(),
             context.db.clone(),
             context.mempool.ac_client.clone(),
+            None,
         );
         assert!(ret.is_ok());
 
@@ -5,17 +5,23 @@ use crate::{
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
+    page::{page, scan, Page},
     param::{
         AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam, TableHandleParam,
     },
+    proof::{self, ProofQuery},
     version::Version,
 };
 use anyhow::anyhow;
 use aptos_api_types::{
-    AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response, TableItemRequest, TransactionId,
+    AsConverter, Error, HexEncodedBytes, LedgerInfo, MoveModuleBytecode, ResourceChange,
+    TableItemRequest, TransactionId,
 };
 use aptos_state_view::StateView;
-use aptos_types::{access_path::AccessPath, state_store::state_key::StateKey};
+use aptos_types::{
+    access_path::AccessPath, ledger_info::LedgerInfoWithSignatures,
+    state_store::state_key::StateKey,
+};
 use aptos_vm::data_cache::AsMoveResolver;
 use move_deps::move_core_types::{
     account_address::AccountAddress,
@@ -32,8 +38,9 @@ pub fn get_account_resource(context: Context) -> BoxedFilter<(impl Reply,)> {
         .and(warp::get())
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, struct_tag, ctx, version: Version| {
-            (version.version, address, struct_tag, ctx)
+        .and(warp::query::<ProofQuery>())
+        .map(|address, struct_tag, ctx, version: Version, proof| {
+            (version.version, address, struct_tag, proof, ctx)
         })
         .untuple_one()
         .and_then(handle_get_account_resource)
@@ -41,13 +48,27 @@ pub fn get_account_resource(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// GET /accounts/<address>/resource/<resource_type>/history?start={u64}&limit={u16}&cursor={cursor}
+pub fn get_account_resource_history(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "resource" / MoveStructTagParam / "history")
+        .and(warp::get())
+        .and(page())
+        .and(context.filter())
+        .and_then(handle_get_account_resource_history)
+        .with(metrics("get_account_resource_history"))
+        .boxed()
+}
+
 // GET /state/module/<address>/<module_name>
 pub fn get_account_module(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "module" / MoveIdentifierParam)
         .and(warp::get())
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, name, ctx, version: Version| (version.version, address, name, ctx))
+        .and(warp::query::<ProofQuery>())
+        .map(|address, name, ctx, version: Version, proof| {
+            (version.version, address, name, proof, ctx)
+        })
         .untuple_one()
         .and_then(handle_get_account_module)
         .with(metrics("get_account_module"))
@@ -64,7 +85,10 @@ pub fn get_table_item(context: Context) -> BoxedFilter<(impl Reply,)> {
         .and(warp::body::json::<TableItemRequest>())
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|handle, body, ctx, version: Version| (version.version, handle, body, ctx))
+        .and(warp::query::<ProofQuery>())
+        .map(|handle, body, ctx, version: Version, proof| {
+            (version.version, handle, body, proof, ctx)
+        })
         .untuple_one()
         .and_then(handle_get_table_item)
         .with(metrics("get_table_item"))
@@ -75,6 +99,7 @@ async fn handle_get_account_resource(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
     struct_tag: MoveStructTagParam,
+    proof: ProofQuery,
     context: Context,
 ) -> anyhow::Result<impl Reply, Rejection> {
     fail_point("endpoint_query_resource")?;
@@ -85,6 +110,25 @@ async fn handle_get_account_resource(
             .clone()
             .try_into()
             .map_err(|_| Error::invalid_param("resource_type", struct_tag))?,
+        proof,
+    )?)
+}
+
+async fn handle_get_account_resource_history(
+    address: AddressParam,
+    struct_tag: MoveStructTagParam,
+    page: Page,
+    context: Context,
+) -> anyhow::Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_resource_history")?;
+    let struct_tag = struct_tag.parse("struct tag")?;
+    Ok(State::new(None, context)?.resource_history(
+        address.parse("account address")?.into(),
+        struct_tag
+            .clone()
+            .try_into()
+            .map_err(|_| Error::invalid_param("resource_type", struct_tag))?,
+        page,
     )?)
 }
 
@@ -92,12 +136,14 @@ async fn handle_get_account_module(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
     name: MoveIdentifierParam,
+    proof: ProofQuery,
     context: Context,
 ) -> anyhow::Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_module")?;
     Ok(State::new(ledger_version, context)?.module(
         address.parse("account address")?.into(),
         name.parse("module name")?,
+        proof,
     )?)
 }
 
@@ -105,16 +151,24 @@ async fn handle_get_table_item(
     ledger_version: Option<LedgerVersionParam>,
     handle: TableHandleParam,
     body: TableItemRequest,
+    proof: ProofQuery,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_table_item")?;
-    Ok(State::new(ledger_version, context)?.table_item(handle.parse("table handle")?, body)?)
+    Ok(State::new(ledger_version, context)?.table_item(
+        handle.parse("table handle")?,
+        body,
+        proof,
+    )?)
 }
 
 pub(crate) struct State {
     state_view: DbStateView,
     ledger_version: aptos_types::transaction::Version,
     latest_ledger_info: LedgerInfo,
+    /// The signed ledger info of `latest_ledger_info`, which the state proofs are made up to.
+    latest_ledger_info_with_sigs: LedgerInfoWithSignatures,
+    context: Context,
 }
 
 impl State {
@@ -122,7 +176,9 @@ impl State {
         ledger_version: Option<LedgerVersionParam>,
         context: Context,
     ) -> Result<Self, Error> {
-        let latest_ledger_info = context.get_latest_ledger_info()?;
+        let latest_ledger_info_with_sigs = context.get_latest_ledger_info_with_signatures()?;
+        let latest_ledger_info =
+            LedgerInfo::new(&context.chain_id(), &latest_ledger_info_with_sigs);
         let ledger_version = ledger_version
             .map(|v| v.parse("ledger version"))
             .unwrap_or_else(|| Ok(latest_ledger_info.version()))?;
@@ -141,6 +197,8 @@ impl State {
             state_view,
             ledger_version,
             latest_ledger_info,
+            latest_ledger_info_with_sigs,
+            context,
         })
     }
 
@@ -148,6 +206,7 @@ impl State {
         self,
         address: AccountAddress,
         struct_tag: StructTag,
+        proof: ProofQuery,
     ) -> Result<impl Reply, Error> {
         let resource_key = ResourceKey::new(address, struct_tag.clone());
         let access_path = AccessPath::resource_access_path(resource_key.clone());
@@ -162,10 +221,87 @@ impl State {
             .as_move_resolver()
             .as_converter()
             .try_into_resource(&struct_tag, &bytes)?;
-        Response::new(self.latest_ledger_info, &resource)
+        let proof = self.prove(&state_key, proof)?;
+        proof::render(self.latest_ledger_info, &resource, proof)
+    }
+
+    /// Lists the changes of the resource made by the write sets of the transactions in the page,
+    /// in order of version, each with the resource as it was before.
+    pub fn resource_history(
+        self,
+        address: AccountAddress,
+        struct_tag: StructTag,
+        page: Page,
+    ) -> Result<impl Reply, Error> {
+        let resource_key = ResourceKey::new(address, struct_tag.clone());
+        let access_path = AccessPath::resource_access_path(resource_key);
+        let state_key = StateKey::AccessPath(access_path);
+        let first_version = self
+            .context
+            .get_first_state_key_history_version()?
+            .unwrap_or(0);
+        let start = page.start(first_version, self.ledger_version)?;
+        if start < first_version {
+            return Err(Error::bad_request(format!(
+                "the history before version {} is not available",
+                first_version
+            )));
+        }
+        let (history, next) = scan(
+            start,
+            page.limit()?,
+            |start, limit| {
+                Ok(self.context.get_state_key_history(
+                    &state_key,
+                    start,
+                    limit,
+                    self.ledger_version,
+                )?)
+            },
+            |(version, _)| *version,
+            |_| true,
+        )?;
+
+        // The values are decoded with the modules as of the version they were written at.
+        let changes = history
+            .into_iter()
+            .map(|(version, write_op)| {
+                let state_view = self.context.state_view_at_version(version)?;
+                let change = state_view
+                    .as_move_resolver()
+                    .as_converter()
+                    .try_into_write_set_change(state_key.clone(), write_op)?;
+                let previous_data = match version.checked_sub(1) {
+                    Some(previous_version) => {
+                        let state_view = self.context.state_view_at_version(previous_version)?;
+                        state_view
+                            .get_state_value(&state_key)?
+                            .map(|bytes| {
+                                state_view
+                                    .as_move_resolver()
+                                    .as_converter()
+                                    .try_into_resource(&struct_tag, &bytes)
+                            })
+                            .transpose()?
+                    }
+                    None => None,
+                };
+                Ok(ResourceChange {
+                    version: version.into(),
+                    previous_data,
+                    change,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        page.render(self.latest_ledger_info, &changes, next)
     }
 
-    pub fn module(self, address: AccountAddress, name: Identifier) -> Result<impl Reply, Error> {
+    pub fn module(
+        self,
+        address: AccountAddress,
+        name: Identifier,
+        proof: ProofQuery,
+    ) -> Result<impl Reply, Error> {
         let module_id = ModuleId::new(address, name);
         let access_path = AccessPath::code_access_path(module_id.clone());
         let state_key = StateKey::AccessPath(access_path);
@@ -177,10 +313,16 @@ impl State {
         let module = MoveModuleBytecode::new(bytes)
             .try_parse_abi()
             .map_err(Error::internal)?;
-        Response::new(self.latest_ledger_info, &module)
+        let proof = self.prove(&state_key, proof)?;
+        proof::render(self.latest_ledger_info, &module, proof)
     }
 
-    pub fn table_item(self, handle: u128, body: TableItemRequest) -> Result<impl Reply, Error> {
+    pub fn table_item(
+        self,
+        handle: u128,
+        body: TableItemRequest,
+        proof: ProofQuery,
+    ) -> Result<impl Reply, Error> {
         let TableItemRequest {
             key_type,
             value_type,
@@ -207,6 +349,26 @@ impl State {
             .ok_or_else(|| Error::not_found("table handle or item", key, self.ledger_version))?;
 
         let move_value = converter.try_into_move_value(&value_type, &bytes)?;
-        Response::new(self.latest_ledger_info, &move_value)
+        let proof = self.prove(&state_key, proof)?;
+        proof::render(self.latest_ledger_info, &move_value, proof)
+    }
+
+    fn prove(
+        &self,
+        state_key: &StateKey,
+        proof: ProofQuery,
+    ) -> Result<Option<HexEncodedBytes>, Error> {
+        proof
+            .known_version(self.ledger_version)?
+            .map(|known_version| {
+                proof::prove_state_value(
+                    &self.context,
+                    state_key,
+                    self.ledger_version,
+                    known_version,
+                    self.latest_ledger_info_with_sigs.clone(),
+                )
+            })
+            .transpose()
     }
 }
@@ -0,0 +1,256 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Server-Sent Events streams of committed transactions and events.
+//!
+//! A stream first catches up from the requested start position by reading the DB, then waits for
+//! the context to be notified of new commits (see `Context::notify_committed`) before reading the
+//! DB again, so idle streams do not poll.
+
+use crate::{
+    accounts::Account,
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam, Param},
+};
+
+use aptos_api_types::{AsConverter, Error, Event, Transaction};
+use aptos_types::event::EventKey;
+
+use anyhow::Result;
+use futures::{
+    future,
+    stream::{self, Stream, StreamExt, TryStreamExt},
+};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use warp::{filters::BoxedFilter, sse, Filter, Rejection, Reply};
+
+/// Max number of transactions or events loaded from the DB at a time for a stream.
+const STREAM_BATCH_SIZE: u16 = 100;
+
+#[derive(Clone, Debug, Deserialize)]
+struct StreamQuery {
+    start: Option<Param<u64>>,
+}
+
+impl StreamQuery {
+    fn start(self) -> Result<Option<u64>, Error> {
+        self.start.map(|v| v.parse("start")).transpose()
+    }
+}
+
+// GET /stream/transactions?start={version}
+pub fn stream_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("stream" / "transactions")
+        .and(warp::get())
+        .and(warp::query::<StreamQuery>())
+        .and(context.filter())
+        .and_then(handle_stream_transactions)
+        .with(metrics("stream_transactions"))
+        .boxed()
+}
+
+// GET /stream/events/{event_key}?start={sequence_number}
+pub fn stream_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("stream" / "events" / EventKeyParam)
+        .and(warp::get())
+        .and(warp::query::<StreamQuery>())
+        .and(context.filter())
+        .and_then(handle_stream_events_by_event_key)
+        .with(metrics("stream_events_by_event_key"))
+        .boxed()
+}
+
+// GET /stream/accounts/{address}/events/{event_handle_struct}/{field_name}?start={sequence_number}
+pub fn stream_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!(
+        "stream" / "accounts" / AddressParam / "events" / MoveStructTagParam / MoveIdentifierParam
+    )
+    .and(warp::get())
+    .and(warp::query::<StreamQuery>())
+    .and(context.filter())
+    .and_then(handle_stream_events_by_event_handle)
+    .with(metrics("stream_events_by_event_handle"))
+    .boxed()
+}
+
+async fn handle_stream_transactions(
+    query: StreamQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_transactions")?;
+    let start_version = match query.start()? {
+        Some(start_version) => start_version,
+        None => context.get_latest_ledger_info()?.version() + 1,
+    };
+    Ok(sse_reply(
+        "transaction",
+        transaction_stream(context, start_version),
+    ))
+}
+
+async fn handle_stream_events_by_event_key(
+    event_key: EventKeyParam,
+    query: StreamQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_events_by_event_key")?;
+    let key = event_key.parse("event key")?.into();
+    Ok(new_event_stream(key, query, context)?)
+}
+
+async fn handle_stream_events_by_event_handle(
+    address: AddressParam,
+    struct_tag: MoveStructTagParam,
+    field_name: MoveIdentifierParam,
+    query: StreamQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_events_by_event_handle")?;
+    let key =
+        Account::new(None, address, context.clone())?.find_event_key(struct_tag, field_name)?;
+    Ok(new_event_stream(key, query, context)?)
+}
+
+fn new_event_stream(
+    key: EventKey,
+    query: StreamQuery,
+    context: Context,
+) -> Result<impl Reply, Error> {
+    let start_sequence_number = match query.start()? {
+        Some(start_sequence_number) => start_sequence_number,
+        None => context.get_next_event_sequence_number(&key)?,
+    };
+    Ok(sse_reply(
+        "event",
+        event_stream(context, key, start_sequence_number),
+    ))
+}
+
+/// Renders each item as an SSE event named `name`. If the stream fails, an `error` event is sent
+/// and the stream ends.
+fn sse_reply<T: Serialize>(
+    name: &'static str,
+    items: impl Stream<Item = Result<T, Error>> + Send + 'static,
+) -> impl Reply {
+    let events = items.scan(false, |failed, item| {
+        if *failed {
+            return future::ready(None);
+        }
+        let event = match item.and_then(|item| to_sse_event(name, &item)) {
+            Ok(event) => event,
+            Err(err) => {
+                *failed = true;
+                to_sse_event("error", &err).unwrap_or_else(|_| sse::Event::default())
+            }
+        };
+        future::ready(Some(Ok::<_, Infallible>(event)))
+    });
+    sse::reply(sse::keep_alive().stream(events))
+}
+
+fn to_sse_event<T: Serialize>(name: &str, item: &T) -> Result<sse::Event, Error> {
+    let data = serde_json::to_string(item)?;
+    Ok(sse::Event::default().event(name).data(data))
+}
+
+/// Streams the committed transactions starting from `start_version`, waiting for new commits once
+/// it has caught up with the ledger.
+pub(crate) fn transaction_stream(
+    context: Context,
+    start_version: u64,
+) -> impl Stream<Item = Result<Transaction, Error>> {
+    let committed = context.committed_version_receiver();
+    stream::try_unfold(
+        (context, committed, start_version),
+        |(context, mut committed, next_version)| async move {
+            loop {
+                let txns = next_transactions(&context, next_version)?;
+                if !txns.is_empty() {
+                    let next_version = next_version + txns.len() as u64;
+                    let txns = stream::iter(txns.into_iter().map(Ok));
+                    return Ok::<_, Error>(Some((txns, (context, committed, next_version))));
+                }
+                if committed.changed().await.is_err() {
+                    return Ok(None);
+                }
+            }
+        },
+    )
+    .try_flatten()
+}
+
+/// Streams the events emitted under `key` starting from `start_sequence_number`, waiting for new
+/// commits once it has caught up with the ledger.
+pub(crate) fn event_stream(
+    context: Context,
+    key: EventKey,
+    start_sequence_number: u64,
+) -> impl Stream<Item = Result<Event, Error>> {
+    let committed = context.committed_version_receiver();
+    stream::try_unfold(
+        (context, committed, start_sequence_number),
+        move |(context, mut committed, next_sequence_number)| async move {
+            loop {
+                let events = next_events(&context, &key, next_sequence_number)?;
+                if !events.is_empty() {
+                    let next_sequence_number = next_sequence_number + events.len() as u64;
+                    let events = stream::iter(events.into_iter().map(Ok));
+                    return Ok::<_, Error>(Some((
+                        events,
+                        (context, committed, next_sequence_number),
+                    )));
+                }
+                if committed.changed().await.is_err() {
+                    return Ok(None);
+                }
+            }
+        },
+    )
+    .try_flatten()
+}
+
+fn next_transactions(context: &Context, start_version: u64) -> Result<Vec<Transaction>, Error> {
+    let ledger_version = context.get_latest_ledger_info()?.version();
+    if start_version > ledger_version {
+        return Ok(vec![]);
+    }
+    let limit = (ledger_version - start_version + 1).min(STREAM_BATCH_SIZE as u64) as u16;
+    let data = context.get_transactions(start_version, limit, ledger_version)?;
+
+    let mut timestamp = context.get_block_timestamp(start_version)?;
+    let resolver = context.move_resolver()?;
+    let converter = resolver.as_converter();
+    let txns = data
+        .into_iter()
+        .map(|t| {
+            let txn = converter.try_into_onchain_transaction(timestamp, t)?;
+            // update timestamp, when txn is metadata block transaction
+            // new timestamp is used for the following transactions
+            timestamp = txn.timestamp();
+            Ok(txn)
+        })
+        .collect::<Result<_>>()?;
+    Ok(txns)
+}
+
+fn next_events(
+    context: &Context,
+    key: &EventKey,
+    start_sequence_number: u64,
+) -> Result<Vec<Event>, Error> {
+    let ledger_version = context.get_latest_ledger_info()?.version();
+    let contract_events = context.get_events(
+        key,
+        start_sequence_number,
+        STREAM_BATCH_SIZE,
+        ledger_version,
+    )?;
+    if contract_events.is_empty() {
+        return Ok(vec![]);
+    }
+    let resolver = context.move_resolver()?;
+    Ok(resolver.as_converter().try_into_events(&contract_events)?)
+}
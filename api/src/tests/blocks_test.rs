@@ -0,0 +1,166 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+use aptos_crypto::HashValue;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_get_genesis_block() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let resp = context.get("/blocks/by_height/0").await;
+    assert_eq!(resp["block_height"], "0");
+    assert_eq!(resp["first_version"], "0");
+    assert_eq!(resp["last_version"], ledger_version.to_string());
+    assert_eq!(resp["block_timestamp"], "0");
+    assert!(resp.get("transactions").is_none());
+}
+
+#[tokio::test]
+async fn test_get_block_by_height_and_version() {
+    let mut context = new_test_context(current_function_name!());
+    let genesis_version = context.get_latest_ledger_info().version();
+
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    // block metadata, user transaction and state checkpoint
+    let first_version = genesis_version + 1;
+    let last_version = genesis_version + 3;
+    let resp = context
+        .get("/blocks/by_height/1?with_transactions=true")
+        .await;
+    assert_eq!(resp["block_height"], "1");
+    assert_eq!(resp["first_version"], first_version.to_string());
+    assert_eq!(resp["last_version"], last_version.to_string());
+    assert_eq!(resp["proposer"], context.validator_owner.to_hex_literal());
+
+    let txns = resp["transactions"].as_array().unwrap();
+    assert_eq!(txns.len(), 3);
+    assert_eq!(txns[0]["type"], "block_metadata_transaction");
+    assert_eq!(txns[0]["id"], resp["block_hash"]);
+    assert_eq!(txns[0]["timestamp"], resp["block_timestamp"]);
+    assert_eq!(txns[1]["hash"], txn.committed_hash().to_hex_literal());
+
+    for version in first_version..=last_version {
+        let block = context
+            .get(&format!(
+                "/blocks/by_version/{}?with_transactions=true",
+                version
+            ))
+            .await;
+        assert_eq!(block, resp);
+    }
+    let genesis_block = context
+        .get(&format!("/blocks/by_version/{}", genesis_version))
+        .await;
+    assert_eq!(genesis_block["block_height"], "0");
+}
+
+#[tokio::test]
+async fn test_get_block_by_version_in_middle_of_block() {
+    let mut context = new_test_context(current_function_name!());
+    let genesis_version = context.get_latest_ledger_info().version();
+
+    let mut root_account = context.root_account();
+    let txns: Vec<_> = (0..3)
+        .map(|_| {
+            let account = context.gen_account();
+            context.create_user_account_by(&mut root_account, &account)
+        })
+        .collect();
+    context.commit_block(&txns).await;
+
+    // block metadata, 3 user transactions and state checkpoint
+    let first_version = genesis_version + 1;
+    let middle_version = genesis_version + 3;
+    let resp = context
+        .get(&format!("/blocks/by_version/{}", middle_version))
+        .await;
+    assert_eq!(resp["block_height"], "1");
+    assert_eq!(resp["first_version"], first_version.to_string());
+    assert_eq!(resp["last_version"], (genesis_version + 5).to_string());
+}
+
+#[tokio::test]
+async fn test_get_block_by_hash() {
+    let mut context = new_test_context(current_function_name!());
+
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    let block = context
+        .get("/blocks/by_height/1?with_transactions=true")
+        .await;
+    let resp = context
+        .get(&format!(
+            "/blocks/by_hash/{}?with_transactions=true",
+            block["block_hash"].as_str().unwrap()
+        ))
+        .await;
+    assert_eq!(resp, block);
+
+    let genesis_block = context.get("/blocks/by_height/0").await;
+    let resp = context
+        .get(&format!(
+            "/blocks/by_hash/{}",
+            genesis_block["block_hash"].as_str().unwrap()
+        ))
+        .await;
+    assert_eq!(resp, genesis_block);
+
+    context
+        .expect_status_code(404)
+        .get(&format!(
+            "/blocks/by_hash/{}",
+            HashValue::random().to_hex_literal()
+        ))
+        .await;
+    context
+        .expect_status_code(400)
+        .get("/blocks/by_hash/abc")
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_block_not_found() {
+    let context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let resp = context
+        .expect_status_code(404)
+        .get("/blocks/by_height/1")
+        .await;
+    assert_eq!(
+        resp,
+        json!({
+            "code": 404,
+            "message": "block not found by 1",
+            "aptos_ledger_version": ledger_version.to_string(),
+        })
+    );
+
+    context
+        .expect_status_code(404)
+        .get(&format!("/blocks/by_version/{}", ledger_version + 1))
+        .await;
+}
+
+#[tokio::test]
+async fn test_get_block_by_invalid_height() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/blocks/by_height/abc")
+        .await;
+    context
+        .expect_status_code(400)
+        .get("/blocks/by_height/0?with_transactions=yes")
+        .await;
+}
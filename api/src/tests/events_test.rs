@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{current_function_name, tests::new_test_context};
+use aptos_api_types::X_APTOS_CURSOR;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 
 static EVENT_KEY: &str =
@@ -43,6 +44,48 @@ async fn test_get_events_filter_by_limit_page_size() {
     assert_eq!(resp.as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_get_events_filter_by_event_type() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .get(
+            format!(
+                "/events/{}?event_type=0x1::Reconfiguration::NewEpochEvent",
+                EVENT_KEY
+            )
+            .as_str(),
+        )
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), 1);
+    assert_eq!(resp[0]["type"], "0x1::Reconfiguration::NewEpochEvent");
+
+    let resp = context
+        .get(format!("/events/{}?event_type=u64", EVENT_KEY).as_str())
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_get_events_with_cursor() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(format!("/events/{}", EVENT_KEY).as_str()),
+        )
+        .await;
+    let cursor = resp.headers()[X_APTOS_CURSOR].to_str().unwrap();
+    assert_eq!(cursor, "0x0100000000000000");
+
+    let resp = context
+        .get(format!("/events/{}?cursor={}", EVENT_KEY, cursor).as_str())
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), 0);
+}
+
 #[tokio::test]
 async fn test_get_events_by_invalid_key() {
     let mut context = new_test_context(current_function_name!());
@@ -0,0 +1,51 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_estimate_gas_price_without_user_transactions() {
+    let context = new_test_context(current_function_name!());
+
+    // the test genesis sets the minimum gas unit price to 0
+    let resp = context.get("/estimate_gas_price").await;
+    assert_eq!(resp, json!({"low": "0", "median": "0", "high": "0"}));
+}
+
+#[tokio::test]
+async fn test_estimate_gas_price_from_committed_and_mempool_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+
+    let txns: Vec<_> = (1..=10)
+        .map(|gas_unit_price| {
+            let account = context.gen_account();
+            root_account.sign_with_transaction_builder(
+                context
+                    .transaction_factory()
+                    .with_gas_unit_price(gas_unit_price)
+                    .create_user_account(account.public_key()),
+            )
+        })
+        .collect();
+    context.commit_block(&txns).await;
+
+    let resp = context.get("/estimate_gas_price").await;
+    assert_eq!(resp, json!({"low": "1", "median": "5", "high": "9"}));
+
+    let account = context.gen_account();
+    let pending_txn = root_account.sign_with_transaction_builder(
+        context
+            .transaction_factory()
+            .with_gas_unit_price(100)
+            .create_user_account(account.public_key()),
+    );
+    context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&pending_txn).unwrap())
+        .await;
+
+    let resp = context.get("/estimate_gas_price").await;
+    assert_eq!(resp, json!({"low": "2", "median": "6", "high": "10"}));
+}
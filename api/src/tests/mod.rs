@@ -2,18 +2,23 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod accounts_test;
+mod blocks_test;
 mod converter_test;
 mod events_test;
+mod gas_estimation_test;
 mod golden_output;
 mod index_test;
 mod invalid_post_request_test;
+mod proof_test;
 mod state_test;
+mod streaming_test;
 mod string_resource_test;
 mod test_context;
 mod transactions_test;
+mod view_function_test;
 
 use serde_json::Value;
-pub use test_context::{new_test_context, TestContext};
+pub use test_context::{new_test_context, new_test_context_with_api_config, TestContext};
 
 pub fn find_value(val: &Value, filter: for<'r> fn(&'r &Value) -> bool) -> Value {
     let resources = val
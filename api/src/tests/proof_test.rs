@@ -0,0 +1,184 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+use aptos_api_types::{StateValueProof, TransactionProof, ValueWithProof};
+use aptos_types::{
+    access_path::AccessPath,
+    account_config::{aptos_root_address, AccountResource},
+    state_store::state_key::StateKey,
+    trusted_state::TrustedState,
+};
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType};
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_get_account_resource_with_proof() {
+    let context = new_test_context(current_function_name!());
+    let resource = context
+        .get(&format!(
+            "/accounts/{}/resource/0x1::Account::Account",
+            aptos_root_address().to_hex_literal()
+        ))
+        .await;
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/resource/0x1::Account::Account?proof=true&known_version=0",
+            aptos_root_address().to_hex_literal()
+        ))
+        .await;
+
+    let resp: ValueWithProof<Value> = serde_json::from_value(resp).unwrap();
+    assert_eq!(resp.value, resource);
+    let proof: StateValueProof = bcs::from_bytes(resp.proof.inner()).unwrap();
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        aptos_root_address(),
+        AccountResource::struct_tag(),
+    )));
+    let trusted_state = TrustedState::from_epoch_waypoint(context.genesis_waypoint);
+    let ledger_version = context.get_latest_ledger_info().version();
+    let new_state = proof
+        .verify(&trusted_state, &state_key, ledger_version)
+        .unwrap();
+    assert_eq!(new_state.version(), ledger_version);
+
+    let account_resource: AccountResource =
+        bcs::from_bytes(proof.state_value.unwrap().maybe_bytes.as_ref().unwrap()).unwrap();
+    assert_eq!(
+        resp.value["data"]["sequence_number"],
+        account_resource.sequence_number().to_string()
+    );
+}
+
+#[tokio::test]
+async fn test_get_account_with_proof() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .get(&format!(
+            "/accounts/{}?proof=true&known_version=0",
+            aptos_root_address().to_hex_literal()
+        ))
+        .await;
+
+    let resp: ValueWithProof<Value> = serde_json::from_value(resp).unwrap();
+    let proof: StateValueProof = bcs::from_bytes(resp.proof.inner()).unwrap();
+    let trusted_state = TrustedState::from_epoch_waypoint(context.genesis_waypoint);
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        aptos_root_address(),
+        AccountResource::struct_tag(),
+    )));
+    let ledger_version = context.get_latest_ledger_info().version();
+    assert!(proof
+        .verify(&trusted_state, &state_key, ledger_version)
+        .is_ok());
+
+    let other_state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        context.validator_owner,
+        AccountResource::struct_tag(),
+    )));
+    assert!(proof
+        .verify(&trusted_state, &other_state_key, ledger_version)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_account_with_stale_proof() {
+    let mut context = new_test_context(current_function_name!());
+    let stale_version = context.get_latest_ledger_info().version();
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    // a valid proof of the value at the previous state checkpoint, up to the latest ledger info
+    let resp = context
+        .get(&format!(
+            "/accounts/{}?version={}&proof=true&known_version=0",
+            aptos_root_address().to_hex_literal(),
+            stale_version
+        ))
+        .await;
+
+    let resp: ValueWithProof<Value> = serde_json::from_value(resp).unwrap();
+    let proof: StateValueProof = bcs::from_bytes(resp.proof.inner()).unwrap();
+    assert_eq!(proof.version, stale_version);
+    assert_eq!(
+        proof.state_proof.latest_ledger_info().version(),
+        ledger_version
+    );
+    let trusted_state = TrustedState::from_epoch_waypoint(context.genesis_waypoint);
+    let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        aptos_root_address(),
+        AccountResource::struct_tag(),
+    )));
+    assert!(proof
+        .verify(&trusted_state, &state_key, ledger_version)
+        .is_err());
+    assert!(proof
+        .verify(&trusted_state, &state_key, stale_version)
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_get_transaction_with_proof() {
+    let context = new_test_context(current_function_name!());
+    let txn = context.get("/transactions/0").await;
+    let resp = context
+        .get("/transactions/0?proof=true&known_version=0")
+        .await;
+
+    let resp: ValueWithProof<Value> = serde_json::from_value(resp).unwrap();
+    assert_eq!(resp.value, txn);
+    let proof: TransactionProof = bcs::from_bytes(resp.proof.inner()).unwrap();
+    let trusted_state = TrustedState::from_epoch_waypoint(context.genesis_waypoint);
+    assert!(proof.verify(&trusted_state, 0).is_ok());
+    assert!(proof.verify(&trusted_state, 1).is_err());
+}
+
+#[tokio::test]
+async fn test_get_account_resource_with_proof_at_non_checkpoint_version() {
+    let mut context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    // the block metadata transaction is not a state checkpoint
+    let version = ledger_version + 1;
+    let resp = context
+        .expect_status_code(400)
+        .get(&format!(
+            "/accounts/{}/resource/0x1::Account::Account?version={}&proof=true",
+            aptos_root_address().to_hex_literal(),
+            version
+        ))
+        .await;
+    assert_eq!(
+        resp,
+        json!({
+            "code": 400,
+            "message": format!(
+                "state proof is not available at version {}, which is not a state checkpoint",
+                version
+            )
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_get_transaction_with_proof_from_invalid_known_version() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions/0?proof=true&known_version=1000000")
+        .await;
+    assert_eq!(
+        resp,
+        json!({
+            "code": 400,
+            "message": "invalid parameter known_version: 1000000"
+        })
+    );
+}
@@ -176,10 +176,86 @@ async fn test_get_table_item() {
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
 }
 
+#[tokio::test]
+async fn test_get_account_resource_history() {
+    let mut context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    // block metadata, then the user transaction creating the account
+    let address = account.address().to_hex_literal();
+    let resp = context
+        .get(&get_account_resource_history(
+            &address,
+            "0x1::Account::Account",
+        ))
+        .await;
+    let changes = resp.as_array().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0]["version"], (ledger_version + 2).to_string());
+    assert_eq!(changes[0]["type"], "write_resource");
+    assert_eq!(changes[0]["address"], address);
+    assert_eq!(changes[0]["data"]["type"], "0x1::Account::Account");
+    assert_eq!(changes[0]["data"]["data"]["sequence_number"], "0");
+    assert_eq!(changes[0]["previous_data"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_get_account_resource_history_paging() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    for _ in 0..2 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&vec![txn]).await;
+    }
+
+    let history = get_account_resource_history("0xA550C18", "0x1::Account::Account");
+    let resp = context.get(&history).await;
+    let versions: Vec<u64> = resp
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| change["version"].as_str().unwrap().parse().unwrap())
+        .collect();
+    // the genesis transaction, then the two transactions sent by the root account
+    assert_eq!(versions.len(), 3);
+    assert_eq!(versions[0], 0);
+
+    let resp = context
+        .get(&format!("{}?start={}&limit=1", history, versions[0] + 1))
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), 1);
+    assert_eq!(resp[0]["version"], versions[1].to_string());
+    assert_eq!(resp[0]["data"]["data"]["sequence_number"], "1");
+    assert_eq!(resp[0]["previous_data"]["type"], "0x1::Account::Account");
+    assert_eq!(resp[0]["previous_data"]["data"]["sequence_number"], "0");
+}
+
+#[tokio::test]
+async fn test_get_account_resource_history_not_changed() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .get(&get_account_resource_history(
+            "0xA550C19",
+            "0x1::Account::Account",
+        ))
+        .await;
+    assert_eq!(resp, json!([]));
+}
+
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
     format!("/accounts/{}/resource/{}", address, struct_tag)
 }
 
+fn get_account_resource_history(address: &str, struct_tag: &str) -> String {
+    format!("/accounts/{}/resource/{}/history", address, struct_tag)
+}
+
 fn get_account_module(address: &str, name: &str) -> String {
     format!("/accounts/{}/module/{}", address, name)
 }
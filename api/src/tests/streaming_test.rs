@@ -0,0 +1,103 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    current_function_name,
+    streaming::{event_stream, transaction_stream},
+    tests::new_test_context,
+};
+use aptos_types::block_metadata::new_block_event_key;
+use futures::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+
+const STREAM_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[tokio::test]
+async fn test_transaction_stream_catches_up_then_waits_for_commits() {
+    let mut context = new_test_context(current_function_name!());
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let mut stream = Box::pin(transaction_stream(context.context.clone(), 0));
+    for version in 0..=ledger_version {
+        let txn = stream.next().await.unwrap().unwrap();
+        assert_eq!(txn.version(), Some(version));
+    }
+    // caught up with the ledger, nothing more until the next commit
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), stream.next())
+            .await
+            .is_err()
+    );
+
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    // block metadata, user transaction and state checkpoint
+    for version in ledger_version + 1..=ledger_version + 3 {
+        let txn = tokio::time::timeout(STREAM_TIMEOUT, stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(txn.version(), Some(version));
+    }
+}
+
+#[tokio::test]
+async fn test_event_stream_from_next_sequence_number() {
+    let mut context = new_test_context(current_function_name!());
+    let key = new_block_event_key();
+    let start = context
+        .context
+        .get_next_event_sequence_number(&key)
+        .unwrap();
+
+    let mut stream = Box::pin(event_stream(context.context.clone(), key, start));
+    context.commit_block(&[]).await;
+    context.commit_block(&[]).await;
+
+    for sequence_number in start..start + 2 {
+        let event = tokio::time::timeout(STREAM_TIMEOUT, stream.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.key, key.into());
+        assert_eq!(event.sequence_number.0, sequence_number);
+    }
+}
+
+#[tokio::test]
+async fn test_stream_transactions_with_invalid_start() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/stream/transactions?start=abc")
+        .await;
+    assert_eq!(
+        resp,
+        json!({
+            "code": 400,
+            "message": "invalid parameter start: abc"
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_stream_events_with_invalid_event_key() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/stream/events/invalid")
+        .await;
+    assert_eq!(
+        resp,
+        json!({
+            "code": 400,
+            "message": "invalid parameter event key: invalid"
+        })
+    );
+}
@@ -27,6 +27,7 @@ use aptos_types::{
     chain_id::ChainId,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     transaction::{Transaction, TransactionStatus},
+    waypoint::Waypoint,
 };
 use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
@@ -47,6 +48,13 @@ use vm_validator::vm_validator::VMValidator;
 use warp::http::header::CONTENT_TYPE;
 
 pub fn new_test_context(test_name: &'static str) -> TestContext {
+    new_test_context_with_api_config(test_name, ApiConfig::default())
+}
+
+pub fn new_test_context_with_api_config(
+    test_name: &'static str,
+    api_config: ApiConfig,
+) -> TestContext {
     let tmp_dir = TempPath::new();
     tmp_dir.create_as_dir().unwrap();
 
@@ -71,11 +79,12 @@ pub fn new_test_context(test_name: &'static str) -> TestContext {
             ChainId::test(),
             db.clone(),
             mempool.ac_client.clone(),
-            ApiConfig::default(),
+            api_config,
         ),
         rng,
         root_keys,
         validator_owner,
+        genesis_waypoint,
         Box::new(BlockExecutor::<AptosVM>::new(db_rw)),
         mempool,
         db,
@@ -87,6 +96,7 @@ pub fn new_test_context(test_name: &'static str) -> TestContext {
 pub struct TestContext {
     pub context: Context,
     pub validator_owner: AccountAddress,
+    pub genesis_waypoint: Waypoint,
     pub mempool: Arc<MockSharedMempool>,
     pub db: Arc<AptosDB>,
     rng: rand::rngs::StdRng,
@@ -104,6 +114,7 @@ impl TestContext {
         rng: rand::rngs::StdRng,
         root_keys: RootKeys,
         validator_owner: AccountAddress,
+        genesis_waypoint: Waypoint,
         executor: Box<dyn BlockExecutorTrait>,
         mempool: MockSharedMempool,
         db: Arc<AptosDB>,
@@ -114,6 +125,7 @@ impl TestContext {
             rng,
             root_keys: Arc::new(root_keys),
             validator_owner,
+            genesis_waypoint,
             executor: executor.into(),
             mempool: Arc::new(mempool),
             expect_status_code: 200,
@@ -254,6 +266,8 @@ impl TestContext {
                 self.new_ledger_info(&metadata, result.root_hash(), txns.len()),
             )
             .unwrap();
+        self.context
+            .notify_committed(self.get_latest_ledger_info().version());
 
         self.mempool
             .mempool_notifier
@@ -6,10 +6,10 @@ use crate::{
     tests::{assert_json, new_test_context, pretty, TestContext},
 };
 
-use aptos_api_types::HexEncodedBytes;
+use aptos_api_types::{AsConverter, HexEncodedBytes, X_APTOS_CURSOR};
 use aptos_crypto::{
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
-    PrivateKey, SigningKey, Uniform,
+    HashValue, PrivateKey, SigningKey, Uniform,
 };
 use aptos_sdk::types::LocalAccount;
 use aptos_types::{
@@ -23,7 +23,7 @@ use aptos_types::{
     write_set::{WriteOp, WriteSetMut},
 };
 
-use aptos_crypto::ed25519::Ed25519PrivateKey;
+use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519Signature};
 use aptos_types::state_store::state_key::StateKey;
 use move_deps::move_core_types::{
     identifier::Identifier,
@@ -31,6 +31,8 @@ use move_deps::move_core_types::{
 };
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde_json::json;
+use std::convert::TryFrom;
+use warp::http::header::LINK;
 
 #[tokio::test]
 async fn test_deserialize_genesis_transaction() {
@@ -283,6 +285,210 @@ async fn test_post_transaction_rejected_by_mempool() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_post_bcs_format_transaction_batch() {
+    let mut context = new_test_context(current_function_name!());
+    let accounts: Vec<_> = (0..2).map(|_| context.gen_account()).collect();
+    let mut root_account = context.root_account();
+    let txns: Vec<SignedTransaction> = accounts
+        .iter()
+        .map(|account| context.create_user_account_by(&mut root_account, account))
+        .collect();
+
+    let resp = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions/batch", bcs::to_bytes(&txns).unwrap())
+        .await;
+    let expected: Vec<_> = txns
+        .into_iter()
+        .map(|txn| {
+            json!({
+                "hash": txn.committed_hash().to_hex_literal(),
+                "mempool_status": "Accepted",
+            })
+        })
+        .collect();
+    assert_eq!(resp, json!(expected));
+}
+
+#[tokio::test]
+async fn test_post_json_format_transaction_batch_with_rejected_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account1 = context.gen_account();
+    let account2 = context.gen_account();
+    let txn1 = context.create_user_account(&account1);
+    let txn2 = context.create_user_account(&account2);
+    let resolver = context.context.move_resolver().unwrap();
+    let body: Vec<_> = vec![txn1.clone(), txn2.clone()]
+        .into_iter()
+        .map(|txn| {
+            let pending_txn = resolver
+                .as_converter()
+                .try_into_pending_transaction(txn)
+                .unwrap();
+            serde_json::to_value(pending_txn).unwrap()
+        })
+        .collect();
+
+    let resp = context
+        .expect_status_code(202)
+        .post("/transactions/batch", json!(body))
+        .await;
+    assert_eq!(
+        resp,
+        json!([
+            {
+                "hash": txn1.committed_hash().to_hex_literal(),
+                "mempool_status": "Accepted",
+            },
+            {
+                "hash": txn2.committed_hash().to_hex_literal(),
+                "mempool_status": "InvalidUpdate",
+                "message": "Transaction already in mempool",
+            },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_post_transaction_batch_exceeding_max_batch_size() {
+    let mut context = new_test_context(current_function_name!());
+    let max_batch_size = context.context.max_submit_transaction_batch_size();
+    let accounts: Vec<_> = (0..=max_batch_size)
+        .map(|_| context.gen_account())
+        .collect();
+    let mut root_account = context.root_account();
+    let txns: Vec<SignedTransaction> = accounts
+        .iter()
+        .map(|account| context.create_user_account_by(&mut root_account, account))
+        .collect();
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions/batch", bcs::to_bytes(&txns).unwrap())
+        .await;
+    assert_eq!(
+        resp,
+        json!({
+            "code": 400,
+            "message": format!(
+                "too many transactions in the batch: {}, the max is {}",
+                max_batch_size + 1,
+                max_batch_size
+            ),
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_simulate_transaction_with_zero_signature() {
+    let context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let root_account = context.root_account();
+    let txn = context.create_user_account(&account);
+    let unsigned_txn = SignedTransaction::new(
+        txn.into_raw_transaction(),
+        root_account.public_key().clone(),
+        Ed25519Signature::try_from(&[0u8; 64][..]).unwrap(),
+    );
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let resp = context
+        .post_bcs_txn(
+            "/transactions/simulate",
+            bcs::to_bytes(&unsigned_txn).unwrap(),
+        )
+        .await;
+    assert_eq!(resp["type"], "user_transaction");
+    assert_eq!(resp["success"], true, "{}", pretty(&resp));
+    assert_eq!(resp["vm_status"], "Executed successfully");
+    assert_eq!(resp["version"], (ledger_version + 1).to_string());
+    assert_eq!(
+        resp["accumulator_root_hash"],
+        HashValue::zero().to_hex_literal()
+    );
+    assert!(!resp["changes"].as_array().unwrap().is_empty());
+
+    // nothing is committed or submitted to mempool
+    assert_eq!(context.get_latest_ledger_info().version(), ledger_version);
+    assert!(context.mempool.get_txns(1).is_empty());
+    context
+        .expect_status_code(404)
+        .get(&format!("/accounts/{}", account.address()))
+        .await;
+}
+
+#[tokio::test]
+async fn test_simulate_json_transaction_with_zero_signature() {
+    let context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let sender = context.root_account();
+    let request = json!({
+        "sender": sender.address().to_hex_literal(),
+        "sequence_number": sender.sequence_number().to_string(),
+        "gas_unit_price": txn.gas_unit_price().to_string(),
+        "max_gas_amount": txn.max_gas_amount().to_string(),
+        "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+        "payload": {
+            "type": "script_function_payload",
+            "function": "0x1::Account::create_account",
+            "type_arguments": [],
+            "arguments": [account.address().to_hex_literal()]
+        },
+        "signature": {
+            "type": "ed25519_signature",
+            "public_key": format!("0x{}", hex::encode(sender.public_key().to_bytes())),
+            "signature": format!("0x{}", hex::encode([0u8; 64])),
+        },
+    });
+
+    let resp = context.post("/transactions/simulate", request).await;
+    assert_eq!(resp["success"], true, "{}", pretty(&resp));
+    assert!(context.mempool.get_txns(1).is_empty());
+}
+
+#[tokio::test]
+async fn test_simulate_transaction_rejects_valid_signature() {
+    let context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions/simulate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(
+        resp["message"],
+        "simulated transaction must not have a valid signature"
+    );
+}
+
+#[tokio::test]
+async fn test_simulate_transaction_with_stale_sequence_number() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&[txn.clone()]).await;
+
+    let unsigned_txn = SignedTransaction::new(
+        txn.into_raw_transaction(),
+        context.root_account().public_key().clone(),
+        Ed25519Signature::try_from(&[0u8; 64][..]).unwrap(),
+    );
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn(
+            "/transactions/simulate",
+            bcs::to_bytes(&unsigned_txn).unwrap(),
+        )
+        .await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("SEQUENCE_NUMBER_TOO_OLD"));
+}
+
 #[ignore]
 #[tokio::test]
 async fn test_multi_agent_signed_transaction() {
@@ -770,6 +976,109 @@ async fn test_get_account_transactions_filter_transactions_by_limit() {
     assert_eq!(txns.as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_get_transactions_with_cursor() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account1 = context.gen_account();
+    let account2 = context.gen_account();
+    let txn1 = context.create_user_account_by(&mut root_account, &account1);
+    let txn2 = context.create_user_account_by(&mut root_account, &account2);
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path("/transactions?start=0&limit=2"),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let cursor = resp.headers()[X_APTOS_CURSOR].to_str().unwrap().to_owned();
+    assert_eq!(
+        resp.headers()[LINK],
+        format!("</transactions?limit=2&cursor={}>; rel=\"next\"", cursor)
+    );
+
+    let txns = context
+        .get(&format!("/transactions?limit=2&cursor={}", cursor))
+        .await;
+    assert_eq!(txns.as_array().unwrap().len(), 2);
+    assert_eq!(txns[0]["version"], "2");
+    assert_eq!(txns[1]["version"], "3");
+}
+
+#[tokio::test]
+async fn test_get_transactions_with_start_and_cursor() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions?start=0&cursor=0x0000000000000000")
+        .await;
+    assert_json(
+        resp,
+        json!({
+            "code": 400,
+            "message": "start and cursor can not be used together"
+        }),
+    );
+}
+
+#[tokio::test]
+async fn test_get_account_transactions_filter_by_success() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn1 = context.create_user_account_by(&mut root_account, &account);
+    // fails as the account already exists
+    let txn2 = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    let path = format!("/accounts/{}/transactions", root_account.address());
+    let txns = context.get(&format!("{}?success=true", path)).await;
+    assert_eq!(txns.as_array().unwrap().len(), 1);
+    assert_eq!(txns[0]["sequence_number"], "0");
+    assert_eq!(txns[0]["success"], true);
+
+    let txns = context.get(&format!("{}?success=false", path)).await;
+    assert_eq!(txns.as_array().unwrap().len(), 1);
+    assert_eq!(txns[0]["sequence_number"], "1");
+    assert_eq!(txns[0]["success"], false);
+}
+
+#[tokio::test]
+async fn test_get_account_transactions_filter_by_function() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    let path = format!("/accounts/{}/transactions", root_account.address());
+    let txns = context
+        .get(&format!("{}?function=0x1::Account::create_account", path))
+        .await;
+    assert_eq!(txns.as_array().unwrap().len(), 1);
+    assert_eq!(
+        txns[0]["payload"]["function"],
+        "0x1::Account::create_account"
+    );
+
+    let resp = context
+        .reply(
+            warp::test::request()
+                .method("GET")
+                .path(&format!("{}?function=0x1::TestCoin::transfer", path)),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_json(serde_json::from_slice(resp.body()).unwrap(), json!([]));
+    // continues after the transactions scanned
+    let cursor = resp.headers()[X_APTOS_CURSOR].to_str().unwrap();
+    let txns = context.get(&format!("{}?cursor={}", path, cursor)).await;
+    assert_json(txns, json!([]));
+}
+
 #[tokio::test]
 async fn test_get_txn_execute_failed_by_invalid_module_payload_bytecode() {
     let context = new_test_context(current_function_name!());
@@ -1173,3 +1482,64 @@ fn gen_string(len: u64) -> String {
         .map(char::from)
         .collect()
 }
+
+#[tokio::test]
+async fn test_get_transactions_filtered_across_blocks_have_their_block_timestamp() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    for _ in 0..3 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&vec![txn]).await;
+    }
+
+    // the block metadata transactions carrying the timestamps are filtered out
+    let paths = [
+        "/transactions?function=0x1::Account::create_account".to_owned(),
+        format!(
+            "/accounts/{}/transactions?success=true",
+            root_account.address()
+        ),
+    ];
+    for path in paths {
+        let txns = context.get(&path).await;
+        let txns = txns.as_array().unwrap();
+        assert_eq!(txns.len(), 3);
+        let mut timestamps = vec![];
+        for txn in txns {
+            let version = txn["version"].as_str().unwrap();
+            let expected = context.get(&format!("/transactions/{}", version)).await;
+            assert_eq!(txn["timestamp"], expected["timestamp"]);
+            timestamps.push(txn["timestamp"].clone());
+        }
+        timestamps.dedup();
+        assert_eq!(timestamps.len(), 3);
+    }
+}
+
+#[tokio::test]
+async fn test_get_account_transactions_across_blocks_have_their_block_timestamp() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    for _ in 0..2 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&vec![txn]).await;
+    }
+
+    // without a filter, the block metadata transactions are still not part of the page
+    let txns = context
+        .get(&format!(
+            "/accounts/{}/transactions",
+            root_account.address()
+        ))
+        .await;
+    let txns = txns.as_array().unwrap();
+    assert_eq!(txns.len(), 2);
+    for txn in txns {
+        let version = txn["version"].as_str().unwrap();
+        let expected = context.get(&format!("/transactions/{}", version)).await;
+        assert_eq!(txn["timestamp"], expected["timestamp"]);
+    }
+    assert_ne!(txns[0]["timestamp"], txns[1]["timestamp"]);
+}
@@ -0,0 +1,166 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    current_function_name,
+    tests::{new_test_context, new_test_context_with_api_config},
+};
+use aptos_config::config::ApiConfig;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::json;
+
+#[tokio::test]
+async fn test_view_function() {
+    let context = new_test_context(current_function_name!());
+    let account = context.get("/accounts/0xa550c18").await;
+
+    let resp = context
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Account::get_sequence_number",
+                "type_arguments": [],
+                "arguments": ["0xa550c18"],
+            }),
+        )
+        .await;
+    assert_eq!(resp, json!([account["sequence_number"]]));
+}
+
+#[tokio::test]
+async fn test_view_function_with_type_arguments() {
+    let context = new_test_context(current_function_name!());
+    let path = format!(
+        "/accounts/0xb1e55ed/resource/{}",
+        utf8_percent_encode(
+            "0x1::Coin::CoinStore<0x1::TestCoin::TestCoin>",
+            NON_ALPHANUMERIC
+        )
+    );
+    let coin_store = context.get(path.as_str()).await;
+
+    let resp = context
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Coin::balance",
+                "type_arguments": ["0x1::TestCoin::TestCoin"],
+                "arguments": ["0xb1e55ed"],
+            }),
+        )
+        .await;
+    assert_eq!(resp, json!([coin_store["data"]["coin"]["value"]]));
+}
+
+#[tokio::test]
+async fn test_view_generic_function() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Vector::singleton",
+                "type_arguments": ["u64"],
+                "arguments": ["42"],
+            }),
+        )
+        .await;
+    assert_eq!(resp, json!([["42"]]));
+
+    let resp = context
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Option::some",
+                "type_arguments": ["vector<address>"],
+                "arguments": [["0xa550c18"]],
+            }),
+        )
+        .await;
+    assert_eq!(resp, json!([{"vec": [["0xa550c18"]]}]));
+}
+
+#[tokio::test]
+async fn test_view_function_out_of_gas() {
+    // Without any gas the first instruction runs out of gas.
+    let context = new_test_context_with_api_config(
+        current_function_name!(),
+        ApiConfig {
+            max_view_function_gas: Some(0),
+            ..Default::default()
+        },
+    );
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Account::get_sequence_number",
+                "type_arguments": [],
+                "arguments": ["0xa550c18"],
+            }),
+        )
+        .await;
+    assert_eq!(
+        resp["message"],
+        "view function ran out of gas, it may use at most 0 gas units"
+    );
+}
+
+#[tokio::test]
+async fn test_view_function_reads_latest_state() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let new_address = account.address().to_hex_literal();
+    let body = json!({
+        "function": "0x1::Account::exists_at",
+        "type_arguments": [],
+        "arguments": [new_address],
+    });
+
+    let resp = context.post("/view", body.clone()).await;
+    assert_eq!(resp, json!([false]));
+
+    let mut root_account = context.root_account();
+    let txn = root_account.sign_with_transaction_builder(
+        context
+            .transaction_factory()
+            .create_user_account(account.public_key()),
+    );
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context.post("/view", body).await;
+    assert_eq!(resp, json!([true]));
+}
+
+#[tokio::test]
+async fn test_view_function_with_signer_param() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Account::rotate_authentication_key_internal",
+                "type_arguments": [],
+                "arguments": ["0x0000000000000000000000000000000000000000000000000000000000000000"],
+            }),
+        )
+        .await;
+}
+
+#[tokio::test]
+async fn test_view_private_function() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .post(
+            "/view",
+            json!({
+                "function": "0x1::Account::create_account_unchecked",
+                "type_arguments": [],
+                "arguments": ["0xa550c18"],
+            }),
+        )
+        .await;
+}
@@ -5,22 +5,33 @@ use crate::{
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
-    page::Page,
-    param::{AddressParam, TransactionIdParam},
+    page::{page, scan, Page},
+    param::{AddressParam, Param, TransactionIdParam},
+    proof::{self, ProofQuery},
 };
 
 use aptos_api_types::{
-    mime_types::BCS_SIGNED_TRANSACTION, AsConverter, Error, LedgerInfo, Response, Transaction,
-    TransactionData, TransactionId, TransactionOnChainData, TransactionSigningMessage,
-    UserCreateSigningMessageRequest, UserTransactionRequest,
+    mime_types::BCS_SIGNED_TRANSACTION, AsConverter, Error, LedgerInfo, Response, ScriptFunctionId,
+    Transaction, TransactionData, TransactionId, TransactionOnChainData, TransactionSigningMessage,
+    TransactionSubmissionStatus, UserCreateSigningMessageRequest, UserTransactionRequest,
+};
+use aptos_crypto::{
+    hash::{CryptoHash, EventAccumulatorHasher},
+    signing_message, HashValue,
 };
-use aptos_crypto::signing_message;
 use aptos_types::{
-    mempool_status::MempoolStatusCode,
-    transaction::{RawTransaction, RawTransactionWithData, SignedTransaction},
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    proof::accumulator::InMemoryAccumulator,
+    transaction::{
+        RawTransaction, RawTransactionWithData, SignedTransaction, TransactionInfo,
+        TransactionPayload, TransactionStatus,
+    },
 };
+use aptos_vm::AptosVM;
 
-use anyhow::Result;
+use anyhow::{format_err, Result};
+use move_deps::move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use serde::Deserialize;
 use warp::{
     filters::BoxedFilter,
     http::{header::CONTENT_TYPE, StatusCode},
@@ -31,28 +42,31 @@ use warp::{
 pub fn get_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / TransactionIdParam)
         .and(warp::get())
+        .and(warp::query::<ProofQuery>())
         .and(context.filter())
         .and_then(handle_get_transaction)
         .with(metrics("get_transaction"))
         .boxed()
 }
 
-// GET /transactions?start={u64}&limit={u16}
+// GET /transactions?start={u64}&limit={u16}&cursor={cursor}&success={bool}&function={function_id}
 pub fn get_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
         .and(warp::get())
-        .and(warp::query::<Page>())
+        .and(page())
+        .and(warp::query::<TransactionFilter>())
         .and(context.filter())
         .and_then(handle_get_transactions)
         .with(metrics("get_transactions"))
         .boxed()
 }
 
-// GET /accounts/{address}/transactions?start={u64}&limit={u16}
+// GET /accounts/{address}/transactions?start={u64}&limit={u16}&cursor={cursor}&success={bool}&function={function_id}
 pub fn get_account_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "transactions")
         .and(warp::get())
-        .and(warp::query::<Page>())
+        .and(page())
+        .and(warp::query::<TransactionFilter>())
         .and(context.filter())
         .and_then(handle_get_account_transactions)
         .with(metrics("get_account_transactions"))
@@ -96,6 +110,72 @@ pub fn submit_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// POST /transactions/batch with JSON
+pub fn submit_json_transaction_batch(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "batch")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<Vec<UserTransactionRequest>>())
+        .and(context.filter())
+        .and_then(handle_submit_json_transaction_batch)
+        .with(metrics("submit_json_transaction_batch"))
+        .boxed()
+}
+
+// POST /transactions/batch with BCS
+pub fn submit_bcs_transaction_batch(context: Context) -> BoxedFilter<(impl Reply,)> {
+    // See `submit_bcs_transactions` for why the content-type header is matched exactly.
+    warp::path!("transactions" / "batch")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::header::exact(
+            CONTENT_TYPE.as_str(),
+            BCS_SIGNED_TRANSACTION,
+        ))
+        .and(warp::body::bytes())
+        .and(context.filter())
+        .and_then(handle_submit_bcs_transaction_batch)
+        .with(metrics("submit_bcs_transaction_batch"))
+        .boxed()
+}
+
+// POST /transactions/simulate with JSON
+pub fn simulate_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "simulate")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<UserTransactionRequest>())
+        .and(context.filter())
+        .and_then(handle_simulate_json_transactions)
+        .with(metrics("simulate_json_transactions"))
+        .boxed()
+}
+
+// POST /transactions/simulate with BCS
+pub fn simulate_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    // See `submit_bcs_transactions` for why the content-type header is matched exactly.
+    warp::path!("transactions" / "simulate")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::header::exact(
+            CONTENT_TYPE.as_str(),
+            BCS_SIGNED_TRANSACTION,
+        ))
+        .and(warp::body::bytes())
+        .and(context.filter())
+        .and_then(handle_simulate_bcs_transactions)
+        .with(metrics("simulate_bcs_transactions"))
+        .boxed()
+}
+
 // POST /transactions/signing_message
 pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / "signing_message")
@@ -112,26 +192,74 @@ pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
 
 async fn handle_get_transaction(
     id: TransactionIdParam,
+    proof: ProofQuery,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transaction")?;
     Ok(Transactions::new(context)?
-        .get_transaction(id.parse("transaction hash or version")?)
+        .get_transaction(id.parse("transaction hash or version")?, proof)
         .await?)
 }
 
-async fn handle_get_transactions(page: Page, context: Context) -> Result<impl Reply, Rejection> {
+async fn handle_get_transactions(
+    page: Page,
+    filter: TransactionFilter,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transactions")?;
-    Ok(Transactions::new(context)?.list(page)?)
+    Ok(Transactions::new(context)?.list(page, filter)?)
 }
 
 async fn handle_get_account_transactions(
     address: AddressParam,
     page: Page,
+    filter: TransactionFilter,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_transactions")?;
-    Ok(Transactions::new(context)?.list_by_account(address, page)?)
+    Ok(Transactions::new(context)?.list_by_account(address, page, filter)?)
+}
+
+/// Filters the listed transactions by their execution status, and by the script function called
+/// by their payload.
+#[derive(Clone, Debug, Deserialize)]
+struct TransactionFilter {
+    success: Option<Param<bool>>,
+    function: Option<Param<ScriptFunctionId>>,
+}
+
+impl TransactionFilter {
+    fn is_empty(&self) -> bool {
+        self.success.is_none() && self.function.is_none()
+    }
+
+    fn matcher(self) -> Result<impl Fn(&TransactionOnChainData) -> bool, Error> {
+        let success = self.success.map(|v| v.parse("success")).transpose()?;
+        let function = self
+            .function
+            .map(|v| v.parse("function"))
+            .transpose()?
+            .map(|id| (ModuleId::from(id.module), id.name));
+        Ok(move |txn: &TransactionOnChainData| {
+            success.map_or(true, |success| txn.info.status().is_success() == success)
+                && function.as_ref().map_or(true, |(module, name)| {
+                    calls_function(&txn.transaction, module, name)
+                })
+        })
+    }
+}
+
+fn calls_function(
+    txn: &aptos_types::transaction::Transaction,
+    module: &ModuleId,
+    name: &Identifier,
+) -> bool {
+    match txn.as_signed_user_txn().map(|txn| txn.payload()) {
+        Ok(TransactionPayload::ScriptFunction(function)) => {
+            function.module() == module && function.function() == name.as_ident_str()
+        }
+        _ => false,
+    }
 }
 
 async fn handle_submit_json_transactions(
@@ -154,6 +282,49 @@ async fn handle_submit_bcs_transactions(
     Ok(Transactions::new(context)?.create(txn).await?)
 }
 
+async fn handle_submit_json_transaction_batch(
+    body: Vec<UserTransactionRequest>,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_submit_json_transaction_batch")?;
+    let transactions = Transactions::new(context)?;
+    let txns = body
+        .into_iter()
+        .map(|req| transactions.signed_transaction_from_request(req))
+        .collect::<Result<_, _>>()?;
+    Ok(transactions.create_batch(txns).await?)
+}
+
+async fn handle_submit_bcs_transaction_batch(
+    body: bytes::Bytes,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_submit_bcs_transaction_batch")?;
+    let txns = bcs::from_bytes(&body)
+        .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
+    Ok(Transactions::new(context)?.create_batch(txns).await?)
+}
+
+async fn handle_simulate_json_transactions(
+    body: UserTransactionRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_simulate_json_transactions")?;
+    let transactions = Transactions::new(context)?;
+    let txn = transactions.signed_transaction_from_request(body)?;
+    Ok(transactions.simulate(txn)?)
+}
+
+async fn handle_simulate_bcs_transactions(
+    body: bytes::Bytes,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_simulate_bcs_transactions")?;
+    let txn = bcs::from_bytes(&body)
+        .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
+    Ok(Transactions::new(context)?.simulate(txn)?)
+}
+
 async fn handle_create_signing_message(
     body: UserCreateSigningMessageRequest,
     context: Context,
@@ -180,8 +351,15 @@ impl Transactions {
         self,
         req: UserTransactionRequest,
     ) -> Result<impl Reply, Error> {
-        let txn = self
-            .context
+        let txn = self.signed_transaction_from_request(req)?;
+        self.create(txn).await
+    }
+
+    fn signed_transaction_from_request(
+        &self,
+        req: UserTransactionRequest,
+    ) -> Result<SignedTransaction, Error> {
+        self.context
             .move_resolver()?
             .as_converter()
             .try_into_signed_transaction(req, self.context.chain_id())
@@ -190,8 +368,67 @@ impl Transactions {
                     "failed to create SignedTransaction from UserTransactionRequest: {}",
                     e
                 ))
-            })?;
-        self.create(txn).await
+            })
+    }
+
+    /// Executes the transaction against the state at the latest ledger version without
+    /// committing it or submitting it to mempool.
+    pub fn simulate(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
+        // A transaction with a valid signature could be replayed by anyone who sees the
+        // simulation request, so only unsigned transactions are accepted.
+        if txn.clone().check_signature().is_ok() {
+            return Err(Error::bad_request(
+                "simulated transaction must not have a valid signature",
+            ));
+        }
+
+        let ledger_version = self.ledger_info.version();
+        let state_view = self.context.state_view_at_version(ledger_version)?;
+        let (vm_status, output) = AptosVM::simulate_signed_transaction(&txn, &state_view);
+        let (write_set, events, gas_used, status) = output.unpack();
+        let exe_status = match status {
+            TransactionStatus::Keep(exe_status) => exe_status,
+            TransactionStatus::Discard(_) => {
+                return Err(Error::bad_request(format!(
+                    "invalid transaction: {:?}",
+                    vm_status
+                )))
+            }
+            TransactionStatus::Retry => {
+                return Err(Error::internal(format_err!(
+                    "unexpected retry status for simulated transaction"
+                )))
+            }
+        };
+
+        let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+        let event_root_hash =
+            InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes).root_hash();
+        let txn = aptos_types::transaction::Transaction::UserTransaction(txn);
+        let info = TransactionInfo::new(
+            txn.hash(),
+            CryptoHash::hash(&write_set),
+            event_root_hash,
+            None,
+            gas_used,
+            exe_status,
+        );
+        // The transaction would be the next one committed, and the accumulator root hash after
+        // it is only known once it's actually committed
+        let data = TransactionOnChainData {
+            version: ledger_version + 1,
+            transaction: txn,
+            info,
+            events,
+            accumulator_root_hash: HashValue::zero(),
+            changes: write_set,
+        };
+
+        let resolver = self.context.move_resolver()?;
+        let simulated_txn = resolver
+            .as_converter()
+            .try_into_onchain_transaction(self.ledger_info.timestamp(), data)?;
+        Response::new(self.ledger_info, &simulated_txn)
     }
 
     pub async fn create(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
@@ -216,7 +453,7 @@ impl Transactions {
         }
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
+    pub fn list(self, page: Page, filter: TransactionFilter) -> Result<impl Reply, Error> {
         let ledger_version = self.ledger_info.version();
         let limit = page.limit()?;
         let last_page_start = if ledger_version > (limit as u64) {
@@ -225,28 +462,69 @@ impl Transactions {
             0
         };
         let start_version = page.start(last_page_start, ledger_version)?;
+        let contiguous = filter.is_empty();
+
+        let (data, next) = scan(
+            start_version,
+            limit,
+            |start, limit| {
+                // a cursor may point right after the ledger version
+                if start > ledger_version {
+                    return Ok(vec![]);
+                }
+                Ok(self
+                    .context
+                    .get_transactions(start, limit, ledger_version)?)
+            },
+            |txn| txn.version,
+            filter.matcher()?,
+        )?;
 
-        let data = self
-            .context
-            .get_transactions(start_version, limit, ledger_version)?;
-
-        self.render_transactions(data)
+        self.render_transactions(&page, data, next, contiguous)
     }
 
-    pub fn list_by_account(self, address: AddressParam, page: Page) -> Result<impl Reply, Error> {
-        let data = self.context.get_account_transactions(
-            address.parse("account address")?.into(),
+    pub fn list_by_account(
+        self,
+        address: AddressParam,
+        page: Page,
+        filter: TransactionFilter,
+    ) -> Result<impl Reply, Error> {
+        let address = address.parse("account address")?.into();
+        let filter = filter.matcher()?;
+        // the account transactions are paginated by sequence number
+        let (data, next) = scan(
             page.start(0, u64::MAX)?,
             page.limit()?,
-            self.ledger_info.version(),
+            |start, limit| {
+                self.context
+                    .get_account_transactions(address, start, limit, self.ledger_info.version())?
+                    .into_iter()
+                    .map(|txn| -> Result<_, Error> {
+                        Ok((txn.transaction.as_signed_user_txn()?.sequence_number(), txn))
+                    })
+                    .collect()
+            },
+            |(sequence_number, _)| *sequence_number,
+            |(_, txn)| filter(txn),
         )?;
-        self.render_transactions(data)
+        let data = data.into_iter().map(|(_, txn)| txn).collect();
+        // the transactions of an account are spread over blocks
+        self.render_transactions(&page, data, next, false)
     }
 
-    fn render_transactions(self, data: Vec<TransactionOnChainData>) -> Result<impl Reply, Error> {
+    /// Renders a page of transactions. The block timestamp is carried over from each block
+    /// metadata transaction to the following transactions, unless the transactions are not
+    /// `contiguous`, e.g. filtered, in which case it's looked up for every transaction.
+    fn render_transactions(
+        self,
+        page: &Page,
+        data: Vec<TransactionOnChainData>,
+        next: u64,
+        contiguous: bool,
+    ) -> Result<impl Reply, Error> {
         if data.is_empty() {
             let txns: Vec<Transaction> = vec![];
-            return Response::new(self.ledger_info, &txns);
+            return page.render(self.ledger_info, &txns, next);
         }
         let first_version = data[0].version;
         let mut timestamp = self.context.get_block_timestamp(first_version)?;
@@ -255,6 +533,9 @@ impl Transactions {
         let txns: Vec<Transaction> = data
             .into_iter()
             .map(|t| {
+                if !contiguous {
+                    timestamp = self.context.get_block_timestamp(t.version)?;
+                }
                 let txn = converter.try_into_onchain_transaction(timestamp, t)?;
                 // update timestamp, when txn is metadata block transaction
                 // new timestamp is used for the following transactions
@@ -262,16 +543,37 @@ impl Transactions {
                 Ok(txn)
             })
             .collect::<Result<_>>()?;
-        Response::new(self.ledger_info, &txns)
+        page.render(self.ledger_info, &txns, next)
     }
 
-    pub async fn get_transaction(self, id: TransactionId) -> Result<impl Reply, Error> {
+    pub async fn get_transaction(
+        self,
+        id: TransactionId,
+        proof: ProofQuery,
+    ) -> Result<impl Reply, Error> {
         let txn_data = match id.clone() {
             TransactionId::Hash(hash) => self.get_by_hash(hash.into()).await?,
             TransactionId::Version(version) => self.get_by_version(version)?,
         }
         .ok_or_else(|| self.transaction_not_found(id))?;
 
+        let proof = match &txn_data {
+            TransactionData::OnChain(txn) => proof
+                .known_version(txn.version)?
+                .map(|known_version| {
+                    proof::prove_transaction(&self.context, txn.version, known_version)
+                })
+                .transpose()?,
+            TransactionData::Pending(_) => {
+                if proof.requested()? {
+                    return Err(Error::bad_request(
+                        "proof is not available for pending transaction",
+                    ));
+                }
+                None
+            }
+        };
+
         let resolver = self.context.move_resolver()?;
         let txn = match txn_data {
             TransactionData::OnChain(txn) => {
@@ -285,7 +587,42 @@ impl Transactions {
             }
         };
 
-        Response::new(self.ledger_info, &txn)
+        proof::render(self.ledger_info, &txn, proof)
+    }
+
+    /// Submits each transaction of the batch to the mempool, replying with their submission
+    /// statuses in the order of the batch. The batch is accepted as a whole even though some of
+    /// its transactions may be rejected, or fail to reach the mempool at all, as the transactions
+    /// submitted before them stay in the mempool.
+    pub async fn create_batch(self, txns: Vec<SignedTransaction>) -> Result<impl Reply, Error> {
+        let max_batch_size = self.context.max_submit_transaction_batch_size();
+        if txns.len() > max_batch_size {
+            return Err(Error::bad_request(format!(
+                "too many transactions in the batch: {}, the max is {}",
+                txns.len(),
+                max_batch_size
+            )));
+        }
+
+        // submitted one by one, so that the transactions of an account are inserted in order
+        let mut statuses = Vec::with_capacity(txns.len());
+        for txn in txns {
+            let hash = txn.clone().committed_hash();
+            let status = match self.context.submit_transaction(txn).await {
+                Ok((mempool_status, vm_status_opt)) => {
+                    TransactionSubmissionStatus::new(hash, &mempool_status, vm_status_opt)
+                }
+                Err(err) => TransactionSubmissionStatus::new(
+                    hash,
+                    &MempoolStatus::new(MempoolStatusCode::UnknownStatus)
+                        .with_message(err.to_string()),
+                    None,
+                ),
+            };
+            statuses.push(status);
+        }
+        let resp = Response::new(self.ledger_info, &statuses)?;
+        Ok(reply::with_status(resp, StatusCode::ACCEPTED))
     }
 
     pub fn signing_message(
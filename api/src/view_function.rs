@@ -0,0 +1,74 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{context::Context, failpoint::fail_point, metrics::metrics};
+
+use aptos_api_types::{AsConverter, Error, LedgerInfo, Response, ScriptFunctionPayload};
+use aptos_types::vm_status::StatusCode;
+use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
+
+use anyhow::Result;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+// POST /view
+pub fn view_function(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("view")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<ScriptFunctionPayload>())
+        .and(context.filter())
+        .and_then(handle_view_function)
+        .with(metrics("view_function"))
+        .boxed()
+}
+
+async fn handle_view_function(
+    body: ScriptFunctionPayload,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_view_function")?;
+    Ok(ViewFunction::new(context)?.view(body)?)
+}
+
+struct ViewFunction {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl ViewFunction {
+    fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info()?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    /// Executes the function against the state at the latest ledger version and returns its
+    /// return values. Nothing written by the function is persisted.
+    fn view(self, payload: ScriptFunctionPayload) -> Result<impl Reply, Error> {
+        let state_view = self
+            .context
+            .state_view_at_version(self.ledger_info.version())?;
+        let resolver = state_view.as_move_resolver();
+        let converter = resolver.as_converter();
+
+        let (function, return_types) = converter
+            .try_into_view_function(payload)
+            .map_err(Error::bad_request)?;
+        let max_gas = self.context.max_view_function_gas();
+        let return_values = AptosVM::execute_view_function(&state_view, &function, max_gas)
+            .map_err(|vm_status| match vm_status.status_code() {
+                StatusCode::OUT_OF_GAS => Error::bad_request(format!(
+                    "view function ran out of gas, it may use at most {} gas units",
+                    max_gas
+                )),
+                _ => Error::bad_request(format!("view function execution failed: {:?}", vm_status)),
+            })?;
+
+        let move_values = converter.try_into_move_values(&return_types, return_values)?;
+        Response::new(self.ledger_info, &move_values)
+    }
+}
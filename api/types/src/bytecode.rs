@@ -39,6 +39,8 @@ pub trait Bytecode {
 
     fn find_script_function(&self, name: &IdentStr) -> Option<MoveFunction>;
 
+    fn find_public_function(&self, name: &IdentStr) -> Option<MoveFunction>;
+
     fn new_move_struct_field(&self, def: &FieldDefinition) -> MoveStructField {
         MoveStructField {
             name: self.identifier_at(def.name).to_owned(),
@@ -181,6 +183,17 @@ impl Bytecode for CompiledModule {
             })
             .map(|def| self.new_move_function(def))
     }
+
+    fn find_public_function(&self, name: &IdentStr) -> Option<MoveFunction> {
+        self.function_defs
+            .iter()
+            .filter(|def| matches!(def.visibility, Visibility::Public | Visibility::Script))
+            .find(|def| {
+                let fhandle = ModuleAccess::function_handle_at(self, def.function);
+                ModuleAccess::identifier_at(self, fhandle.name) == name
+            })
+            .map(|def| self.new_move_function(def))
+    }
 }
 
 impl Bytecode for CompiledScript {
@@ -215,4 +228,8 @@ impl Bytecode for CompiledScript {
             None
         }
     }
+
+    fn find_public_function(&self, _name: &IdentStr) -> Option<MoveFunction> {
+        None
+    }
 }
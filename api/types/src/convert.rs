@@ -4,7 +4,7 @@
 use crate::{
     transaction::{ModuleBundlePayload, StateCheckpointTransaction},
     Bytecode, DirectWriteSet, Event, HexEncodedBytes, MoveFunction, MoveModuleBytecode,
-    MoveResource, MoveScriptBytecode, MoveValue, ScriptFunctionId, ScriptFunctionPayload,
+    MoveResource, MoveScriptBytecode, MoveType, MoveValue, ScriptFunctionId, ScriptFunctionPayload,
     ScriptPayload, ScriptWriteSet, Transaction, TransactionInfo, TransactionOnChainData,
     TransactionPayload, UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
 };
@@ -362,8 +362,12 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
                     function,
                     type_arguments.len()
                 );
+                let type_arguments = type_arguments
+                    .into_iter()
+                    .map(|v| v.try_into())
+                    .collect::<Result<Vec<TypeTag>>>()?;
                 let args = self
-                    .try_into_vm_values(func, arguments)?
+                    .try_into_vm_values(func, &type_arguments, arguments)?
                     .iter()
                     .map(bcs::to_bytes)
                     .collect::<Result<_, bcs::Error>>()?;
@@ -371,10 +375,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
                 Target::ScriptFunction(ScriptFunction::new(
                     module.into(),
                     function.name,
-                    type_arguments
-                        .into_iter()
-                        .map(|v| v.try_into())
-                        .collect::<Result<_>>()?,
+                    type_arguments,
                     args,
                 ))
             }
@@ -397,13 +398,14 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
                 let MoveScriptBytecode { bytecode, abi } = code.try_parse_abi();
                 match abi {
                     Some(func) => {
-                        let args = self.try_into_vm_values(func, arguments)?;
+                        let type_arguments = type_arguments
+                            .into_iter()
+                            .map(|v| v.try_into())
+                            .collect::<Result<Vec<TypeTag>>>()?;
+                        let args = self.try_into_vm_values(func, &type_arguments, arguments)?;
                         Target::Script(Script::new(
                             bytecode.into(),
-                            type_arguments
-                                .into_iter()
-                                .map(|v| v.try_into())
-                                .collect::<Result<_>>()?,
+                            type_arguments,
                             args.into_iter()
                                 .map(|arg| arg.try_into())
                                 .collect::<Result<_>>()?,
@@ -421,9 +423,87 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         Ok(ret)
     }
 
+    /// Converts a `ScriptFunctionPayload` into a `ScriptFunction` that can be executed as a
+    /// read-only view function, along with the return types of the function instantiated with
+    /// the given type arguments.
+    pub fn try_into_view_function(
+        &self,
+        payload: ScriptFunctionPayload,
+    ) -> Result<(ScriptFunction, Vec<TypeTag>)> {
+        let ScriptFunctionPayload {
+            function,
+            type_arguments,
+            arguments,
+        } = payload;
+
+        let module = function.module.clone();
+        let code = self.inner.get_module(&module.clone().into())? as Rc<dyn Bytecode>;
+        let func = code
+            .find_public_function(function.name.as_ident_str())
+            .ok_or_else(|| format_err!("could not find public function by {}", function))?;
+        ensure!(
+            func.generic_type_params.len() == type_arguments.len(),
+            "expect {} type arguments for function {}, but got {}",
+            func.generic_type_params.len(),
+            function,
+            type_arguments.len()
+        );
+        ensure!(
+            !func.params.iter().any(|p| p.is_signer()),
+            "function {} takes a signer and can not be executed as a view function",
+            function
+        );
+        ensure!(
+            !func.return_.is_empty(),
+            "function {} does not return any value",
+            function
+        );
+
+        let type_arguments = type_arguments
+            .into_iter()
+            .map(|v| v.try_into())
+            .collect::<Result<Vec<TypeTag>>>()?;
+        let return_types = func
+            .return_
+            .iter()
+            .map(|typ| instantiate_type(typ, &type_arguments))
+            .collect::<Result<_>>()?;
+        let args = self
+            .try_into_vm_values(func, &type_arguments, arguments)?
+            .iter()
+            .map(bcs::to_bytes)
+            .collect::<Result<_, bcs::Error>>()?;
+
+        Ok((
+            ScriptFunction::new(module.into(), function.name, type_arguments, args),
+            return_types,
+        ))
+    }
+
+    pub fn try_into_move_values(
+        &self,
+        types: &[TypeTag],
+        values: Vec<Vec<u8>>,
+    ) -> Result<Vec<MoveValue>> {
+        ensure!(
+            types.len() == values.len(),
+            "expected {} values, but got {}",
+            types.len(),
+            values.len()
+        );
+        types
+            .iter()
+            .zip(values.into_iter())
+            .map(|(typ, bytes)| self.try_into_move_value(typ, &bytes))
+            .collect()
+    }
+
+    /// Converts the JSON `args` of `func` into `MoveValue`s, with the generic type params in the
+    /// param types of `func` instantiated with `type_args`.
     pub fn try_into_vm_values(
         &self,
         func: MoveFunction,
+        type_args: &[TypeTag],
         args: Vec<serde_json::Value>,
     ) -> Result<Vec<move_core_types::value::MoveValue>> {
         let arg_types = func
@@ -448,7 +528,7 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
             .zip(args.into_iter())
             .enumerate()
             .map(|(i, (arg_type, arg))| {
-                self.try_into_vm_value(&arg_type.clone().try_into()?, arg)
+                self.try_into_vm_value(&instantiate_type(&arg_type, type_args)?, arg)
                     .map_err(|e| {
                         format_err!(
                             "parse arguments[{}] failed, expect {}, caused by error: {}",
@@ -636,6 +716,30 @@ impl<R: MoveResolverExt> AsConverter<R> for R {
     }
 }
 
+// Substitutes the generic type params in `typ` with the given type arguments.
+fn instantiate_type(typ: &MoveType, type_args: &[TypeTag]) -> Result<TypeTag> {
+    Ok(match typ {
+        MoveType::GenericTypeParam { index } => type_args
+            .get(*index as usize)
+            .cloned()
+            .ok_or_else(|| format_err!("missing type argument for generic type param {}", index))?,
+        MoveType::Vector { items } => {
+            TypeTag::Vector(Box::new(instantiate_type(items, type_args)?))
+        }
+        MoveType::Struct(tag) => TypeTag::Struct(StructTag {
+            address: (&tag.address).into(),
+            module: tag.module.clone(),
+            name: tag.name.clone(),
+            type_params: tag
+                .generic_type_params
+                .iter()
+                .map(|p| instantiate_type(p, type_args))
+                .collect::<Result<_>>()?,
+        }),
+        _ => typ.clone().try_into()?,
+    })
+}
+
 pub fn new_vm_ascii_string(string: &str) -> move_core_types::value::MoveValue {
     use move_deps::move_core_types::value::{MoveStruct, MoveValue};
 
@@ -0,0 +1,13 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::U64;
+use serde::{Deserialize, Serialize};
+
+/// Gas unit prices derived from recently committed transactions and the current mempool.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GasEstimation {
+    pub low: U64,
+    pub median: U64,
+    pub high: U64,
+}
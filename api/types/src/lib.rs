@@ -3,39 +3,46 @@
 
 mod account;
 mod address;
+mod block;
 mod bytecode;
 mod convert;
 mod error;
 mod event_key;
+mod gas_estimation;
 mod hash;
 mod ledger_info;
 pub mod mime_types;
 mod move_types;
+mod proof;
 mod response;
 mod table;
 mod transaction;
 
 pub use account::AccountData;
 pub use address::Address;
+pub use block::Block;
 pub use bytecode::Bytecode;
 pub use convert::{new_vm_ascii_string, AsConverter, MoveConverter};
 pub use error::Error;
 pub use event_key::EventKey;
+pub use gas_estimation::GasEstimation;
 pub use hash::HashValue;
 pub use ledger_info::LedgerInfo;
 pub use move_types::{
-    HexEncodedBytes, MoveFunction, MoveModule, MoveModuleBytecode, MoveModuleId, MoveResource,
-    MoveScriptBytecode, MoveStructTag, MoveStructValue, MoveType, MoveValue, ScriptFunctionId,
-    U128, U64,
+    HexEncodedBytes, MoveFunction, MoveFunctionVisibility, MoveModule, MoveModuleBytecode,
+    MoveModuleId, MoveResource, MoveScriptBytecode, MoveStructTag, MoveStructValue, MoveType,
+    MoveValue, ScriptFunctionId, U128, U64,
 };
+pub use proof::{StateValueProof, TransactionProof, ValueWithProof};
 pub use response::{
-    Response, X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+    Response, X_APTOS_CHAIN_ID, X_APTOS_CURSOR, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP,
+    X_APTOS_LEDGER_VERSION,
 };
 pub use table::TableItemRequest;
 pub use transaction::{
     BlockMetadataTransaction, DirectWriteSet, Event, GenesisTransaction, PendingTransaction,
-    ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, Transaction, TransactionData,
-    TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
-    TransactionSigningMessage, UserCreateSigningMessageRequest, UserTransaction,
-    UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
+    ResourceChange, ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, Transaction,
+    TransactionData, TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
+    TransactionSigningMessage, TransactionSubmissionStatus, UserCreateSigningMessageRequest,
+    UserTransaction, UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
 };
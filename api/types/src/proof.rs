@@ -0,0 +1,125 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::HexEncodedBytes;
+use anyhow::{ensure, Result};
+use aptos_crypto::hash::CryptoHash;
+use aptos_types::{
+    proof::{SparseMerkleProof, TransactionInfoWithProof},
+    state_proof::StateProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{TransactionWithProof, Version},
+    trusted_state::{TrustedState, TrustedStateChange},
+};
+use serde::{Deserialize, Serialize};
+
+/// A value rendered by the API along with the BCS serialized proof authenticating it, returned
+/// instead of the bare value when a proof is requested with the `proof` query parameter.
+///
+/// The proof is a [`StateValueProof`] for the state reads and a [`TransactionProof`] for the
+/// transaction reads.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValueWithProof<T> {
+    pub value: T,
+    pub proof: HexEncodedBytes,
+}
+
+/// Proves the value of a state key at `version`, up to a ledger info signed by the validators.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateValueProof {
+    /// The latest ledger info, and the epoch changes since the version known by the client.
+    pub state_proof: StateProof,
+    pub version: Version,
+    /// Proves the transaction info at `version`, which holds the state root hash, against the
+    /// latest ledger info.
+    pub transaction_info_with_proof: TransactionInfoWithProof,
+    pub state_value: Option<StateValue>,
+    /// Proves the state value against the state root hash.
+    pub sparse_merkle_proof: SparseMerkleProof,
+}
+
+impl StateValueProof {
+    /// Verifies that `state_key` has `self.state_value` at `version`, and returns
+    /// `trusted_state` ratcheted to the latest ledger info of the proof.
+    ///
+    /// The value must be the latest one, i.e. `version` must be the latest state checkpoint at
+    /// or before the latest ledger info. As a ledger info is only committed at the end of a
+    /// block, which is a state checkpoint, that is the version of the ledger info itself, so an
+    /// older value proven against an older transaction info is rejected.
+    pub fn verify(
+        &self,
+        trusted_state: &TrustedState,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<TrustedState> {
+        ensure!(
+            self.version == version,
+            "Version ({}) is not expected ({}).",
+            self.version,
+            version,
+        );
+        let latest_version = self.state_proof.latest_ledger_info().version();
+        ensure!(
+            version == latest_version,
+            "Version ({}) is not the latest state checkpoint at ledger version {}.",
+            version,
+            latest_version,
+        );
+        let new_state = ratchet(trusted_state, &self.state_proof)?;
+        self.transaction_info_with_proof
+            .verify(self.state_proof.latest_ledger_info(), self.version)?;
+        let state_root_hash = self
+            .transaction_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()?;
+        self.sparse_merkle_proof.verify(
+            state_root_hash,
+            state_key.hash(),
+            self.state_value.as_ref(),
+        )?;
+        Ok(new_state)
+    }
+}
+
+/// Proves a committed transaction and its events, up to a ledger info signed by the validators.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionProof {
+    /// The latest ledger info, and the epoch changes since the version known by the client.
+    pub state_proof: StateProof,
+    pub transaction_with_proof: TransactionWithProof,
+}
+
+impl TransactionProof {
+    /// Verifies that `self.transaction_with_proof` is committed at `version`, and returns
+    /// `trusted_state` ratcheted to the latest ledger info of the proof.
+    pub fn verify(&self, trusted_state: &TrustedState, version: Version) -> Result<TrustedState> {
+        let new_state = ratchet(trusted_state, &self.state_proof)?;
+        ensure!(
+            self.transaction_with_proof.version == version,
+            "Version ({}) is not expected ({}).",
+            self.transaction_with_proof.version,
+            version,
+        );
+        self.transaction_with_proof
+            .verify(self.state_proof.latest_ledger_info())?;
+        Ok(new_state)
+    }
+}
+
+/// Ratchets `trusted_state` to the latest ledger info of `state_proof`, failing if that ledger
+/// info can not be verified from `trusted_state`.
+fn ratchet(trusted_state: &TrustedState, state_proof: &StateProof) -> Result<TrustedState> {
+    let new_state = match trusted_state.verify_and_ratchet(state_proof)? {
+        TrustedStateChange::Version { new_state } | TrustedStateChange::Epoch { new_state, .. } => {
+            new_state
+        }
+        TrustedStateChange::NoChange => trusted_state.clone(),
+    };
+    // a partial epoch change proof only ratchets up to the last epoch change it holds
+    ensure!(
+        new_state.version() == state_proof.latest_ledger_info().version(),
+        "Latest ledger info at version {} is not reached by the epoch change proof.",
+        state_proof.latest_ledger_info().version(),
+    );
+    Ok(new_state)
+}
@@ -5,16 +5,19 @@ use crate::{Error, LedgerInfo};
 
 use anyhow::Result;
 use serde::Serialize;
-use warp::http::header::{HeaderValue, CONTENT_TYPE};
+use warp::http::header::{HeaderValue, CONTENT_TYPE, LINK};
 
 pub const X_APTOS_CHAIN_ID: &str = "X-Aptos-Chain-Id";
 pub const X_APTOS_EPOCH: &str = "X-Aptos-Epoch";
 pub const X_APTOS_LEDGER_VERSION: &str = "X-Aptos-Ledger-Version";
 pub const X_APTOS_LEDGER_TIMESTAMP: &str = "X-Aptos-Ledger-TimestampUsec";
+pub const X_APTOS_CURSOR: &str = "X-Aptos-Cursor";
 
 pub struct Response {
     pub ledger_info: LedgerInfo,
     pub body: Vec<u8>,
+    /// The cursor continuing a paginated listing, and the link to its next page.
+    pub cursor: Option<(String, String)>,
 }
 
 impl Response {
@@ -22,8 +25,16 @@ impl Response {
         Ok(Self {
             ledger_info,
             body: serde_json::to_vec(body)?,
+            cursor: None,
         })
     }
+
+    pub fn with_cursor(self, cursor: String, link: String) -> Self {
+        Self {
+            cursor: Some((cursor, link)),
+            ..self
+        }
+    }
 }
 
 impl warp::Reply for Response {
@@ -42,6 +53,14 @@ impl warp::Reply for Response {
             self.ledger_info.ledger_timestamp.into(),
         );
         headers.insert(X_APTOS_EPOCH, self.ledger_info.epoch.into());
+        if let Some((cursor, link)) = self.cursor {
+            if let Ok(cursor) = HeaderValue::from_str(&cursor) {
+                headers.insert(X_APTOS_CURSOR, cursor);
+            }
+            if let Ok(link) = HeaderValue::from_str(&link) {
+                headers.insert(LINK, link);
+            }
+        }
 
         res
     }
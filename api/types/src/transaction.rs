@@ -16,10 +16,12 @@ use aptos_types::{
     account_address::AccountAddress,
     block_metadata::BlockMetadata,
     contract_event::ContractEvent,
+    mempool_status::{MempoolStatus, MempoolStatusCode},
     transaction::{
         authenticator::{AccountAuthenticator, TransactionAuthenticator},
         Script, SignedTransaction, TransactionOutput, TransactionWithProof,
     },
+    vm_status::DiscardedVMStatus,
 };
 
 use serde::{Deserialize, Serialize};
@@ -299,6 +301,38 @@ pub struct PendingTransaction {
     pub request: UserTransactionRequest,
 }
 
+/// The outcome of submitting one of the transactions of a batch to the mempool.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionSubmissionStatus {
+    pub hash: HashValue,
+    /// The mempool status code, e.g. `Accepted` or `VmError`.
+    pub mempool_status: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub message: String,
+    /// The status of a transaction that failed the VM validation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vm_status: Option<String>,
+}
+
+impl TransactionSubmissionStatus {
+    pub fn new(
+        hash: aptos_crypto::HashValue,
+        mempool_status: &MempoolStatus,
+        vm_status: Option<DiscardedVMStatus>,
+    ) -> Self {
+        Self {
+            hash: hash.into(),
+            mempool_status: mempool_status.code.to_string(),
+            message: mempool_status.message.clone(),
+            vm_status: vm_status.map(|s| format!("{:?}", s)),
+        }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        self.mempool_status == MempoolStatusCode::Accepted.to_string()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct UserTransaction {
     #[serde(flatten)]
@@ -501,6 +535,16 @@ impl WriteSetChange {
     }
 }
 
+/// A change of a resource made by the write set of the transaction at `version`, along with the
+/// resource as it was before the transaction, if it existed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResourceChange {
+    pub version: U64,
+    pub previous_data: Option<MoveResource>,
+    #[serde(flatten)]
+    pub change: WriteSetChange,
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TransactionSignature {
@@ -11,7 +11,7 @@ use crate::{
         charge_global_write_gas_usage, get_transaction_output, AptosVMImpl, AptosVMInternals,
     },
     counters::*,
-    data_cache::StateViewCache,
+    data_cache::{AsMoveResolver, StateViewCache},
     errors::expect_only_successful_execution,
     logging::AdapterLogSchema,
     move_vm_ext::{MoveResolverExt, SessionExt, SessionId},
@@ -29,9 +29,9 @@ use aptos_types::{
     block_metadata::BlockMetadata,
     on_chain_config::{VMConfig, VMPublishingOption, Version},
     transaction::{
-        ChangeSet, ExecutionStatus, ModuleBundle, SignatureCheckedTransaction, SignedTransaction,
-        Transaction, TransactionOutput, TransactionPayload, TransactionStatus, VMValidatorResult,
-        WriteSetPayload,
+        ChangeSet, ExecutionStatus, ModuleBundle, ScriptFunction, SignatureCheckedTransaction,
+        SignedTransaction, Transaction, TransactionOutput, TransactionPayload, TransactionStatus,
+        VMValidatorResult, WriteSetPayload,
     },
     vm_status::{StatusCode, VMStatus},
     write_set::{WriteSet, WriteSetMut},
@@ -803,6 +803,50 @@ impl AptosVM {
         BLOCK_TRANSACTION_COUNT.observe(count as f64);
         Ok(res)
     }
+
+    /// Executes a user transaction against `state_view` without checking its signature, so that
+    /// clients can dry-run a transaction before signing it. The returned output is never
+    /// committed and its write set is not applied to `state_view`.
+    pub fn simulate_signed_transaction(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutput) {
+        let vm = AptosVM::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let txn = SignatureCheckedTransaction::new_unchecked_for_simulation(txn.clone());
+        vm.execute_user_transaction(&state_view.as_move_resolver(), &txn, &log_context)
+    }
+
+    /// Executes `function` in a session over `state_view` and returns its BCS serialized return
+    /// values. The session is never finished, so any writes made by the function are discarded.
+    /// Execution is metered with the on-chain gas schedule and fails once `max_gas_amount` is
+    /// exhausted.
+    pub fn execute_view_function(
+        state_view: &impl StateView,
+        function: &ScriptFunction,
+        max_gas_amount: u64,
+    ) -> Result<Vec<Vec<u8>>, VMStatus> {
+        let vm = AptosVM::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let gas_schedule = vm.0.get_gas_schedule(&log_context)?;
+        let mut gas_status = GasStatus::new(gas_schedule, GasUnits::new(max_gas_amount));
+        let resolver = state_view.as_move_resolver();
+        let mut session = vm.0.new_session(&resolver, SessionId::void());
+        let return_values = session
+            .execute_function_bypass_visibility(
+                function.module(),
+                function.function(),
+                function.ty_args().to_vec(),
+                function.args().to_vec(),
+                &mut gas_status,
+            )
+            .map_err(|e| e.into_vm_status())?;
+        Ok(return_values
+            .return_values
+            .into_iter()
+            .map(|(bytes, _layout)| bytes)
+            .collect())
+    }
 }
 
 // Executor external API
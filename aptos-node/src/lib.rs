@@ -25,8 +25,9 @@ use aptos_telemetry::{
 };
 use aptos_time_service::TimeService;
 use aptos_types::{
-    account_config::aptos_root_address, account_view::AccountView, chain_id::ChainId,
-    move_resource::MoveStorage, on_chain_config::ON_CHAIN_CONFIG_REGISTRY, waypoint::Waypoint,
+    account_config::aptos_root_address, account_view::AccountView,
+    block_metadata::new_block_event_key, chain_id::ChainId, move_resource::MoveStorage,
+    on_chain_config::ON_CHAIN_CONFIG_REGISTRY, waypoint::Waypoint,
 };
 use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
@@ -585,6 +586,11 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         .subscribe_to_reconfigurations()
         .unwrap();
 
+    // Create an API subscription for new block events, so that streams are notified of new commits
+    let api_commit_subscription = event_subscription_service
+        .subscribe_to_events(vec![new_block_event_key()])
+        .unwrap();
+
     // Create a consensus subscription for reconfiguration events (if this node is a validator).
     let consensus_reconfig_subscription = if node_config.base.role.is_validator() {
         Some(
@@ -715,7 +721,14 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
 
     let (mp_client_sender, mp_client_events) = channel(AC_SMP_CHANNEL_BUFFER_SIZE);
 
-    let api_runtime = bootstrap_api(node_config, chain_id, aptos_db, mp_client_sender).unwrap();
+    let api_runtime = bootstrap_api(
+        node_config,
+        chain_id,
+        aptos_db,
+        mp_client_sender,
+        Some(api_commit_subscription),
+    )
+    .unwrap();
 
     let mut consensus_runtime = None;
     let (consensus_to_mempool_sender, consensus_to_mempool_receiver) =
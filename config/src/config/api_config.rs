@@ -18,11 +18,19 @@ pub struct ApiConfig {
     // optional for compatible with old configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_length_limit: Option<u64>,
+    /// Maximum gas units a single view function execution may consume
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_view_function_gas: Option<u64>,
+    /// Maximum number of transactions submitted in a single batch request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_submit_transaction_batch_size: Option<usize>,
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
 pub const DEFAULT_PORT: u16 = 8080;
 pub const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 4 * 1024 * 1024; // 4mb
+pub const DEFAULT_MAX_VIEW_FUNCTION_GAS: u64 = 1_000_000;
+pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 100;
 
 fn default_enabled() -> bool {
     true
@@ -38,6 +46,8 @@ impl Default for ApiConfig {
             tls_cert_path: None,
             tls_key_path: None,
             content_length_limit: None,
+            max_view_function_gas: None,
+            max_submit_transaction_batch_size: None,
         }
     }
 }
@@ -53,4 +63,14 @@ impl ApiConfig {
             None => DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT,
         }
     }
+
+    pub fn max_view_function_gas(&self) -> u64 {
+        self.max_view_function_gas
+            .unwrap_or(DEFAULT_MAX_VIEW_FUNCTION_GAS)
+    }
+
+    pub fn max_submit_transaction_batch_size(&self) -> usize {
+        self.max_submit_transaction_batch_size
+            .unwrap_or(DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE)
+    }
 }
@@ -0,0 +1,100 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A pool of accounts which mint in parallel, each with its own sequence number.
+
+use aptos_sdk::types::{account_address::AccountAddress, LocalAccount};
+use futures::lock::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use structopt::StructOpt;
+
+#[derive(Clone, Debug, StructOpt)]
+pub struct FunderPoolConfig {
+    /// Number of delegated accounts minting in parallel, ignored with `do_not_delegate`
+    #[structopt(long = "num-funders", default_value = "1")]
+    pub num_funders: usize,
+    /// Balance below which a funder is topped up, so that it can keep paying for gas
+    #[structopt(long = "funder-minimum-balance", default_value = "10000000")]
+    pub minimum_balance: u64,
+    /// Amount of coins a funder is given when created and when topped up
+    #[structopt(long = "funder-top-up-amount", default_value = "100000000000")]
+    pub top_up_amount: u64,
+}
+
+impl Default for FunderPoolConfig {
+    fn default() -> Self {
+        Self {
+            num_funders: 1,
+            minimum_balance: 10_000_000,
+            top_up_amount: 100_000_000_000,
+        }
+    }
+}
+
+pub struct FunderPool {
+    funders: Vec<Mutex<LocalAccount>>,
+    addresses: Vec<AccountAddress>,
+    next: AtomicUsize,
+}
+
+impl FunderPool {
+    pub fn new(funders: Vec<LocalAccount>) -> Self {
+        assert!(!funders.is_empty(), "The funder pool can't be empty");
+        Self {
+            addresses: funders.iter().map(LocalAccount::address).collect(),
+            funders: funders.into_iter().map(Mutex::new).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn addresses(&self) -> &[AccountAddress] {
+        &self.addresses
+    }
+
+    /// Locks an idle funder, or waits for the next one in turn if they're all busy
+    ///
+    /// The search starts after the last funder handed out, so that the load is spread over the
+    /// pool even when it's mostly idle.
+    pub async fn acquire(&self) -> MutexGuard<'_, LocalAccount> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        for offset in 0..self.funders.len() {
+            let index = (start + offset) % self.funders.len();
+            if let Some(funder) = self.funders[index].try_lock() {
+                return funder;
+            }
+        }
+        self.funders[start % self.funders.len()].lock().await
+    }
+
+    /// Locks the funder at `address`, if it's in the pool
+    pub async fn lock(&self, address: AccountAddress) -> Option<MutexGuard<'_, LocalAccount>> {
+        let index = self.addresses.iter().position(|a| *a == address)?;
+        Some(self.funders[index].lock().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_acquire_idle_funder() {
+        let mut rng = rand::rngs::OsRng;
+        let pool = FunderPool::new(vec![
+            LocalAccount::generate(&mut rng),
+            LocalAccount::generate(&mut rng),
+        ]);
+
+        // A busy funder is skipped
+        let first = pool.acquire().await;
+        let second = pool.acquire().await;
+        assert_ne!(first.address(), second.address());
+        drop(first);
+
+        // Idle funders are handed out in turn
+        let second_address = second.address();
+        drop(second);
+        assert_ne!(pool.acquire().await.address(), second_address);
+        assert_eq!(pool.acquire().await.address(), second_address);
+    }
+}
@@ -20,10 +20,10 @@
 //! ```
 
 use anyhow::Result;
-use aptos::common::types::EncodingType;
 use aptos_config::keys::ConfigKey;
 use aptos_crypto::ed25519::Ed25519PrivateKey;
-use aptos_logger::info;
+use aptos_logger::{info, warn};
+use aptos_metrics_core::{Encoder, TextEncoder};
 use aptos_rest_client::Client;
 use aptos_sdk::{
     transaction_builder::{aptos_stdlib, TransactionFactory},
@@ -32,14 +32,23 @@ use aptos_sdk::{
         LocalAccount,
     },
 };
-use futures::lock::Mutex;
 use reqwest::StatusCode;
-use std::{convert::Infallible, fmt, path::Path, sync::Arc};
+use std::{collections::BTreeMap, convert::Infallible, fmt, path::Path, sync::Arc, time::Duration};
 use structopt::StructOpt;
 use url::Url;
 use warp::{http, Filter, Rejection, Reply};
 
+pub mod funder_pool;
 pub mod mint;
+pub mod rate_limit;
+
+use funder_pool::{FunderPool, FunderPoolConfig};
+use rate_limit::{RateLimitConfig, RateLimiter};
+
+/// How often the funders' balances are checked for a top up
+const FUNDER_TOP_UP_INTERVAL: Duration = Duration::from_secs(30);
+/// How long to wait before retrying to create a funder which failed to be created
+const FUNDER_CREATION_RETRY_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Debug, StructOpt)]
 #[structopt(
@@ -82,6 +91,10 @@ pub struct FaucetArgs {
     pub maximum_amount: Option<u64>,
     #[structopt(long)]
     pub do_not_delegate: bool,
+    #[structopt(flatten)]
+    pub rate_limit_config: RateLimitConfig,
+    #[structopt(flatten)]
+    pub funder_pool_config: FunderPoolConfig,
 }
 
 impl FaucetArgs {
@@ -91,18 +104,19 @@ impl FaucetArgs {
             .expect("invalid address or port number");
 
         info!(
-            "[faucet]: chain id: {}, server url: {} . Limit: {:?}",
+            "[faucet]: chain id: {}, server url: {} . Limit: {:?}, rate limits: {:?}",
             self.chain_id,
             self.server_url.as_str(),
             self.maximum_amount,
+            self.rate_limit_config,
         );
 
         let key = if let Some(ref key) = self.mint_key {
             key.private_key()
         } else {
-            EncodingType::BCS
-                .load_key::<Ed25519PrivateKey>("mint key", Path::new(&self.mint_key_file_path))
-                .unwrap()
+            let bytes = std::fs::read(Path::new(&self.mint_key_file_path))
+                .expect("Unable to read mint key file");
+            bcs::from_bytes::<Ed25519PrivateKey>(&bytes).expect("Invalid mint key")
         };
 
         let faucet_address: AccountAddress =
@@ -117,61 +131,76 @@ impl FaucetArgs {
             None
         };
 
-        let service = Arc::new(Service::new(
+        let service = Service::new(
             self.server_url.clone(),
             self.chain_id,
-            faucet_account,
+            vec![faucet_account],
             maximum_amount,
-        ));
+        );
+        let rate_limiter = RateLimiter::new(self.rate_limit_config);
 
         let actual_service = if self.do_not_delegate {
-            service
+            Arc::new(service.with_rate_limiter(rate_limiter))
         } else {
-            delegate_mint_account(
-                service,
+            delegate_mint_accounts(
+                Arc::new(service),
                 self.server_url.clone(),
                 self.chain_id,
                 self.maximum_amount,
+                rate_limiter,
+                &self.funder_pool_config,
             )
             .await
         };
 
         info!(
-            "[faucet]: running on: {}. Minting from {}",
+            "[faucet]: running on: {}. Minting from {:?}",
             address,
-            actual_service.faucet_account.lock().await.address()
+            actual_service.funders.addresses()
         );
+        tokio::spawn(top_up_funders(
+            actual_service.clone(),
+            self.funder_pool_config,
+        ));
         warp::serve(routes(actual_service)).run(address).await;
     }
 }
 
 pub struct Service {
-    pub faucet_account: Mutex<LocalAccount>,
+    pub funders: FunderPool,
     transaction_factory: TransactionFactory,
     client: Client,
     endpoint: String,
     maximum_amount: Option<u64>,
+    rate_limiter: RateLimiter,
 }
 
 impl Service {
     pub fn new(
         endpoint: String,
         chain_id: ChainId,
-        faucet_account: LocalAccount,
+        funders: Vec<LocalAccount>,
         maximum_amount: Option<u64>,
     ) -> Self {
         let client = Client::new(Url::parse(&endpoint).expect("Invalid rest endpoint"));
         Service {
-            faucet_account: Mutex::new(faucet_account),
+            funders: FunderPool::new(funders),
             transaction_factory: TransactionFactory::new(chain_id)
                 .with_gas_unit_price(1)
                 .with_transaction_expiration_time(30),
             client,
             endpoint,
             maximum_amount,
+            rate_limiter: RateLimiter::open(),
         }
     }
 
+    /// Throttles the mint requests with `rate_limiter`, instead of serving all of them
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
     pub fn endpoint(&self) -> &String {
         &self.endpoint
     }
@@ -184,6 +213,7 @@ pub fn routes(
     let health = health_route(service);
 
     health
+        .or(metrics_route())
         .or(mint)
         .with(warp::log::custom(|info| {
             info!(
@@ -209,17 +239,36 @@ pub fn routes(
 fn health_route(
     service: Arc<Service>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let funders_service = service.clone();
     warp::path!("health")
         .and(warp::get())
         .and(warp::any().map(move || service.clone()))
         .and_then(handle_health)
+        .or(warp::path!("health" / "funders")
+            .and(warp::get())
+            .and(warp::any().map(move || funders_service.clone()))
+            .and_then(handle_funders_health))
 }
 
+fn metrics_route() -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("metrics").and(warp::get()).map(|| {
+        let mut buffer = vec![];
+        match TextEncoder::new().encode(&aptos_metrics_core::gather(), &mut buffer) {
+            Ok(()) => Box::new(buffer) as Box<dyn warp::Reply>,
+            Err(err) => Box::new(warp::reply::with_status(
+                err.to_string(),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )),
+        }
+    })
+}
+
+/// Replies with the sequence number of the first funder
 async fn handle_health(service: Arc<Service>) -> Result<Box<dyn warp::Reply>, Infallible> {
-    let faucet_address = service.faucet_account.lock().await.address();
-    let faucet_account = service.client.get_account(faucet_address).await;
+    let funder_address = service.funders.addresses()[0];
+    let funder_account = service.client.get_account(funder_address).await;
 
-    match faucet_account {
+    match funder_account {
         Ok(account) => Ok(Box::new(account.inner().sequence_number.to_string())),
         Err(err) => Ok(Box::new(warp::reply::with_status(
             err.to_string(),
@@ -228,6 +277,35 @@ async fn handle_health(service: Arc<Service>) -> Result<Box<dyn warp::Reply>, In
     }
 }
 
+/// Checks that all the funders exist, and replies with the sequence number of each of them, keyed
+/// by address
+async fn handle_funders_health(service: Arc<Service>) -> Result<Box<dyn warp::Reply>, Infallible> {
+    let client = &service.client;
+    let requests = service
+        .funders
+        .addresses()
+        .iter()
+        .map(|address| async move {
+            let account = client
+                .get_account(*address)
+                .await
+                .map_err(|err| anyhow::format_err!("funder {}: {}", address, err))?;
+            Result::<_>::Ok((address.to_hex_literal(), account.inner().sequence_number))
+        });
+    let sequence_numbers: Result<BTreeMap<_, _>> = futures::future::join_all(requests)
+        .await
+        .into_iter()
+        .collect();
+
+    match sequence_numbers {
+        Ok(sequence_numbers) => Ok(Box::new(warp::reply::json(&sequence_numbers))),
+        Err(err) => Ok(Box::new(warp::reply::with_status(
+            err.to_string(),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ))),
+    }
+}
+
 //
 // Common Types
 //
@@ -244,23 +322,49 @@ impl<T: fmt::Display> fmt::Display for OptFmt<T> {
     }
 }
 
-/// The idea is that this may be happening concurrently. If we end up in such a race, the faucets
-/// might attempt to send transactions with the same sequence number, in such an event, one will
-/// succeed and the other will hit an unwrap. Eventually all faucets should get online.
-pub async fn delegate_mint_account(
+/// Creates the funders concurrently. They all take their sequence numbers from the funder of
+/// `service`, so a failed submission may make a concurrent one fail too. A funder which fails to be
+/// created is logged and retried with a new account, until the pool is full.
+pub async fn delegate_mint_accounts(
     service: Arc<Service>,
     server_url: String,
     chain_id: ChainId,
     maximum_amount: Option<u64>,
+    rate_limiter: RateLimiter,
+    funder_pool_config: &FunderPoolConfig,
 ) -> Arc<Service> {
-    // Create a new random account, then delegate to it
+    let (service, top_up_amount) = (&service, funder_pool_config.top_up_amount);
+    let requests = (0..funder_pool_config.num_funders).map(|_| async move {
+        loop {
+            match delegate_mint_account(service, top_up_amount).await {
+                Ok(account) => return account,
+                Err(err) => {
+                    warn!(
+                        "[faucet]: failed to create a funder, retrying in {:?}: {}",
+                        FUNDER_CREATION_RETRY_INTERVAL, err
+                    );
+                    tokio::time::sleep(FUNDER_CREATION_RETRY_INTERVAL).await;
+                }
+            }
+        }
+    });
+    let delegated_accounts = futures::future::join_all(requests).await;
+
+    Arc::new(
+        Service::new(server_url, chain_id, delegated_accounts, maximum_amount)
+            .with_rate_limiter(rate_limiter),
+    )
+}
+
+/// Creates a new random account funded with `amount` coins, and delegates minting to it
+async fn delegate_mint_account(service: &Service, amount: u64) -> Result<LocalAccount> {
     let mut delegated_account = LocalAccount::generate(&mut rand::rngs::OsRng);
 
     // Create the account
     let response = mint::process(
-        &service,
+        service,
         mint::MintParams {
-            amount: 100_000_000_000,
+            amount,
             auth_key: None,
             address: Some(
                 delegated_account
@@ -273,36 +377,28 @@ pub async fn delegate_mint_account(
             return_txns: Some(true),
         },
     )
-    .await
-    .unwrap();
+    .await?;
 
     match response {
         mint::Response::SubmittedTxns(txns) => {
             for txn in txns {
-                service
-                    .client
-                    .wait_for_signed_transaction(&txn)
-                    .await
-                    .unwrap();
+                service.client.wait_for_signed_transaction(&txn).await?;
             }
         }
-        _ => panic!("Expected a set of Response::SubmittedTxns"),
+        _ => anyhow::bail!("Expected a set of Response::SubmittedTxns"),
     }
 
     // Delegate minting to the account
-    {
-        let mut faucet_account = service.faucet_account.lock().await;
-        service
-            .client
-            .submit_and_wait(&faucet_account.sign_with_transaction_builder(
-                service.transaction_factory.payload(
-                    aptos_stdlib::encode_test_coin_delegate_mint_capability(
-                        delegated_account.address(),
-                    ),
-                ),
-            ))
-            .await
-            .unwrap();
+    let txn = service
+        .funders
+        .acquire()
+        .await
+        .sign_with_transaction_builder(service.transaction_factory.payload(
+            aptos_stdlib::encode_test_coin_delegate_mint_capability(delegated_account.address()),
+        ));
+    if let Err(err) = service.client.submit_and_wait(&txn).await {
+        mint::resync_sequence_number(service, txn.sender()).await;
+        return Err(err);
     }
 
     // claim the capability!
@@ -315,13 +411,54 @@ pub async fn delegate_mint_account(
                     .payload(aptos_stdlib::encode_test_coin_claim_mint_capability()),
             ),
         )
+        .await?;
+
+    Ok(delegated_account)
+}
+
+/// Periodically mints coins to the funders whose balance runs low, so that they can keep paying
+/// for gas
+pub async fn top_up_funders(service: Arc<Service>, funder_pool_config: FunderPoolConfig) {
+    loop {
+        tokio::time::sleep(FUNDER_TOP_UP_INTERVAL).await;
+        for address in service.funders.addresses() {
+            if let Err(err) = top_up_funder(&service, *address, &funder_pool_config).await {
+                warn!("[faucet]: failed to top up funder {}: {}", address, err);
+            }
+        }
+    }
+}
+
+async fn top_up_funder(
+    service: &Service,
+    address: AccountAddress,
+    funder_pool_config: &FunderPoolConfig,
+) -> Result<()> {
+    let balance = service
+        .client
+        .get_account_balance(address)
+        .await?
+        .inner()
+        .get();
+    if balance >= funder_pool_config.minimum_balance {
+        return Ok(());
+    }
+
+    info!(
+        "[faucet]: funder {} balance {} is low, minting {}",
+        address, balance, funder_pool_config.top_up_amount
+    );
+    let txn = service
+        .funders
+        .lock(address)
         .await
-        .unwrap();
-
-    Arc::new(Service::new(
-        server_url,
-        chain_id,
-        delegated_account,
-        maximum_amount,
-    ))
+        .ok_or_else(|| anyhow::format_err!("funder {} is not in the pool", address))?
+        .sign_with_transaction_builder(service.transaction_factory.payload(
+            aptos_stdlib::encode_test_coin_mint(address, funder_pool_config.top_up_amount),
+        ));
+    if let Err(err) = service.client.submit_and_wait(&txn).await {
+        mint::resync_sequence_number(service, address).await;
+        return Err(err);
+    }
+    Ok(())
 }
@@ -8,14 +8,27 @@ use structopt::StructOpt;
 async fn main() {
     aptos_logger::Logger::new().init();
     let args: FaucetArgs = FaucetArgs::from_args();
+    if let Err(err) = args.rate_limit_config.validate() {
+        structopt::clap::Error::with_description(
+            &err.to_string(),
+            structopt::clap::ErrorKind::ValueValidation,
+        )
+        .exit();
+    }
     args.run().await
 }
 
 #[cfg(test)]
 mod tests {
-    use aptos::op::key::GenerateKey;
-    use aptos_crypto::{ed25519::Ed25519PublicKey, hash::HashValue, PrivateKey};
-    use aptos_faucet::{routes, Service};
+    use aptos_crypto::{
+        ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+        hash::HashValue,
+        PrivateKey, Uniform,
+    };
+    use aptos_faucet::{
+        rate_limit::{RateLimitConfig, RateLimiter},
+        routes, Service,
+    };
     use aptos_infallible::RwLock;
     use aptos_rest_client::{
         aptos_api_types::{
@@ -36,10 +49,12 @@ mod tests {
             LocalAccount,
         },
     };
+    use rand::{rngs::StdRng, SeedableRng};
     use serde::Serialize;
     use std::{
         collections::HashMap,
         convert::{Infallible, TryFrom, TryInto},
+        net::{IpAddr, SocketAddr},
         sync::{Arc, Mutex},
     };
     use tokio::task::yield_now;
@@ -69,18 +84,31 @@ mod tests {
         }
     }
 
-    fn setup(maximum_amount: Option<u64>) -> (AccountStates, Arc<Service>) {
-        let key = GenerateKey::generate_ed25519_in_memory();
-        let account_address = AuthenticationKey::ed25519(&key.public_key()).derived_address();
+    /// The stub node rejects transactions this far ahead of the sequence number of their sender
+    const MAX_SEQUENCE_NUMBER_GAP: u64 = 10;
 
-        let faucet_account = LocalAccount::new(account_address, key, 0);
+    fn setup(maximum_amount: Option<u64>) -> (AccountStates, Arc<Service>) {
+        setup_with_funders(maximum_amount, 1)
+    }
 
+    fn setup_with_funders(
+        maximum_amount: Option<u64>,
+        num_funders: usize,
+    ) -> (AccountStates, Arc<Service>) {
         let chain_id = ChainId::test();
 
         let accounts = AccountStates::new(aptos_infallible::RwLock::new(HashMap::new()));
-        accounts
-            .write()
-            .insert(account_address, AccountState::new(0));
+        let funders = (0..num_funders)
+            .map(|_| {
+                let key = Ed25519PrivateKey::generate(&mut StdRng::from_entropy());
+                let account_address =
+                    AuthenticationKey::ed25519(&key.public_key()).derived_address();
+                accounts
+                    .write()
+                    .insert(account_address, AccountState::new(0));
+                LocalAccount::new(account_address, key, 0)
+            })
+            .collect();
 
         let last_txn = Arc::new(Mutex::new(None));
         let last_txn_0 = last_txn.clone();
@@ -112,7 +140,7 @@ mod tests {
         let service = Service::new(
             format!("http://localhost:{}/", address.port()),
             chain_id,
-            faucet_account,
+            funders,
             maximum_amount,
         );
         (accounts, Arc::new(service))
@@ -183,6 +211,16 @@ mod tests {
         if let Script(script) = txn.payload() {
             panic!("unexpected type of script: {:?}", script.args())
         }
+        if let Some(sender) = accounts.write().get_mut(&txn.sender()) {
+            if txn.sequence_number() > sender.sequence_number + MAX_SEQUENCE_NUMBER_GAP {
+                return Err(warp::reject::custom(Error::new(
+                    StatusCode::BAD_REQUEST,
+                    "SEQUENCE_NUMBER_TOO_NEW".to_owned(),
+                )));
+            }
+            sender.sequence_number =
+                std::cmp::max(sender.sequence_number, txn.sequence_number() + 1);
+        }
         if let Some(script_function) = ScriptFunctionCall::decode(txn.payload()) {
             match script_function {
                 ScriptFunctionCall::AccountCreateAccount {
@@ -196,8 +234,11 @@ mod tests {
                     dst_addr, amount, ..
                 } => {
                     // Sometimes we call CreateAccount and Mint at the same time (from our tests: this is a test method)
-                    // If the account doesn't exist yet, we sleep for 100ms to let the other request finish
-                    if accounts.write().get_mut(&dst_addr).is_none() {
+                    // If the account doesn't exist yet, we yield to let the other request finish
+                    for _ in 0..100 {
+                        if accounts.read().contains_key(&dst_addr) {
+                            break;
+                        }
                         yield_now().await;
                     }
                     let mut writer = accounts.write();
@@ -244,6 +285,8 @@ mod tests {
         pub message: String,
     }
 
+    impl warp::reject::Reject for Error {}
+
     impl Error {
         fn new(code: StatusCode, message: String) -> Error {
             Error {
@@ -434,6 +477,195 @@ mod tests {
         assert_eq!(resp.body(), std::string::ToString::to_string(&0).as_str());
     }
 
+    #[tokio::test]
+    async fn test_funders_health() {
+        let (_accounts, service) = setup_with_funders(None, 2);
+        let addresses = service.funders.addresses().to_vec();
+
+        let resp = warp::test::request()
+            .method("GET")
+            .path("/health/funders")
+            .reply(&routes(service))
+            .await;
+
+        assert_eq!(resp.status(), 200);
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(resp.body()).unwrap(),
+            serde_json::json!({
+                addresses[0].to_hex_literal(): 0,
+                addresses[1].to_hex_literal(): 0,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_funders_health_funder_not_found() {
+        let (accounts, service) = setup_with_funders(None, 2);
+        let address = service.funders.addresses()[1];
+        accounts.write().remove(&address);
+
+        let resp = warp::test::request()
+            .method("GET")
+            .path("/health/funders")
+            .reply(&routes(service))
+            .await;
+
+        assert_eq!(resp.status(), 500);
+        assert!(std::str::from_utf8(resp.body())
+            .unwrap()
+            .starts_with(&format!("funder {}", address)));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_mints_are_spread_over_funders() {
+        let (accounts, service) = setup_with_funders(None, 2);
+        let addresses = service.funders.addresses().to_vec();
+        let filter = routes(service);
+
+        let requests = (0..4u8).map(|i| {
+            let receiver = AccountAddress::new([i + 1; AccountAddress::LENGTH]);
+            warp::test::request()
+                .method("POST")
+                .path(&format!("/mint?address={}&amount=10", receiver))
+                .reply(&filter)
+        });
+        for resp in futures::future::join_all(requests).await {
+            assert_eq!(resp.status(), 200);
+        }
+
+        // each mint creates the receiver and funds it, with two transactions of the same funder
+        let reader = accounts.read();
+        let sequence_numbers: Vec<_> = addresses
+            .iter()
+            .map(|address| reader.get(address).unwrap().sequence_number)
+            .collect();
+        assert_eq!(sequence_numbers, vec![4, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_submission_resyncs_sequence_number() {
+        let (accounts, service) = setup(None);
+        let address = service.funders.addresses()[0];
+        // the funder is ahead of the chain by more than the node accepts
+        *service
+            .funders
+            .lock(address)
+            .await
+            .unwrap()
+            .sequence_number_mut() = MAX_SEQUENCE_NUMBER_GAP + 1;
+        let filter = routes(service.clone());
+
+        let auth_key = "459c77a38803bd53f3adee52703810e3a74fd7c46952c497e75afb0a7932586d";
+        let resp = warp::test::request()
+            .method("POST")
+            .path(format!("/mint?auth_key={}&amount=10", auth_key).as_str())
+            .reply(&filter)
+            .await;
+        assert_eq!(resp.status(), 500);
+        assert_eq!(
+            service
+                .funders
+                .lock(address)
+                .await
+                .unwrap()
+                .sequence_number(),
+            0
+        );
+
+        // once resynced, the next mint goes through
+        let resp = warp::test::request()
+            .method("POST")
+            .path(format!("/mint?auth_key={}&amount=10", auth_key).as_str())
+            .reply(&filter)
+            .await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(accounts.read().get(&address).unwrap().sequence_number, 2);
+    }
+
+    /// A service letting a single mint request through per client IP
+    fn setup_rate_limited(trusted_proxy: Vec<IpAddr>) -> Arc<Service> {
+        let (_accounts, service) = setup(None);
+        let service = Arc::try_unwrap(service).ok().unwrap();
+        Arc::new(service.with_rate_limiter(RateLimiter::new(RateLimitConfig {
+            ip_bucket_size: Some(1),
+            trusted_proxy,
+            ..RateLimitConfig::default()
+        })))
+    }
+
+    /// Mints to a new address from `remote`, through a proxy setting `forwarded_for` if given
+    async fn mint_from<F>(
+        filter: &F,
+        receiver: u8,
+        remote: &str,
+        forwarded_for: Option<&str>,
+    ) -> warp::http::Response<bytes::Bytes>
+    where
+        F: Filter + 'static,
+        F::Extract: Reply + Send,
+    {
+        let receiver = AccountAddress::new([receiver; AccountAddress::LENGTH]);
+        let mut request = warp::test::request()
+            .method("POST")
+            .path(&format!("/mint?address={}&amount=10", receiver))
+            .remote_addr(SocketAddr::new(remote.parse().unwrap(), 1234));
+        if let Some(forwarded_for) = forwarded_for {
+            request = request.header("x-forwarded-for", forwarded_for);
+        }
+        request.reply(filter).await
+    }
+
+    #[tokio::test]
+    async fn test_mint_rate_limited() {
+        let filter = routes(setup_rate_limited(vec![]));
+
+        assert_eq!(mint_from(&filter, 1, "1.1.1.1", None).await.status(), 200);
+        let resp = mint_from(&filter, 2, "1.1.1.1", None).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = resp.headers()[header::RETRY_AFTER]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(retry_after >= 1);
+
+        // other clients have their own bucket
+        assert_eq!(mint_from(&filter, 3, "2.2.2.2", None).await.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_mint_rate_limited_behind_proxy() {
+        let filter = routes(setup_rate_limited(vec!["10.0.0.1".parse().unwrap()]));
+
+        // a trusted proxy identifies the clients
+        let resp = mint_from(&filter, 1, "10.0.0.1", Some("1.1.1.1")).await;
+        assert_eq!(resp.status(), 200);
+        let resp = mint_from(&filter, 2, "10.0.0.1", Some("2.2.2.2")).await;
+        assert_eq!(resp.status(), 200);
+        let resp = mint_from(&filter, 3, "10.0.0.1", Some("1.1.1.1")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // an untrusted one is the client itself, whatever it forwards
+        let resp = mint_from(&filter, 4, "10.0.0.2", Some("3.3.3.3")).await;
+        assert_eq!(resp.status(), 200);
+        let resp = mint_from(&filter, 5, "10.0.0.2", Some("4.4.4.4")).await;
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_mint_is_not_rate_limited() {
+        let filter = routes(setup_rate_limited(vec![]));
+
+        let resp = warp::test::request()
+            .method("POST")
+            .path("/mint?auth_key=invalid-auth-key&amount=10")
+            .remote_addr(SocketAddr::new("1.1.1.1".parse().unwrap(), 1234))
+            .reply(&filter)
+            .await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(mint_from(&filter, 1, "1.1.1.1", None).await.status(), 200);
+    }
+
     #[tokio::test]
     async fn test_mint_invalid_auth_key() {
         let (_accounts, service) = setup(None);
@@ -454,7 +686,7 @@ mod tests {
     #[tokio::test]
     async fn test_mint_fullnode_error() {
         let (accounts, service) = setup(None);
-        let address = service.faucet_account.lock().await.address();
+        let address = service.funders.addresses()[0];
         accounts.write().remove(&address);
         let filter = routes(service);
 
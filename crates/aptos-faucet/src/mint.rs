@@ -14,7 +14,7 @@ use aptos_sdk::{
 };
 use reqwest::StatusCode;
 use serde::Deserialize;
-use std::{convert::Infallible, fmt, sync::Arc};
+use std::{convert::Infallible, fmt, net::SocketAddr, sync::Arc};
 use warp::{Filter, Rejection, Reply};
 
 pub fn mint_routes(
@@ -27,13 +27,46 @@ pub fn mint_routes(
         .and(warp::post())
         .and(warp::any().map(move || service.clone()))
         .and(warp::query().map(move |params: MintParams| params))
-        .and_then(|_, service, params| handle(service, params))
+        .and(warp::addr::remote())
+        .and(warp::header::optional::<String>("x-forwarded-for"))
+        .and_then(|_, service, params, remote, forwarded_for| {
+            handle(service, params, remote, forwarded_for)
+        })
 }
 
 async fn handle(
     service: Arc<Service>,
     params: MintParams,
+    remote: Option<SocketAddr>,
+    forwarded_for: Option<String>,
 ) -> Result<Box<dyn warp::Reply>, Infallible> {
+    // Invalid requests are rejected before taking a token, so they don't count against the client
+    let receiver = match params.receiver_or_err() {
+        Ok(receiver) => receiver,
+        Err(err) => {
+            return Ok(Box::new(warp::reply::with_status(
+                err.to_string(),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )))
+        }
+    };
+    let rate_limiter = &service.rate_limiter;
+    let client_ip = rate_limiter.client_ip(remote, forwarded_for.as_deref());
+    if let Err(retry_after) = rate_limiter.check(client_ip, Some(receiver)) {
+        warn!(
+            "Rate limited {} from {:?}, retry after {:?}",
+            params, client_ip, retry_after
+        );
+        return Ok(Box::new(warp::reply::with_header(
+            warp::reply::with_status(
+                "Too many requests, try again later".to_string(),
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            "Retry-After",
+            retry_after.as_secs().to_string(),
+        )));
+    }
+
     match process(&service, params).await {
         Ok(body) => Ok(Box::new(body.to_string())),
         Err(err) => Ok(Box::new(warp::reply::with_status(
@@ -102,89 +135,95 @@ impl MintParams {
         }
         None
     }
+
+    fn receiver_or_err(&self) -> Result<AccountAddress> {
+        self.receiver().ok_or_else(|| {
+            anyhow::format_err!("You must provide 'address' (preferred), 'pub_key', or 'auth_key'")
+        })
+    }
 }
 
 pub async fn process(service: &Service, params: MintParams) -> Result<Response> {
     let maybe_maximum_amount = service.maximum_amount.unwrap_or(params.amount);
     let amount = std::cmp::min(params.amount, maybe_maximum_amount);
 
-    let receiver_address = params.receiver().ok_or_else(|| {
-        anyhow::format_err!("You must provide 'address' (preferred), 'pub_key', or 'auth_key'")
-    })?;
+    let receiver_address = params.receiver_or_err()?;
+
+    // Requests are spread over the funders. A funder is only locked while its sequence number is
+    // read or bumped, never across network calls, so one slow request doesn't stall the others
+    let funder_address = service.funders.acquire().await.address();
 
-    let (mut faucet_seq, mut receiver_seq) = sequences(service, receiver_address).await?;
-    let our_faucet_seq = {
-        let mut faucet_account = service.faucet_account.lock().await;
+    let mut attempts = 0;
+    let (mut funder, receiver_seq) = loop {
+        let (funder_seq, receiver_seq) =
+            sequences(service, funder_address, receiver_address).await?;
+        let mut funder =
+            service.funders.lock(funder_address).await.ok_or_else(|| {
+                anyhow::format_err!("funder {} is not in the pool", funder_address)
+            })?;
 
         // If the onchain sequence_number is greater than what we have, update our
         // sequence_numbers
-        if faucet_seq > faucet_account.sequence_number() {
-            *faucet_account.sequence_number_mut() = faucet_seq;
+        if funder_seq > funder.sequence_number() {
+            *funder.sequence_number_mut() = funder_seq;
+        }
+
+        // We shouldn't have too many outstanding txns
+        if funder.sequence_number() < funder_seq + 50 {
+            break (funder, receiver_seq);
         }
-        faucet_account.sequence_number()
-    };
 
-    // We shouldn't have too many outstanding txns
-    for _ in 0..60 {
-        if our_faucet_seq < faucet_seq + 50 {
-            break;
+        // After 30 seconds, we still have not caught up, we are likely unhealthy
+        if attempts == 60 {
+            error!("We are unhealthy, transactions have likely expired.");
+            info!(
+                "Resetting the sequence number counter of {}.",
+                funder_address
+            );
+            *funder.sequence_number_mut() = funder_seq;
+            break (funder, receiver_seq);
         }
+
         warn!(
-            "We have too many outstanding transactions: {}. Sleeping to let the system catchup.",
-            (our_faucet_seq - faucet_seq)
+            "We have too many outstanding transactions on {}: {}. Sleeping to let the system catchup.",
+            funder_address,
+            (funder.sequence_number() - funder_seq)
         );
-
+        drop(funder);
+        attempts += 1;
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        let (lhs, rhs) = sequences(service, receiver_address).await?;
-        faucet_seq = lhs;
-        receiver_seq = rhs;
-    }
-
-    // After 30 seconds, we still have not caught up, we are likely unhealthy
-    if our_faucet_seq >= faucet_seq + 50 {
-        error!("We are unhealthy, transactions have likely expired.");
-        let mut faucet_account = service.faucet_account.lock().await;
-        if faucet_account.sequence_number() >= faucet_seq + 50 {
-            info!("Resetting the sequence number counter.");
-            *faucet_account.sequence_number_mut() = faucet_seq;
-        } else {
-            info!("Someone else reset the sequence number counter ahead of us.");
-        }
-    }
+    };
 
     let mut txns = vec![];
 
-    {
-        let mut faucet_account = service.faucet_account.lock().await;
-
-        if receiver_seq.is_none() {
-            let builder =
-                service
-                    .transaction_factory
-                    .payload(aptos_stdlib::encode_account_create_account(
-                        receiver_address,
-                    ));
+    if receiver_seq.is_none() {
+        let builder =
+            service
+                .transaction_factory
+                .payload(aptos_stdlib::encode_account_create_account(
+                    receiver_address,
+                ));
 
-            let txn = faucet_account.sign_with_transaction_builder(builder);
-            txns.push(txn)
-        }
+        let txn = funder.sign_with_transaction_builder(builder);
+        txns.push(txn)
+    }
 
-        if amount != 0 {
-            txns.push(
-                faucet_account.sign_with_transaction_builder(service.transaction_factory.payload(
-                    aptos_stdlib::encode_test_coin_mint(receiver_address, amount),
-                )),
-            );
-        }
+    if amount != 0 {
+        txns.push(
+            funder.sign_with_transaction_builder(service.transaction_factory.payload(
+                aptos_stdlib::encode_test_coin_mint(receiver_address, amount),
+            )),
+        );
     }
+    drop(funder);
 
     let requests = txns.iter().map(|txn| service.client.submit(txn));
     let mut responses = futures::future::join_all(requests).await;
 
     // If there was an issue submitting a transaction we should just reset our sequence_numbers
-    // to what was on chain
+    // to what is on chain
     if responses.iter().any(Result::is_err) {
-        *service.faucet_account.lock().await.sequence_number_mut() = faucet_seq;
+        resync_sequence_number(service, funder_address).await;
     }
 
     while !responses.is_empty() {
@@ -203,9 +242,36 @@ pub async fn process(service: &Service, params: MintParams) -> Result<Response>
     }
 }
 
-async fn sequences(service: &Service, receiver: AccountAddress) -> Result<(u64, Option<u64>)> {
-    let faucet_address = service.faucet_account.lock().await.address();
-    let f_request = service.client.get_account(faucet_address);
+/// Resets the sequence number of the funder at `address` to the one on chain, after a failed
+/// submission
+pub(crate) async fn resync_sequence_number(service: &Service, address: AccountAddress) {
+    let sequence_number = match service.client.get_account(address).await {
+        Ok(account) => account.inner().sequence_number,
+        Err(err) => {
+            error!(
+                "Unable to resync the sequence number of {}: {}",
+                address, err
+            );
+            return;
+        }
+    };
+    if let Some(mut funder) = service.funders.lock(address).await {
+        info!(
+            "Resyncing the sequence number of {} from {} to {}.",
+            address,
+            funder.sequence_number(),
+            sequence_number
+        );
+        *funder.sequence_number_mut() = sequence_number;
+    }
+}
+
+async fn sequences(
+    service: &Service,
+    funder: AccountAddress,
+    receiver: AccountAddress,
+) -> Result<(u64, Option<u64>)> {
+    let f_request = service.client.get_account(funder);
     let r_request = service.client.get_account(receiver);
     let mut responses = futures::future::join_all([f_request, r_request]).await;
 
@@ -214,11 +280,11 @@ async fn sequences(service: &Service, receiver: AccountAddress) -> Result<(u64,
         .as_ref()
         .ok()
         .map(|account| account.inner().sequence_number);
-    let faucet_seq_num = responses
+    let funder_seq_num = responses
         .remove(0)
-        .map_err(|_| anyhow::format_err!("faucet account {} not found", faucet_address))?
+        .map_err(|_| anyhow::format_err!("faucet account {} not found", funder))?
         .inner()
         .sequence_number;
 
-    Ok((faucet_seq_num, receiver_seq_num))
+    Ok((funder_seq_num, receiver_seq_num))
 }
@@ -0,0 +1,286 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Throttling of mint requests, per client IP and per receiving address.
+
+use anyhow::{ensure, Result};
+use aptos_infallible::Mutex;
+use aptos_metrics_core::{register_int_counter_vec, IntCounterVec};
+use aptos_rate_limiter::rate_limit::TokenBucketRateLimiter;
+use aptos_sdk::types::account_address::AccountAddress;
+use once_cell::sync::Lazy;
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::{Duration, Instant},
+};
+use structopt::StructOpt;
+
+/// Number of mint requests rejected by the rate limiter, by the key which was throttled
+pub static APTOS_FAUCET_RATE_LIMITED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_faucet_rate_limited_requests",
+        "Number of mint requests rejected by the rate limiter",
+        &["key"]
+    )
+    .unwrap()
+});
+
+/// How often the buckets which are full and unused are dropped
+const GARBAGE_COLLECTION_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, StructOpt)]
+pub struct RateLimitConfig {
+    /// Number of mint requests a client IP can burst, unlimited if not set
+    #[structopt(long)]
+    pub ip_bucket_size: Option<usize>,
+    /// Number of mint requests per second a client IP regains, up to its bucket size
+    #[structopt(long, default_value = "1")]
+    pub ip_fill_rate: usize,
+    /// Number of mint requests an address can burst, unlimited if not set
+    #[structopt(long)]
+    pub address_bucket_size: Option<usize>,
+    /// Number of mint requests per second an address regains, up to its bucket size
+    #[structopt(long, default_value = "1")]
+    pub address_fill_rate: usize,
+    /// IP of a reverse proxy in front of the faucet, whose `X-Forwarded-For` header is trusted
+    /// to identify the client. Can be repeated
+    #[structopt(long)]
+    pub trusted_proxy: Vec<IpAddr>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            ip_bucket_size: None,
+            ip_fill_rate: 1,
+            address_bucket_size: None,
+            address_fill_rate: 1,
+            trusted_proxy: vec![],
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Checks that every bucket size is at least its fill rate, and the fill rate isn't zero
+    pub fn validate(&self) -> Result<()> {
+        for (key, bucket_size, fill_rate) in [
+            ("ip", self.ip_bucket_size, self.ip_fill_rate),
+            ("address", self.address_bucket_size, self.address_fill_rate),
+        ] {
+            if let Some(bucket_size) = bucket_size {
+                ensure!(fill_rate > 0, "--{}-fill-rate must be positive", key);
+                ensure!(
+                    bucket_size >= fill_rate,
+                    "--{}-bucket-size {} must be at least --{}-fill-rate {}",
+                    key,
+                    bucket_size,
+                    key,
+                    fill_rate
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Token buckets of mint requests, one per client IP and one per receiving address
+pub struct RateLimiter {
+    ip_buckets: TokenBucketRateLimiter<IpAddr>,
+    address_buckets: TokenBucketRateLimiter<AccountAddress>,
+    trusted_proxies: Vec<IpAddr>,
+    last_garbage_collection: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            ip_buckets: token_buckets("faucet_ip", config.ip_bucket_size, config.ip_fill_rate),
+            address_buckets: token_buckets(
+                "faucet_address",
+                config.address_bucket_size,
+                config.address_fill_rate,
+            ),
+            trusted_proxies: config.trusted_proxy,
+            last_garbage_collection: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// A rate limiter which lets every request through
+    pub fn open() -> Self {
+        Self::new(RateLimitConfig::default())
+    }
+
+    /// Identifies the client of a request from its peer address
+    ///
+    /// If the peer is a trusted proxy, the client is the last address of `X-Forwarded-For`
+    /// which isn't itself a trusted proxy.
+    pub fn client_ip(
+        &self,
+        remote: Option<SocketAddr>,
+        forwarded_for: Option<&str>,
+    ) -> Option<IpAddr> {
+        let mut client = remote?.ip();
+        if let Some(forwarded_for) = forwarded_for {
+            for hop in forwarded_for.rsplit(',') {
+                if !self.trusted_proxies.contains(&client) {
+                    break;
+                }
+                match hop.trim().parse() {
+                    Ok(ip) => client = ip,
+                    Err(_) => break,
+                }
+            }
+        }
+        Some(client)
+    }
+
+    /// Takes a token from the buckets of `client_ip` and `receiver`, or returns how long to wait
+    /// before retrying if either is empty
+    pub fn check(
+        &self,
+        client_ip: Option<IpAddr>,
+        receiver: Option<AccountAddress>,
+    ) -> Result<(), Duration> {
+        self.garbage_collect();
+
+        let ip_bucket = client_ip.map(|ip| self.ip_buckets.bucket(ip));
+        if let Some(bucket) = &ip_bucket {
+            if let Err(ready_at) = bucket.lock().acquire_all_tokens(1) {
+                APTOS_FAUCET_RATE_LIMITED_REQUESTS
+                    .with_label_values(&["ip"])
+                    .inc();
+                return Err(retry_after(ready_at));
+            }
+        }
+
+        if let Some(receiver) = receiver {
+            let address_bucket = self.address_buckets.bucket(receiver);
+            let result = address_bucket.lock().acquire_all_tokens(1);
+            if let Err(ready_at) = result {
+                // The request isn't served, so it shouldn't count against the client
+                if let Some(bucket) = &ip_bucket {
+                    bucket.lock().return_tokens(1);
+                }
+                APTOS_FAUCET_RATE_LIMITED_REQUESTS
+                    .with_label_values(&["address"])
+                    .inc();
+                return Err(retry_after(ready_at));
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the buckets which are full and unused, at most every `GARBAGE_COLLECTION_INTERVAL`.
+    /// They'd be recreated full, while keeping them would grow the buckets with every client IP
+    /// and address ever seen
+    fn garbage_collect(&self) {
+        let mut last_garbage_collection = self.last_garbage_collection.lock();
+        if last_garbage_collection.elapsed() < GARBAGE_COLLECTION_INTERVAL {
+            return;
+        }
+        *last_garbage_collection = Instant::now();
+        self.ip_buckets.garbage_collect_full_buckets();
+        self.address_buckets.garbage_collect_full_buckets();
+    }
+}
+
+fn token_buckets<Key: Eq + std::hash::Hash + Clone + std::fmt::Debug>(
+    label: &'static str,
+    bucket_size: Option<usize>,
+    fill_rate: usize,
+) -> TokenBucketRateLimiter<Key> {
+    match bucket_size {
+        Some(bucket_size) => {
+            TokenBucketRateLimiter::new(label, String::new(), 100, bucket_size, fill_rate, None)
+        }
+        None => TokenBucketRateLimiter::open(label),
+    }
+}
+
+/// Whole seconds until `ready_at`, at least one so that clients don't retry immediately
+fn retry_after(ready_at: Option<Instant>) -> Duration {
+    let seconds = ready_at
+        .map(|ready_at| {
+            let wait = ready_at.saturating_duration_since(Instant::now());
+            wait.as_secs() + u64::from(wait.subsec_nanos() > 0)
+        })
+        .unwrap_or(1);
+    Duration::from_secs(seconds.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate_limiter(trusted_proxy: Vec<IpAddr>) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            ip_bucket_size: Some(1),
+            address_bucket_size: Some(2),
+            trusted_proxy,
+            ..RateLimitConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_client_ip() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let remote = Some(SocketAddr::new(proxy, 1234));
+        let forwarded_for = Some("1.1.1.1, 2.2.2.2, 10.0.0.1");
+
+        // Only trusted proxies can set the client
+        let untrusted = rate_limiter(vec![]);
+        assert_eq!(untrusted.client_ip(remote, forwarded_for), Some(proxy));
+
+        let trusted = rate_limiter(vec![proxy]);
+        assert_eq!(
+            trusted.client_ip(remote, forwarded_for),
+            Some("2.2.2.2".parse().unwrap())
+        );
+        assert_eq!(trusted.client_ip(remote, Some("garbage")), Some(proxy));
+        assert_eq!(trusted.client_ip(remote, None), Some(proxy));
+        assert_eq!(trusted.client_ip(None, forwarded_for), None);
+    }
+
+    #[test]
+    fn test_check() {
+        let rate_limiter = rate_limiter(vec![]);
+        let ip_1: IpAddr = "1.1.1.1".parse().unwrap();
+        let ip_2: IpAddr = "2.2.2.2".parse().unwrap();
+        let ip_3: IpAddr = "3.3.3.3".parse().unwrap();
+
+        assert!(rate_limiter
+            .check(Some(ip_1), Some(AccountAddress::ONE))
+            .is_ok());
+        let retry_after = rate_limiter
+            .check(Some(ip_1), Some(AccountAddress::ONE))
+            .unwrap_err();
+        assert!(retry_after >= Duration::from_secs(1));
+
+        // The address bucket is independent of the client's
+        assert!(rate_limiter
+            .check(Some(ip_2), Some(AccountAddress::ONE))
+            .is_ok());
+        assert!(rate_limiter
+            .check(Some(ip_3), Some(AccountAddress::ONE))
+            .is_err());
+        // A request rejected for its address doesn't use up the client's bucket
+        assert!(rate_limiter.check(Some(ip_3), None).is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(RateLimitConfig::default().validate().is_ok());
+        let config = RateLimitConfig {
+            ip_bucket_size: Some(1),
+            ip_fill_rate: 2,
+            ..RateLimitConfig::default()
+        };
+        assert!(config.validate().is_err());
+        let config = RateLimitConfig {
+            address_bucket_size: Some(1),
+            address_fill_rate: 0,
+            ..RateLimitConfig::default()
+        };
+        assert!(config.validate().is_err());
+    }
+}
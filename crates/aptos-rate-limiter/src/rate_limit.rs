@@ -171,6 +171,15 @@ impl<Key: Eq + Hash + Clone + Debug> TokenBucketRateLimiter<Key> {
         }
         remove
     }
+
+    /// Garbage collects every bucket that isn't in use and is full, as it would be recreated with
+    /// at most the same tokens.  Returns the number of buckets collected
+    pub fn garbage_collect_full_buckets(&self) -> usize {
+        let mut buckets = self.buckets.write();
+        let num_buckets = buckets.len();
+        buckets.retain(|_, bucket| Arc::strong_count(bucket) > 1 || !bucket.lock().is_full());
+        num_buckets - buckets.len()
+    }
 }
 
 /// A token bucket object that keeps track of everything related to a key
@@ -364,6 +373,12 @@ impl Bucket {
         }
     }
 
+    /// Tells us if the bucket is refilled up to its size
+    pub fn is_full(&mut self) -> bool {
+        self.refill();
+        self.tokens >= self.size
+    }
+
     /// Add new tokens
     /// Ensures bucket doesn't overfill
     fn add_tokens(&mut self, new_tokens: usize) {
@@ -546,4 +561,30 @@ mod tests {
         assert!(!rate_limiter.try_garbage_collect_key(&key_to_keep));
         assert_num_keys(&rate_limiter, 1);
     }
+
+    #[test]
+    fn test_garbage_collect_full_buckets() {
+        let key_in_use = "in use";
+        let key_not_full = "not full";
+        let key_full = "full";
+        let rate_limiter = TokenBucketRateLimiter::test(2, 1);
+
+        let _bucket_arc = rate_limiter.bucket(key_in_use);
+        rate_limiter
+            .bucket(key_not_full)
+            .lock()
+            .acquire_all_tokens(1)
+            .unwrap();
+        let _ = rate_limiter.bucket(key_full);
+        assert_num_keys(&rate_limiter, 3);
+
+        // Only the full bucket nobody holds onto is collected
+        assert_eq!(1, rate_limiter.garbage_collect_full_buckets());
+        assert_num_keys(&rate_limiter, 2);
+
+        // Once refilled, the bucket can be collected too
+        sleep(Duration::from_secs(1));
+        assert_eq!(1, rate_limiter.garbage_collect_full_buckets());
+        assert_num_keys(&rate_limiter, 1);
+    }
 }
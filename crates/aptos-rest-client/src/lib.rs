@@ -3,12 +3,16 @@
 
 use anyhow::{anyhow, Result};
 use aptos_api_types::mime_types::BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE;
-pub use aptos_api_types::{self, MoveModuleBytecode, PendingTransaction, Transaction};
+pub use aptos_api_types::{
+    self, Block, Event, GasEstimation, MoveModuleBytecode, PendingTransaction, Transaction,
+    TransactionSubmissionStatus,
+};
 use aptos_crypto::HashValue;
 use aptos_types::{
-    account_address::AccountAddress, account_config::aptos_root_address,
+    account_address::AccountAddress, account_config::aptos_root_address, event::EventKey,
     transaction::SignedTransaction,
 };
+use futures::Stream;
 use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient, StatusCode};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -21,10 +25,13 @@ pub mod faucet;
 pub use faucet::FaucetClient;
 pub mod response;
 pub use response::Response;
+mod sse;
 mod state;
 pub mod types;
 use crate::aptos::{AptosVersion, Balance};
 pub use types::{Account, Resource, RestError};
+pub mod verifying_client;
+pub use verifying_client::VerifyingClient;
 pub mod aptos;
 
 const USER_AGENT: &str = concat!("aptos-client-sdk-rust / ", env!("CARGO_PKG_VERSION"));
@@ -83,11 +90,18 @@ impl Client {
             epoch: r.epoch,
             version: r.ledger_version,
             timestamp_usecs: r.ledger_timestamp,
+            cursor: None,
         });
 
         Ok(response)
     }
 
+    pub async fn estimate_gas_price(&self) -> Result<Response<GasEstimation>> {
+        let url = self.base_url.join("estimate_gas_price")?;
+        let response = self.inner.get(url).send().await?;
+        self.json(response).await
+    }
+
     pub async fn submit(&self, txn: &SignedTransaction) -> Result<Response<PendingTransaction>> {
         let txn_payload = bcs::to_bytes(txn)?;
         let url = self.base_url.join("transactions")?;
@@ -103,6 +117,27 @@ impl Client {
         self.json(response).await
     }
 
+    /// Submits the transactions in a single request, returning their submission statuses in the
+    /// same order. Transactions rejected by the mempool don't fail the request, their statuses
+    /// must be checked.
+    pub async fn submit_batch(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<Response<Vec<TransactionSubmissionStatus>>> {
+        let txn_payload = bcs::to_bytes(txns)?;
+        let url = self.base_url.join("transactions/batch")?;
+
+        let response = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_CONTENT_TYPE)
+            .body(txn_payload)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
     pub async fn submit_and_wait(&self, txn: &SignedTransaction) -> Result<Response<Transaction>> {
         self.submit(txn).await?;
         self.wait_for_signed_transaction(txn).await
@@ -208,6 +243,47 @@ impl Client {
         .await
     }
 
+    pub async fn get_block_by_height(
+        &self,
+        height: u64,
+        with_transactions: bool,
+    ) -> Result<Response<Block>> {
+        self.get_block(format!("blocks/by_height/{}", height), with_transactions)
+            .await
+    }
+
+    pub async fn get_block_by_version(
+        &self,
+        version: u64,
+        with_transactions: bool,
+    ) -> Result<Response<Block>> {
+        self.get_block(format!("blocks/by_version/{}", version), with_transactions)
+            .await
+    }
+
+    pub async fn get_block_by_hash(
+        &self,
+        hash: HashValue,
+        with_transactions: bool,
+    ) -> Result<Response<Block>> {
+        self.get_block(
+            format!("blocks/by_hash/{}", hash.to_hex_literal()),
+            with_transactions,
+        )
+        .await
+    }
+
+    async fn get_block(&self, path: String, with_transactions: bool) -> Result<Response<Block>> {
+        let url = self.base_url.join(&path)?;
+        let response = self
+            .inner
+            .get(url)
+            .query(&[("with_transactions", with_transactions)])
+            .send()
+            .await?;
+        self.json(response).await
+    }
+
     async fn get_transaction_by_version_or_hash(
         &self,
         version_or_hash: String,
@@ -327,13 +403,81 @@ impl Client {
         self.json(response).await
     }
 
+    /// Streams the committed transactions starting from version `start`, or only the transactions
+    /// committed from now on if `start` is `None`. The stream waits for new transactions once it
+    /// has caught up with the ledger, it never ends unless an error occurs.
+    pub async fn stream_transactions(
+        &self,
+        start: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<Transaction>>> {
+        self.stream("stream/transactions", start, "transaction")
+            .await
+    }
+
+    /// Streams the events emitted under `key` starting from sequence number `start`, or only the
+    /// events emitted from now on if `start` is `None`.
+    pub async fn stream_events_by_event_key(
+        &self,
+        key: EventKey,
+        start: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<Event>>> {
+        self.stream(&format!("stream/events/{:#x}", key), start, "event")
+            .await
+    }
+
+    /// Streams the events emitted under the event handle `field_name` of the resource
+    /// `struct_tag` of the account, see `stream_events_by_event_key`.
+    pub async fn stream_events_by_event_handle(
+        &self,
+        address: AccountAddress,
+        struct_tag: &str,
+        field_name: &str,
+        start: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<Event>>> {
+        self.stream(
+            &format!(
+                "stream/accounts/{}/events/{}/{}",
+                address, struct_tag, field_name
+            ),
+            start,
+            "event",
+        )
+        .await
+    }
+
+    async fn stream<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        start: Option<u64>,
+        name: &'static str,
+    ) -> Result<impl Stream<Item = Result<T>>> {
+        let url = self.base_url.join(path)?;
+        // The timeout of `inner` covers the whole response body, which never ends for a stream.
+        let client = ReqwestClient::builder()
+            .user_agent(USER_AGENT)
+            .cookie_store(true)
+            .build()?;
+
+        let mut request = client.get(url);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            let error_response = response.json::<RestError>().await?;
+            return Err(error_response.into());
+        }
+
+        Ok(sse::decode_stream(response, name))
+    }
+
     async fn check_response(
         &self,
         response: reqwest::Response,
     ) -> Result<(reqwest::Response, State)> {
         if !response.status().is_success() {
             let error_response = response.json::<RestError>().await?;
-            return Err(anyhow::anyhow!("Request failed: {:?}", error_response));
+            return Err(error_response.into());
         }
         let state = State::from_headers(response.headers())?;
 
@@ -0,0 +1,131 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Decoding of the Server-Sent Events streams served under `/stream`.
+
+use crate::types::RestError;
+use anyhow::{anyhow, Result};
+use futures::stream::{self, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::mem;
+
+/// Name of the event the server sends before closing a stream that failed.
+const ERROR_EVENT: &str = "error";
+
+/// Turns the body of a stream response into the items sent as events named `name`.
+pub(crate) fn decode_stream<T: DeserializeOwned>(
+    response: reqwest::Response,
+    name: &'static str,
+) -> impl Stream<Item = Result<T>> {
+    let mut decoder = Decoder::default();
+    response
+        .bytes_stream()
+        .map(move |chunk| match chunk {
+            Ok(chunk) => decoder
+                .push(&chunk)
+                .into_iter()
+                .map(|message| message.parse(name))
+                .collect(),
+            Err(err) => vec![Err(err.into())],
+        })
+        .flat_map(stream::iter)
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Message {
+    event: String,
+    data: String,
+}
+
+impl Message {
+    fn parse<T: DeserializeOwned>(self, name: &str) -> Result<T> {
+        if self.event == name {
+            Ok(serde_json::from_str(&self.data)?)
+        } else if self.event == ERROR_EVENT {
+            let error_response: RestError = serde_json::from_str(&self.data)?;
+            Err(anyhow!("Stream failed: {:?}", error_response))
+        } else {
+            Err(anyhow!("Unexpected stream event: {}", self.event))
+        }
+    }
+}
+
+/// Incrementally parses the messages out of the chunks of a `text/event-stream` body. Only the
+/// `event` and `data` fields are kept, comments (e.g. keep-alives) are skipped.
+#[derive(Debug, Default)]
+struct Decoder {
+    buffer: Vec<u8>,
+    message: Message,
+}
+
+impl Decoder {
+    fn push(&mut self, chunk: &[u8]) -> Vec<Message> {
+        self.buffer.extend_from_slice(chunk);
+        let mut messages = vec![];
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(|c| c == '\n' || c == '\r');
+
+            if line.is_empty() {
+                // a blank line dispatches the message
+                if self.message != Message::default() {
+                    messages.push(mem::take(&mut self.message));
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+            let (field, value) = match line.split_once(':') {
+                Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+                None => (line, ""),
+            };
+            match field {
+                "event" => self.message.event = value.to_owned(),
+                "data" => {
+                    if !self.message.data.is_empty() {
+                        self.message.data.push('\n');
+                    }
+                    self.message.data.push_str(value);
+                }
+                _ => (),
+            }
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Decoder, Message};
+
+    fn message(event: &str, data: &str) -> Message {
+        Message {
+            event: event.to_owned(),
+            data: data.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_decode_messages_split_across_chunks() {
+        let mut decoder = Decoder::default();
+        assert_eq!(decoder.push(b"event:transaction\ndata:{\"ver"), vec![]);
+        assert_eq!(
+            decoder.push(b"sion\":\"1\"}\n\n:\n\nevent: error\r\ndata: {}\r\n\r\n"),
+            vec![
+                message("transaction", "{\"version\":\"1\"}"),
+                message("error", "{}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_multi_line_data() {
+        let mut decoder = Decoder::default();
+        assert_eq!(
+            decoder.push(b"event:event\ndata:[1,\ndata:2]\n\n"),
+            vec![message("event", "[1,\n2]")]
+        );
+    }
+}
@@ -5,7 +5,7 @@ use aptos_api_types::{Address, U64};
 use aptos_types::transaction::authenticator::AuthenticationKey;
 use move_deps::move_core_types::{language_storage::StructTag, parser::parse_struct_tag};
 use serde::{Deserialize, Deserializer, Serialize};
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct RestError {
@@ -14,6 +14,14 @@ pub struct RestError {
     pub aptos_ledger_version: Option<U64>,
 }
 
+impl fmt::Display for RestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Request failed: {:?}", self)
+    }
+}
+
+impl std::error::Error for RestError {}
+
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct Resource {
     #[serde(rename = "type", deserialize_with = "deserialize_resource_type")]
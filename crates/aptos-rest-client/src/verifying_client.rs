@@ -0,0 +1,181 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A client verifying the proofs served by the REST API when requested with `proof=true`, so
+//! that it can read from a node it doesn't trust.
+
+use crate::{Client, Response};
+use anyhow::{anyhow, Result};
+use aptos_api_types::{StateValueProof, TransactionProof, ValueWithProof};
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    account_config::AccountResource,
+    state_store::state_key::StateKey,
+    transaction::{TransactionWithProof, Version},
+    trusted_state::TrustedState,
+    waypoint::Waypoint,
+};
+use move_deps::move_core_types::{
+    language_storage::{ResourceKey, StructTag},
+    move_resource::MoveStructType,
+    parser::parse_struct_tag,
+};
+use reqwest::RequestBuilder;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, RwLock};
+
+/// Reads values from the REST API along with their proofs, and verifies them against a
+/// [`TrustedState`]. The trusted state is ratcheted forward to the latest ledger info of each
+/// verified response.
+///
+/// The values are BCS deserialized from the proven state values and transactions rather than
+/// read from the JSON rendered by the node, which can't be verified.
+#[derive(Clone, Debug)]
+pub struct VerifyingClient {
+    inner: Client,
+    trusted_state: Arc<RwLock<TrustedState>>,
+}
+
+impl VerifyingClient {
+    /// Creates a client trusting the ledger from the epoch change ledger info committed to by
+    /// `waypoint`, e.g. the genesis waypoint.
+    pub fn new(inner: Client, waypoint: Waypoint) -> Self {
+        Self::new_with_state(inner, TrustedState::from_epoch_waypoint(waypoint))
+    }
+
+    pub fn new_with_state(inner: Client, trusted_state: TrustedState) -> Self {
+        Self {
+            inner,
+            trusted_state: Arc::new(RwLock::new(trusted_state)),
+        }
+    }
+
+    pub fn trusted_state(&self) -> TrustedState {
+        self.trusted_state.read().unwrap().clone()
+    }
+
+    pub async fn get_account(&self, address: AccountAddress) -> Result<AccountResource> {
+        let url = self.inner.base_url.join(&format!("accounts/{}", address))?;
+        let state_key = resource_state_key(address, AccountResource::struct_tag());
+        let bytes = self
+            .get_state_value(self.inner.inner.get(url), &state_key)
+            .await?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    /// Gets the resource `resource_type` of `address`, BCS deserialized into `T`.
+    pub async fn get_account_resource_bcs<T: DeserializeOwned>(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+    ) -> Result<T> {
+        let url = self
+            .inner
+            .base_url
+            .join(&format!("accounts/{}/resource/{}", address, resource_type))?;
+        let state_key = resource_state_key(address, parse_struct_tag(resource_type)?);
+        let bytes = self
+            .get_state_value(self.inner.inner.get(url), &state_key)
+            .await?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    /// Gets the item `key` of a table, BCS deserialized into `V`. The key is sent as JSON and
+    /// must BCS serialize to the same bytes as the Move value of type `key_type`.
+    pub async fn get_table_item_bcs<K: Serialize, V: DeserializeOwned>(
+        &self,
+        table_handle: u128,
+        key_type: &str,
+        value_type: &str,
+        key: K,
+    ) -> Result<V> {
+        let url = self
+            .inner
+            .base_url
+            .join(&format!("tables/{}/item", table_handle))?;
+        let state_key = StateKey::table_item(table_handle, bcs::to_bytes(&key)?);
+        let data = json!({
+            "key_type": key_type,
+            "value_type": value_type,
+            "key": json!(key),
+        });
+        let bytes = self
+            .get_state_value(self.inner.inner.post(url).json(&data), &state_key)
+            .await?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    /// Gets the committed transaction at `version`, along with its events.
+    pub async fn get_transaction_by_version(
+        &self,
+        version: Version,
+    ) -> Result<TransactionWithProof> {
+        let url = self
+            .inner
+            .base_url
+            .join(&format!("transactions/{}", version))?;
+        let trusted_state = self.trusted_state();
+        let value = self
+            .get_with_proof(self.inner.inner.get(url), &trusted_state)
+            .await?
+            .into_inner();
+
+        let proof: TransactionProof = bcs::from_bytes(value.proof.inner())?;
+        let new_state = proof.verify(&trusted_state, version)?;
+        self.ratchet(new_state);
+        Ok(proof.transaction_with_proof)
+    }
+
+    async fn get_state_value(
+        &self,
+        request: RequestBuilder,
+        state_key: &StateKey,
+    ) -> Result<Vec<u8>> {
+        let trusted_state = self.trusted_state();
+        let (value, state) = self
+            .get_with_proof(request, &trusted_state)
+            .await?
+            .into_parts();
+
+        // the value is read at the ledger version of the response, which the proof must be of
+        let proof: StateValueProof = bcs::from_bytes(value.proof.inner())?;
+        let new_state = proof.verify(&trusted_state, state_key, state.version)?;
+        self.ratchet(new_state);
+        proof
+            .state_value
+            .and_then(|state_value| state_value.maybe_bytes)
+            .ok_or_else(|| anyhow!("State value of {:?} is proven to not exist", state_key))
+    }
+
+    async fn get_with_proof(
+        &self,
+        request: RequestBuilder,
+        trusted_state: &TrustedState,
+    ) -> Result<Response<ValueWithProof<Value>>> {
+        let response = request
+            .query(&[
+                ("proof", "true".to_owned()),
+                ("known_version", trusted_state.version().to_string()),
+            ])
+            .send()
+            .await?;
+        Ok(self.inner.json(response).await?)
+    }
+
+    /// Keeps the newest of the current trusted state and `new_state`, as concurrent requests may
+    /// be verified out of order.
+    fn ratchet(&self, new_state: TrustedState) {
+        let mut trusted_state = self.trusted_state.write().unwrap();
+        if new_state.version() > trusted_state.version() {
+            *trusted_state = new_state;
+        }
+    }
+}
+
+fn resource_state_key(address: AccountAddress, struct_tag: StructTag) -> StateKey {
+    StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+        address, struct_tag,
+    )))
+}
@@ -7,10 +7,12 @@
 //!
 
 use crate::{
-    common::{check_network, get_account, get_account_balance, handle_request, with_context},
+    common::{
+        check_network, get_account, get_account_balance, handle_request, native_coin, with_context,
+    },
     error::{ApiError, ApiResult},
-    types::{AccountBalanceRequest, AccountBalanceResponse, Amount, BlockIdentifier, Currency},
-    RosettaContext, CURRENCY, NUM_DECIMALS,
+    types::{AccountBalanceRequest, AccountBalanceResponse, Amount, BlockIdentifier},
+    RosettaContext,
 };
 use aptos_logger::{debug, trace};
 use warp::Filter;
@@ -54,23 +56,12 @@ async fn account_balance(
     let address = request.account_identifier.account_address()?;
     let response = get_account(&rest_client, address).await?;
     let state = response.state();
-    let txns = rest_client
-        .get_transactions(Some(state.version), Some(1))
+    let block = rest_client
+        .get_block_by_version(state.version, false)
         .await
         .map_err(|err| ApiError::AptosError(err.to_string()))?
         .into_inner();
-    let txn = txns
-        .first()
-        .ok_or_else(|| ApiError::AptosError("Transaction not found".to_string()))?;
-
-    let txn_info = txn
-        .transaction_info()
-        .map_err(|err| ApiError::AptosError(err.to_string()))?;
-
-    let block_identifier = BlockIdentifier {
-        index: txn_info.version.0,
-        hash: txn_info.hash.to_string(),
-    };
+    let block_identifier = BlockIdentifier::from(&block);
 
     let response = get_account_balance(&rest_client, address).await?;
     let balance = response.into_inner();
@@ -78,10 +69,7 @@ async fn account_balance(
     // TODO: Cleanup to match reality
     let balances = vec![Amount {
         value: balance.coin.value.to_string(),
-        currency: Currency {
-            symbol: CURRENCY.to_string(),
-            decimals: NUM_DECIMALS,
-        },
+        currency: native_coin(),
     }];
 
     let response = AccountBalanceResponse {
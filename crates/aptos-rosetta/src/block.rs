@@ -2,33 +2,38 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    common::{check_network, get_timestamp, handle_request, with_context},
+    common::{check_network, handle_request, parse_hash, with_context},
     error::{ApiError, ApiResult},
     types::{
-        Block, BlockIdentifier, BlockRequest, BlockResponse, Transaction, TransactionIdentifier,
+        Block, BlockIdentifier, BlockRequest, BlockResponse, BlockTransactionRequest,
+        BlockTransactionResponse, Transaction,
     },
     RosettaContext,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::{debug, trace};
-use std::str::FromStr;
 use warp::Filter;
 
 pub fn routes(
     server_context: RosettaContext,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
-    warp::post().and(
-        warp::path!("block")
+    warp::post()
+        .and(
+            warp::path!("block")
+                .and(warp::body::json())
+                .and(with_context(server_context.clone()))
+                .and_then(handle_request(block)),
+        )
+        .or(warp::path!("block" / "transaction")
             .and(warp::body::json())
             .and(with_context(server_context))
-            .and_then(handle_request(block)),
-    )
+            .and_then(handle_request(block_transaction)))
 }
 
-/// Retrieves a block (in this case a single transaction) given it's identifier.
+/// Retrieves a block given its identifier.
 ///
-/// Our implementation allows for by `index`, which is the ledger `version` or by
-/// transaction `hash`.
+/// Blocks are looked up by `index`, which is the block height, or else by `hash`.  If both are
+/// given the `hash` must match the block.  If neither is given, the latest block is returned.
 ///
 /// [API Spec](https://www.rosetta-api.org/docs/BlockApi.html#block)
 async fn block(request: BlockRequest, server_context: RosettaContext) -> ApiResult<BlockResponse> {
@@ -43,43 +48,63 @@ async fn block(request: BlockRequest, server_context: RosettaContext) -> ApiResu
 
     let rest_client = &server_context.rest_client;
 
-    // Retrieve by block or by hash, both or neither is not allowed
-    let response = match (
-        &request.block_identifier.index,
+    let block = match (
+        request.block_identifier.index,
         &request.block_identifier.hash,
     ) {
-        (Some(version), None) => rest_client.get_transaction_by_version(*version).await?,
-        (None, Some(hash)) => {
-            // Allow 0x in front of hash
-            let hash = HashValue::from_str(hash.strip_prefix("0x").unwrap())
-                .map_err(|err| ApiError::AptosError(err.to_string()))?;
-            rest_client.get_transaction(hash).await?
+        (Some(height), hash) => {
+            let block = rest_client
+                .get_block_by_height(height, true)
+                .await?
+                .into_inner();
+            if let Some(hash) = hash {
+                if HashValue::from(block.block_hash) != parse_hash(hash)? {
+                    return Err(ApiError::BadBlockRequest);
+                }
+            }
+            block
+        }
+        (None, Some(hash)) => rest_client
+            .get_block_by_hash(parse_hash(hash)?, true)
+            .await?
+            .into_inner(),
+        (None, None) => {
+            let state = rest_client.get_ledger_information().await?.into_inner();
+            rest_client
+                .get_block_by_version(state.version, true)
+                .await?
+                .into_inner()
         }
-        (_, _) => return Err(ApiError::BadBlockRequest),
     };
+    let height = block.block_height.0;
+    let block_identifier = BlockIdentifier::from(&block);
 
-    // Build up the transaction, which should contain the `operations` as the change set
-    let transaction_info = response.inner().transaction_info()?;
-    let transactions = vec![Transaction {
-        transaction_identifier: TransactionIdentifier {
-            hash: transaction_info.hash.to_string(),
-        },
-        // TODO: Add operations
-        operations: vec![],
-        related_transactions: None,
-    }];
-
-    let block_identifier: BlockIdentifier = transaction_info.into();
     // For the genesis block, we populate parent_block_identifier with the
     // same genesis block. Refer to
     // https://www.rosetta-api.org/docs/common_mistakes.html#malformed-genesis-block
-    // TODO: Retrieve the previous block? (if not genesis)
-    let parent_block_identifier = block_identifier.clone();
+    let parent_block_identifier = if height == 0 {
+        block_identifier.clone()
+    } else {
+        let parent_block = rest_client
+            .get_block_by_height(height - 1, false)
+            .await?
+            .into_inner();
+        BlockIdentifier::from(&parent_block)
+    };
+
+    // Build up the transactions, which should contain the `operations` as the change set
+    let transactions = block
+        .transactions
+        .unwrap_or_default()
+        .iter()
+        .map(Transaction::from_transaction)
+        .collect::<ApiResult<_>>()?;
 
     let block = Block {
         block_identifier,
         parent_block_identifier,
-        timestamp: get_timestamp(&response),
+        // note: timestamps are in microseconds, so we convert to milliseconds
+        timestamp: block.block_timestamp.0 / 1000,
         transactions,
     };
 
@@ -90,3 +115,47 @@ async fn block(request: BlockRequest, server_context: RosettaContext) -> ApiResu
 
     Ok(response)
 }
+
+/// Retrieves a transaction of a block given their identifiers.
+///
+/// The transaction is looked up by hash, and must be within the block.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/BlockApi.html#blocktransaction)
+async fn block_transaction(
+    request: BlockTransactionRequest,
+    server_context: RosettaContext,
+) -> ApiResult<BlockTransactionResponse> {
+    debug!("/block/transaction");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "block_transaction",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let rest_client = &server_context.rest_client;
+
+    let block = rest_client
+        .get_block_by_height(request.block_identifier.index, false)
+        .await?
+        .into_inner();
+    if HashValue::from(block.block_hash) != parse_hash(&request.block_identifier.hash)? {
+        return Err(ApiError::BadBlockRequest);
+    }
+
+    let hash = parse_hash(&request.transaction_identifier.hash)?;
+    let txn = rest_client
+        .get_transaction(hash)
+        .await
+        .map_err(|_| ApiError::TransactionNotFound)?
+        .into_inner();
+    match txn.version() {
+        Some(version) if block.first_version.0 <= version && version <= block.last_version.0 => {}
+        _ => return Err(ApiError::TransactionNotFound),
+    }
+
+    Ok(BlockTransactionResponse {
+        transaction: Transaction::from_transaction(&txn)?,
+    })
+}
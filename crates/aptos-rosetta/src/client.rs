@@ -3,13 +3,29 @@
 
 use crate::{
     common::EmptyRequest,
+    construction::InternalOperation,
     types::{
         AccountBalanceRequest, AccountBalanceResponse, BlockRequest, BlockResponse,
-        NetworkListResponse, NetworkOptionsResponse, NetworkRequest, NetworkStatusResponse,
+        BlockTransactionRequest, BlockTransactionResponse, ConstructionCombineRequest,
+        ConstructionCombineResponse, ConstructionDeriveRequest, ConstructionDeriveResponse,
+        ConstructionHashRequest, ConstructionMetadataRequest, ConstructionMetadataResponse,
+        ConstructionParseRequest, ConstructionParseResponse, ConstructionPayloadsRequest,
+        ConstructionPayloadsResponse, ConstructionPreprocessRequest,
+        ConstructionPreprocessResponse, ConstructionSubmitRequest, ConstructionSubmitResponse,
+        CurveType, MempoolRequest, MempoolResponse, MempoolTransactionRequest,
+        MempoolTransactionResponse, NetworkIdentifier, NetworkListResponse, NetworkOptionsResponse,
+        NetworkRequest, NetworkStatusResponse, Operation, PublicKey, Signature, SignatureType,
+        TransactionIdentifier, TransactionIdentifierResponse,
     },
 };
+use anyhow::anyhow;
+use aptos_crypto::{
+    ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, ValidCryptoMaterialStringExt,
+};
 use aptos_rest_client::aptos_api_types::mime_types::JSON;
-use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
+use aptos_types::{
+    account_address::AccountAddress, chain_id::ChainId, transaction::RawTransaction,
+};
 use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient};
 use url::Url;
 
@@ -44,63 +60,260 @@ impl RosettaClient {
         &self,
         request: &AccountBalanceRequest,
     ) -> anyhow::Result<AccountBalanceResponse> {
-        let response = self
-            .inner
-            .post(self.address.join("account/balance").unwrap())
-            .header(CONTENT_TYPE, JSON)
-            .body(serde_json::to_string(request)?)
-            .send()
-            .await?;
-
-        self.json(response).await
+        self.make_call("account/balance", request).await
     }
 
     pub async fn block(&self, request: &BlockRequest) -> anyhow::Result<BlockResponse> {
-        let response = self
-            .inner
-            .post(self.address.join("block").unwrap())
-            .header(CONTENT_TYPE, JSON)
-            .body(serde_json::to_string(request)?)
-            .send()
-            .await?;
+        self.make_call("block", request).await
+    }
 
-        self.json(response).await
+    pub async fn block_transaction(
+        &self,
+        request: &BlockTransactionRequest,
+    ) -> anyhow::Result<BlockTransactionResponse> {
+        self.make_call("block/transaction", request).await
     }
 
-    pub async fn network_list(&self) -> anyhow::Result<NetworkListResponse> {
-        let response = self
-            .inner
-            .post(self.address.join("network/list").unwrap())
-            .header(CONTENT_TYPE, JSON)
-            .body(serde_json::to_string(&EmptyRequest)?)
-            .send()
-            .await?;
+    pub async fn construction_combine(
+        &self,
+        request: &ConstructionCombineRequest,
+    ) -> anyhow::Result<ConstructionCombineResponse> {
+        self.make_call("construction/combine", request).await
+    }
 
-        self.json(response).await
+    pub async fn construction_derive(
+        &self,
+        request: &ConstructionDeriveRequest,
+    ) -> anyhow::Result<ConstructionDeriveResponse> {
+        self.make_call("construction/derive", request).await
+    }
+
+    pub async fn construction_hash(
+        &self,
+        request: &ConstructionHashRequest,
+    ) -> anyhow::Result<TransactionIdentifierResponse> {
+        self.make_call("construction/hash", request).await
+    }
+
+    pub async fn construction_metadata(
+        &self,
+        request: &ConstructionMetadataRequest,
+    ) -> anyhow::Result<ConstructionMetadataResponse> {
+        self.make_call("construction/metadata", request).await
+    }
+
+    pub async fn construction_parse(
+        &self,
+        request: &ConstructionParseRequest,
+    ) -> anyhow::Result<ConstructionParseResponse> {
+        self.make_call("construction/parse", request).await
+    }
+
+    pub async fn construction_payloads(
+        &self,
+        request: &ConstructionPayloadsRequest,
+    ) -> anyhow::Result<ConstructionPayloadsResponse> {
+        self.make_call("construction/payloads", request).await
+    }
+
+    pub async fn construction_preprocess(
+        &self,
+        request: &ConstructionPreprocessRequest,
+    ) -> anyhow::Result<ConstructionPreprocessResponse> {
+        self.make_call("construction/preprocess", request).await
+    }
+
+    pub async fn construction_submit(
+        &self,
+        request: &ConstructionSubmitRequest,
+    ) -> anyhow::Result<ConstructionSubmitResponse> {
+        self.make_call("construction/submit", request).await
+    }
+
+    pub async fn mempool(&self, request: &MempoolRequest) -> anyhow::Result<MempoolResponse> {
+        self.make_call("mempool", request).await
+    }
+
+    pub async fn mempool_transaction(
+        &self,
+        request: &MempoolTransactionRequest,
+    ) -> anyhow::Result<MempoolTransactionResponse> {
+        self.make_call("mempool/transaction", request).await
+    }
+
+    pub async fn network_list(&self) -> anyhow::Result<NetworkListResponse> {
+        self.make_call("network/list", &EmptyRequest).await
     }
 
     pub async fn network_options(
         &self,
         request: &NetworkRequest,
     ) -> anyhow::Result<NetworkOptionsResponse> {
-        let response = self
-            .inner
-            .post(self.address.join("network/options").unwrap())
-            .header(CONTENT_TYPE, JSON)
-            .body(serde_json::to_string(request)?)
-            .send()
-            .await?;
-
-        self.json(response).await
+        self.make_call("network/options", request).await
     }
 
     pub async fn network_status(
         &self,
         request: &NetworkRequest,
     ) -> anyhow::Result<NetworkStatusResponse> {
+        self.make_call("network/status", request).await
+    }
+
+    /// Creates `new_account`, paid for by `sender`
+    ///
+    /// Returns the hash of the submitted transaction
+    pub async fn create_account(
+        &self,
+        chain_id: ChainId,
+        sender: AccountAddress,
+        new_account: AccountAddress,
+        private_key: &Ed25519PrivateKey,
+    ) -> anyhow::Result<TransactionIdentifier> {
+        let operation = InternalOperation::CreateAccount {
+            sender,
+            new_account,
+        };
+        self.submit_operations(chain_id, operation.into_operations(None), private_key)
+            .await
+    }
+
+    /// Transfers `amount` of the native coin from `sender` to `receiver`
+    ///
+    /// Returns the hash of the submitted transaction
+    pub async fn transfer(
+        &self,
+        chain_id: ChainId,
+        sender: AccountAddress,
+        receiver: AccountAddress,
+        amount: u64,
+        private_key: &Ed25519PrivateKey,
+    ) -> anyhow::Result<TransactionIdentifier> {
+        let operation = InternalOperation::Transfer {
+            sender,
+            receiver,
+            amount,
+        };
+        self.submit_operations(chain_id, operation.into_operations(None), private_key)
+            .await
+    }
+
+    /// Runs the whole construction flow for `operations`, signing offline with `private_key`
+    async fn submit_operations(
+        &self,
+        chain_id: ChainId,
+        operations: Vec<Operation>,
+        private_key: &Ed25519PrivateKey,
+    ) -> anyhow::Result<TransactionIdentifier> {
+        let network_identifier: NetworkIdentifier = chain_id.into();
+        let public_key = PublicKey {
+            hex_bytes: private_key.public_key().to_encoded_string()?,
+            curve_type: CurveType::Edwards25519,
+        };
+
+        let preprocess = self
+            .construction_preprocess(&ConstructionPreprocessRequest {
+                network_identifier: network_identifier.clone(),
+                operations: operations.clone(),
+                max_fee: None,
+                suggested_fee_multiplier: None,
+            })
+            .await?;
+        let options = preprocess
+            .options
+            .ok_or_else(|| anyhow!("No metadata options returned from preprocess"))?;
+
+        let metadata = self
+            .construction_metadata(&ConstructionMetadataRequest {
+                network_identifier: network_identifier.clone(),
+                options,
+                public_keys: vec![public_key.clone()],
+            })
+            .await?;
+
+        let payloads = self
+            .construction_payloads(&ConstructionPayloadsRequest {
+                network_identifier: network_identifier.clone(),
+                operations: operations.clone(),
+                metadata: Some(metadata.metadata),
+                public_keys: Some(vec![public_key.clone()]),
+            })
+            .await?;
+
+        // Don't blindly sign what the server returned, check it's the requested transaction
+        let parsed = self
+            .construction_parse(&ConstructionParseRequest {
+                network_identifier: network_identifier.clone(),
+                signed: false,
+                transaction: payloads.unsigned_transaction.clone(),
+            })
+            .await?;
+        if parsed.operations != operations {
+            return Err(anyhow!(
+                "Unsigned transaction doesn't match the operations: {:?}",
+                parsed.operations
+            ));
+        }
+
+        let signing_payload = match payloads.payloads.as_slice() {
+            [signing_payload] => signing_payload.clone(),
+            _ => return Err(anyhow!("Expected exactly one signing payload")),
+        };
+        let raw_txn: RawTransaction =
+            bcs::from_bytes(&hex::decode(&payloads.unsigned_transaction)?)?;
+        if hex::encode(raw_txn.signing_message()) != signing_payload.hex_bytes {
+            return Err(anyhow!(
+                "Signing payload doesn't match the unsigned transaction"
+            ));
+        }
+        let signature = private_key.sign(&raw_txn);
+
+        let combine = self
+            .construction_combine(&ConstructionCombineRequest {
+                network_identifier: network_identifier.clone(),
+                unsigned_transaction: payloads.unsigned_transaction,
+                signatures: vec![Signature {
+                    signing_payload,
+                    public_key,
+                    signature_type: SignatureType::Ed25519,
+                    hex_bytes: signature.to_encoded_string()?,
+                }],
+            })
+            .await?;
+
+        let hash = self
+            .construction_hash(&ConstructionHashRequest {
+                network_identifier: network_identifier.clone(),
+                signed_transaction: combine.signed_transaction.clone(),
+            })
+            .await?
+            .transaction_identifier;
+
+        let submitted = self
+            .construction_submit(&ConstructionSubmitRequest {
+                network_identifier,
+                signed_transaction: combine.signed_transaction,
+            })
+            .await?
+            .transaction_identifier;
+        if submitted != hash {
+            return Err(anyhow!(
+                "Submitted transaction {} doesn't match its hash {}",
+                submitted.hash,
+                hash.hash
+            ));
+        }
+
+        Ok(submitted)
+    }
+
+    async fn make_call<Request: serde::Serialize, Response: serde::de::DeserializeOwned>(
+        &self,
+        path: &'static str,
+        request: &Request,
+    ) -> anyhow::Result<Response> {
         let response = self
             .inner
-            .post(self.address.join("network/status").unwrap())
+            .post(self.address.join(path)?)
             .header(CONTENT_TYPE, JSON)
             .body(serde_json::to_string(request)?)
             .send()
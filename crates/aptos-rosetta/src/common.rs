@@ -3,10 +3,11 @@
 
 use crate::{
     error::{ApiError, ApiResult},
-    types::NetworkIdentifier,
-    RosettaContext,
+    types::{Currency, NetworkIdentifier},
+    RosettaContext, CURRENCY, NUM_DECIMALS,
 };
-use aptos_rest_client::{aptos::Balance, Account, Response, Transaction};
+use aptos_crypto::HashValue;
+use aptos_rest_client::{aptos::Balance, Account, Response};
 use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
 use futures::future::BoxFuture;
 use serde::{Deserialize, Serialize};
@@ -29,6 +30,14 @@ pub fn check_network(
     }
 }
 
+/// The native coin, the only [`Currency`] supported
+pub fn native_coin() -> Currency {
+    Currency {
+        symbol: CURRENCY.to_string(),
+        decimals: NUM_DECIMALS,
+    }
+}
+
 /// Attaches RosettaContext to warp paths
 pub fn with_context(
     context: RosettaContext,
@@ -77,6 +86,12 @@ where
     }
 }
 
+/// Parses a hash, allowing 0x in front of it
+pub fn parse_hash(hash: &str) -> ApiResult<HashValue> {
+    HashValue::from_str(hash.strip_prefix("0x").unwrap_or(hash))
+        .map_err(|err| ApiError::AptosError(err.to_string()))
+}
+
 pub async fn get_account(
     rest_client: &aptos_rest_client::Client,
     address: AccountAddress,
@@ -96,15 +111,3 @@ pub async fn get_account_balance(
         .await
         .map_err(|_| ApiError::AccountNotFound)
 }
-
-pub async fn get_genesis_transaction(
-    rest_client: &aptos_rest_client::Client,
-) -> ApiResult<Response<Transaction>> {
-    Ok(rest_client.get_transaction_by_version(0).await?)
-}
-
-/// Retrieve the timestamp according ot the Rosetta spec (milliseconds)
-pub fn get_timestamp<T>(response: &Response<T>) -> u64 {
-    // note: timestamps are in microseconds, so we convert to milliseconds
-    response.state().timestamp_usecs / 1000
-}
@@ -0,0 +1,583 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rosetta Construction API
+//!
+//! Builds, signs offline, and submits coin transfers and account creations.  Only `metadata`
+//! and `submit` need the node, the other endpoints work offline.
+//!
+//! See: [Construction API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html)
+//!
+
+use crate::{
+    common::{check_network, get_account, handle_request, native_coin, with_context},
+    error::{ApiError, ApiResult},
+    types::{
+        AccountIdentifier, Amount, ConstructionCombineRequest, ConstructionCombineResponse,
+        ConstructionDeriveRequest, ConstructionDeriveResponse, ConstructionHashRequest,
+        ConstructionMetadata, ConstructionMetadataRequest, ConstructionMetadataResponse,
+        ConstructionParseRequest, ConstructionParseResponse, ConstructionPayloadsRequest,
+        ConstructionPayloadsResponse, ConstructionPreprocessRequest,
+        ConstructionPreprocessResponse, ConstructionSubmitRequest, ConstructionSubmitResponse,
+        CurveType, MetadataOptions, Operation, OperationStatusType, OperationType, PublicKey,
+        SignatureType, SigningPayload, TransactionIdentifier, TransactionIdentifierResponse,
+    },
+    RosettaContext,
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    Signature, ValidCryptoMaterialStringExt,
+};
+use aptos_logger::{debug, trace};
+use aptos_rest_client::aptos_api_types::{self, Address, MoveType};
+use aptos_sdk::transaction_builder::{aptos_stdlib, TransactionFactory};
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    transaction::{
+        authenticator::AuthenticationKey, RawTransaction, SignedTransaction, TransactionPayload,
+    },
+    utility_coin::TEST_COIN_TYPE,
+};
+use std::{convert::TryFrom, str::FromStr};
+use warp::Filter;
+
+/// Maximum gas units a constructed transaction can use
+pub const MAX_GAS_AMOUNT: u64 = 2_000;
+
+/// Lifetime of a constructed transaction, long enough for it to be signed offline
+pub const TRANSACTION_EXPIRATION_SECS: u64 = 300;
+
+pub fn routes(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(
+            warp::path!("construction" / "combine")
+                .and(warp::body::json())
+                .and(with_context(server_context.clone()))
+                .and_then(handle_request(construction_combine)),
+        )
+        .or(warp::path!("construction" / "derive")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_derive)))
+        .or(warp::path!("construction" / "hash")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_hash)))
+        .or(warp::path!("construction" / "metadata")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_metadata)))
+        .or(warp::path!("construction" / "parse")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_parse)))
+        .or(warp::path!("construction" / "payloads")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_payloads)))
+        .or(warp::path!("construction" / "preprocess")
+            .and(warp::body::json())
+            .and(with_context(server_context.clone()))
+            .and_then(handle_request(construction_preprocess)))
+        .or(warp::path!("construction" / "submit")
+            .and(warp::body::json())
+            .and(with_context(server_context))
+            .and_then(handle_request(construction_submit)))
+}
+
+/// Combines the unsigned transaction with its signature into a signed transaction
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructioncombine)
+async fn construction_combine(
+    request: ConstructionCombineRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionCombineResponse> {
+    debug!("/construction/combine");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_combine",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let raw_txn: RawTransaction = decode_bcs(&request.unsigned_transaction, "RawTransaction")?;
+
+    // Only single signer transactions are supported
+    let signature = match request.signatures.as_slice() {
+        [signature] => signature,
+        _ => return Err(ApiError::BadSignatureCount),
+    };
+    if signature.signature_type != SignatureType::Ed25519 {
+        return Err(ApiError::BadSignatureType);
+    }
+
+    let public_key = decode_public_key(&signature.public_key)?;
+    let signature = Ed25519Signature::from_encoded_string(&signature.hex_bytes)
+        .map_err(|_| ApiError::BadSignature)?;
+    if AuthenticationKey::ed25519(&public_key).derived_address() != raw_txn.sender() {
+        return Err(ApiError::BadSignature);
+    }
+    signature
+        .verify(&raw_txn, &public_key)
+        .map_err(|_| ApiError::BadSignature)?;
+
+    let signed_txn = SignedTransaction::new(raw_txn, public_key, signature);
+
+    Ok(ConstructionCombineResponse {
+        signed_transaction: encode_bcs(&signed_txn)?,
+    })
+}
+
+/// Derives the account address of a public key
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionderive)
+async fn construction_derive(
+    request: ConstructionDeriveRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionDeriveResponse> {
+    debug!("/construction/derive");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_derive",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let public_key = decode_public_key(&request.public_key)?;
+    let address = AuthenticationKey::ed25519(&public_key).derived_address();
+
+    Ok(ConstructionDeriveResponse {
+        account_identifier: Some(address.into()),
+    })
+}
+
+/// Hashes a signed transaction, the hash is its identifier once committed
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionhash)
+async fn construction_hash(
+    request: ConstructionHashRequest,
+    server_context: RosettaContext,
+) -> ApiResult<TransactionIdentifierResponse> {
+    debug!("/construction/hash");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_hash",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let signed_txn: SignedTransaction =
+        decode_bcs(&request.signed_transaction, "SignedTransaction")?;
+
+    Ok(TransactionIdentifierResponse {
+        transaction_identifier: TransactionIdentifier {
+            hash: signed_txn.committed_hash().to_hex_literal(),
+        },
+    })
+}
+
+/// Looks up the sender's sequence number and the gas price to build the transaction with
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionmetadata)
+async fn construction_metadata(
+    request: ConstructionMetadataRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionMetadataResponse> {
+    debug!("/construction/metadata");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_metadata",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let rest_client = &server_context.rest_client;
+    let sender = AccountIdentifier {
+        address: request.options.sender_address,
+        sub_account: None,
+    }
+    .account_address()?;
+    let sequence_number = get_account(rest_client, sender)
+        .await?
+        .into_inner()
+        .sequence_number;
+    let gas_unit_price = *rest_client
+        .estimate_gas_price()
+        .await?
+        .into_inner()
+        .median
+        .inner();
+
+    let suggested_fee = Amount {
+        value: (MAX_GAS_AMOUNT * gas_unit_price).to_string(),
+        currency: native_coin(),
+    };
+
+    Ok(ConstructionMetadataResponse {
+        metadata: ConstructionMetadata {
+            chain_id: server_context.chain_id.id(),
+            sequence_number,
+            max_gas_amount: MAX_GAS_AMOUNT,
+            gas_unit_price,
+        },
+        suggested_fee: Some(vec![suggested_fee]),
+    })
+}
+
+/// Parses the operations back out of a transaction, signed or not
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionparse)
+async fn construction_parse(
+    request: ConstructionParseRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionParseResponse> {
+    debug!("/construction/parse");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_parse",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let (raw_txn, account_identifier_signers) = if request.signed {
+        let signed_txn: SignedTransaction = decode_bcs(&request.transaction, "SignedTransaction")?;
+        let signers = vec![signed_txn.sender().into()];
+        (signed_txn.into_raw_transaction(), Some(signers))
+    } else {
+        let raw_txn: RawTransaction = decode_bcs(&request.transaction, "RawTransaction")?;
+        (raw_txn, None)
+    };
+
+    let operation = InternalOperation::from_payload(raw_txn.sender(), raw_txn.payload())?;
+
+    Ok(ConstructionParseResponse {
+        operations: operation.into_operations(None),
+        account_identifier_signers,
+    })
+}
+
+/// Builds the unsigned transaction, and the payload for the sender to sign
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionpayloads)
+async fn construction_payloads(
+    request: ConstructionPayloadsRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionPayloadsResponse> {
+    debug!("/construction/payloads");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_payloads",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let metadata = request.metadata.ok_or_else(|| {
+        ApiError::DeserializationFailed("Metadata from /construction/metadata".to_string())
+    })?;
+    if ChainId::new(metadata.chain_id) != server_context.chain_id {
+        return Err(ApiError::BadNetwork);
+    }
+
+    let operation = InternalOperation::extract(&request.operations)?;
+    let sender = operation.sender();
+    let raw_txn = TransactionFactory::new(server_context.chain_id)
+        .with_max_gas_amount(metadata.max_gas_amount)
+        .with_gas_unit_price(metadata.gas_unit_price)
+        .with_transaction_expiration_time(TRANSACTION_EXPIRATION_SECS)
+        .payload(operation.payload())
+        .sender(sender)
+        .sequence_number(metadata.sequence_number)
+        .build();
+
+    let signing_payload = SigningPayload {
+        address: None,
+        account_identifier: Some(sender.into()),
+        hex_bytes: hex::encode(raw_txn.signing_message()),
+        signature_type: Some(SignatureType::Ed25519),
+    };
+
+    Ok(ConstructionPayloadsResponse {
+        unsigned_transaction: encode_bcs(&raw_txn)?,
+        payloads: vec![signing_payload],
+    })
+}
+
+/// Determines the sender of the operations, whose account is looked up by `metadata`
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionpreprocess)
+async fn construction_preprocess(
+    request: ConstructionPreprocessRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionPreprocessResponse> {
+    debug!("/construction/preprocess");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_preprocess",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let sender = InternalOperation::extract(&request.operations)?.sender();
+
+    Ok(ConstructionPreprocessResponse {
+        options: Some(MetadataOptions {
+            sender_address: sender.to_hex_literal(),
+        }),
+        required_public_keys: Some(vec![sender.into()]),
+    })
+}
+
+/// Submits a signed transaction to the node
+///
+/// [API Spec](https://www.rosetta-api.org/docs/ConstructionApi.html#constructionsubmit)
+async fn construction_submit(
+    request: ConstructionSubmitRequest,
+    server_context: RosettaContext,
+) -> ApiResult<ConstructionSubmitResponse> {
+    debug!("/construction/submit");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "construction_submit",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let signed_txn: SignedTransaction =
+        decode_bcs(&request.signed_transaction, "SignedTransaction")?;
+    let pending_txn = server_context
+        .rest_client
+        .submit(&signed_txn)
+        .await?
+        .into_inner();
+
+    Ok(ConstructionSubmitResponse {
+        transaction_identifier: TransactionIdentifier {
+            hash: pending_txn.hash.to_string(),
+        },
+    })
+}
+
+/// A transaction's worth of [`Operation`]s
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InternalOperation {
+    CreateAccount {
+        sender: AccountAddress,
+        new_account: AccountAddress,
+    },
+    Transfer {
+        sender: AccountAddress,
+        receiver: AccountAddress,
+        amount: u64,
+    },
+}
+
+impl InternalOperation {
+    /// Matches the operations against the supported transactions
+    pub fn extract(operations: &[Operation]) -> ApiResult<InternalOperation> {
+        let mut operations: Vec<_> = operations.iter().collect();
+        operations.sort_by_key(|operation| operation.operation_identifier.index);
+        let types = operations
+            .iter()
+            .map(|operation| OperationType::from_str(&operation.type_))
+            .collect::<ApiResult<Vec<_>>>()?;
+
+        match (types.as_slice(), operations.as_slice()) {
+            ([OperationType::CreateAccount], [create_account]) => {
+                let sender = create_account
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.sender.as_ref())
+                    .ok_or_else(|| {
+                        ApiError::BadTransferOperations(
+                            "create_account requires a sender in its metadata".to_string(),
+                        )
+                    })?
+                    .account_address()?;
+                Ok(InternalOperation::CreateAccount {
+                    sender,
+                    new_account: operation_account(create_account)?,
+                })
+            }
+            ([OperationType::Withdraw, OperationType::Deposit], [withdraw, deposit])
+            | ([OperationType::Deposit, OperationType::Withdraw], [deposit, withdraw]) => {
+                let withdrawn = operation_amount(withdraw)?;
+                let deposited = operation_amount(deposit)?;
+                if withdrawn >= 0 || withdrawn.checked_neg() != Some(deposited) {
+                    return Err(ApiError::BadTransferOperations(
+                        "A transfer must withdraw the amount it deposits".to_string(),
+                    ));
+                }
+                Ok(InternalOperation::Transfer {
+                    sender: operation_account(withdraw)?,
+                    receiver: operation_account(deposit)?,
+                    amount: u64::try_from(deposited).map_err(|_| {
+                        ApiError::BadTransferOperations("Invalid amount".to_string())
+                    })?,
+                })
+            }
+            _ => Err(ApiError::BadTransferOperations(format!(
+                "Unsupported operations {:?}",
+                types
+            ))),
+        }
+    }
+
+    /// Recovers the operation from the payload of a transaction built by `/construction/payloads`
+    pub fn from_payload(
+        sender: AccountAddress,
+        payload: &TransactionPayload,
+    ) -> ApiResult<InternalOperation> {
+        match aptos_stdlib::ScriptFunctionCall::decode(payload) {
+            Some(aptos_stdlib::ScriptFunctionCall::AccountCreateAccount { auth_key }) => {
+                Ok(InternalOperation::CreateAccount {
+                    sender,
+                    new_account: auth_key,
+                })
+            }
+            Some(aptos_stdlib::ScriptFunctionCall::CoinTransfer {
+                coin_type,
+                to,
+                amount,
+            }) if coin_type == *TEST_COIN_TYPE => Ok(InternalOperation::Transfer {
+                sender,
+                receiver: to,
+                amount,
+            }),
+            _ => Err(ApiError::BadTransactionPayload),
+        }
+    }
+
+    /// Recovers the operation from the JSON payload of a transaction, as pending in mempool
+    pub fn from_api_payload(
+        sender: AccountAddress,
+        payload: &aptos_api_types::TransactionPayload,
+    ) -> ApiResult<InternalOperation> {
+        let payload = match payload {
+            aptos_api_types::TransactionPayload::ScriptFunctionPayload(payload) => payload,
+            _ => return Err(ApiError::BadTransactionPayload),
+        };
+        let function = payload.function.to_string();
+        match (
+            function.as_str(),
+            payload.type_arguments.as_slice(),
+            payload.arguments.as_slice(),
+        ) {
+            ("0x1::Account::create_account", [], [new_account]) => {
+                Ok(InternalOperation::CreateAccount {
+                    sender,
+                    new_account: json_argument::<Address>(new_account)?.into(),
+                })
+            }
+            ("0x1::Coin::transfer", [coin_type], [receiver, amount])
+                if *coin_type == MoveType::from(TEST_COIN_TYPE.clone()) =>
+            {
+                Ok(InternalOperation::Transfer {
+                    sender,
+                    receiver: json_argument::<Address>(receiver)?.into(),
+                    amount: json_argument(amount)?,
+                })
+            }
+            _ => Err(ApiError::BadTransactionPayload),
+        }
+    }
+
+    pub fn sender(&self) -> AccountAddress {
+        match self {
+            InternalOperation::CreateAccount { sender, .. }
+            | InternalOperation::Transfer { sender, .. } => *sender,
+        }
+    }
+
+    pub fn payload(&self) -> TransactionPayload {
+        match self {
+            InternalOperation::CreateAccount { new_account, .. } => {
+                aptos_stdlib::encode_account_create_account(*new_account)
+            }
+            InternalOperation::Transfer {
+                receiver, amount, ..
+            } => aptos_stdlib::encode_test_coin_transfer(*receiver, *amount),
+        }
+    }
+
+    /// The operations of the transaction, with `status` set once it has executed
+    pub fn into_operations(self, status: Option<OperationStatusType>) -> Vec<Operation> {
+        match self {
+            InternalOperation::CreateAccount {
+                sender,
+                new_account,
+            } => vec![Operation::create_account(0, status, new_account, sender)],
+            InternalOperation::Transfer {
+                sender,
+                receiver,
+                amount,
+            } => {
+                let withdraw = Operation::withdraw(0, status, sender, amount);
+                let mut deposit = Operation::deposit(1, status, receiver, amount);
+                deposit.related_operations = Some(vec![withdraw.operation_identifier.clone()]);
+                vec![withdraw, deposit]
+            }
+        }
+    }
+}
+
+/// Parses a script function argument, which the API encodes as a string
+fn json_argument<T: FromStr>(argument: &serde_json::Value) -> ApiResult<T> {
+    argument
+        .as_str()
+        .and_then(|argument| T::from_str(argument).ok())
+        .ok_or(ApiError::BadTransactionPayload)
+}
+
+fn operation_account(operation: &Operation) -> ApiResult<AccountAddress> {
+    operation
+        .account
+        .as_ref()
+        .ok_or_else(|| {
+            ApiError::BadTransferOperations(format!("{} requires an account", operation.type_))
+        })?
+        .account_address()
+}
+
+/// The signed amount of native coin moved by the operation
+fn operation_amount(operation: &Operation) -> ApiResult<i128> {
+    let amount = operation.amount.as_ref().ok_or_else(|| {
+        ApiError::BadTransferOperations(format!("{} requires an amount", operation.type_))
+    })?;
+    if amount.currency != native_coin() {
+        return Err(ApiError::BadCoin);
+    }
+    i128::from_str(&amount.value)
+        .map_err(|_| ApiError::BadTransferOperations(format!("Invalid amount {}", amount.value)))
+}
+
+fn decode_public_key(public_key: &PublicKey) -> ApiResult<Ed25519PublicKey> {
+    if public_key.curve_type != CurveType::Edwards25519 {
+        return Err(ApiError::BadSignatureType);
+    }
+    Ed25519PublicKey::from_encoded_string(&public_key.hex_bytes)
+        .map_err(|_| ApiError::deserialization_failed("Ed25519PublicKey"))
+}
+
+pub(crate) fn encode_bcs<T: serde::Serialize>(value: &T) -> ApiResult<String> {
+    Ok(hex::encode(bcs::to_bytes(value)?))
+}
+
+pub(crate) fn decode_bcs<T: serde::de::DeserializeOwned>(
+    hex_bytes: &str,
+    type_name: &str,
+) -> ApiResult<T> {
+    let bytes = hex::decode(hex_bytes.strip_prefix("0x").unwrap_or(hex_bytes))
+        .map_err(|_| ApiError::deserialization_failed(type_name))?;
+    bcs::from_bytes(&bytes).map_err(|_| ApiError::deserialization_failed(type_name))
+}
@@ -38,6 +38,10 @@ pub enum ApiError {
     BadSignatureCount,
     #[error("historic balances unsupported")]
     HistoricBalancesUnsupported,
+    #[error("transaction not found")]
+    TransactionNotFound,
+    #[error("listing mempool unsupported")]
+    MempoolListingUnsupported,
 }
 
 impl ApiError {
@@ -56,6 +60,8 @@ impl ApiError {
             ApiError::BadCoin,
             ApiError::BadSignatureCount,
             ApiError::HistoricBalancesUnsupported,
+            ApiError::TransactionNotFound,
+            ApiError::MempoolListingUnsupported,
         ]
     }
 
@@ -74,6 +80,8 @@ impl ApiError {
             ApiError::BadCoin => 150,
             ApiError::BadSignatureCount => 160,
             ApiError::HistoricBalancesUnsupported => 170,
+            ApiError::TransactionNotFound => 180,
+            ApiError::MempoolListingUnsupported => 190,
         }
     }
 
@@ -92,6 +100,8 @@ impl ApiError {
             ApiError::BadCoin => false,
             ApiError::BadSignatureCount => false,
             ApiError::HistoricBalancesUnsupported => false,
+            ApiError::TransactionNotFound => true,
+            ApiError::MempoolListingUnsupported => false,
         }
     }
 
@@ -110,6 +120,8 @@ impl ApiError {
             ApiError::BadCoin => StatusCode::BAD_REQUEST,
             ApiError::BadSignatureCount => StatusCode::BAD_REQUEST,
             ApiError::HistoricBalancesUnsupported => StatusCode::BAD_REQUEST,
+            ApiError::TransactionNotFound => StatusCode::NOT_FOUND,
+            ApiError::MempoolListingUnsupported => StatusCode::BAD_REQUEST,
         }
     }
 
@@ -20,6 +20,8 @@ use warp::{
 
 mod account;
 mod block;
+mod construction;
+mod mempool;
 mod network;
 
 pub mod client;
@@ -88,6 +90,8 @@ pub fn routes(
 ) -> impl Filter<Extract = impl Reply, Error = Infallible> + Clone {
     account::routes(context.clone())
         .or(block::routes(context.clone()))
+        .or(construction::routes(context.clone()))
+        .or(mempool::routes(context.clone()))
         .or(network::routes(context))
         // TODO: Add health check?
         .with(
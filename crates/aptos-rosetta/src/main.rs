@@ -27,6 +27,8 @@ async fn main() {
         tls_cert_path: args.tls_cert_path,
         tls_key_path: args.tls_key_path,
         content_length_limit: args.content_length_limit,
+        max_view_function_gas: None,
+        max_submit_transaction_batch_size: None,
     };
 
     // Ensure runtime for Rosetta is up and running
@@ -0,0 +1,107 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Rosetta Mempool API
+//!
+//! The node doesn't expose the contents of its mempool, so listing it fails with
+//! `MempoolListingUnsupported`, and only transactions whose hash is already known can be looked up.
+//!
+//! See: [Mempool API Spec](https://www.rosetta-api.org/docs/MempoolApi.html)
+//!
+
+use crate::{
+    common::{check_network, handle_request, parse_hash, with_context},
+    construction::InternalOperation,
+    error::{ApiError, ApiResult},
+    types::{
+        MempoolRequest, MempoolResponse, MempoolTransactionRequest, MempoolTransactionResponse,
+        Transaction,
+    },
+    RosettaContext,
+};
+use aptos_logger::{debug, trace};
+use aptos_rest_client::aptos_api_types;
+use warp::Filter;
+
+pub fn routes(
+    server_context: RosettaContext,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(
+            warp::path!("mempool")
+                .and(warp::body::json())
+                .and(with_context(server_context.clone()))
+                .and_then(handle_request(mempool)),
+        )
+        .or(warp::path!("mempool" / "transaction")
+            .and(warp::body::json())
+            .and(with_context(server_context))
+            .and_then(handle_request(mempool_transaction)))
+}
+
+/// Lists the transactions in mempool
+///
+/// Always fails, as the node can't list its mempool. An empty list would wrongly claim that no
+/// transaction is pending.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempool)
+async fn mempool(
+    request: MempoolRequest,
+    server_context: RosettaContext,
+) -> ApiResult<MempoolResponse> {
+    debug!("/mempool");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "mempool",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    Err(ApiError::MempoolListingUnsupported)
+}
+
+/// Retrieves a pending transaction given its hash
+///
+/// Its operations are the ones it was constructed from, they have no status until it's
+/// committed.
+///
+/// [API Spec](https://www.rosetta-api.org/docs/MempoolApi.html#mempooltransaction)
+async fn mempool_transaction(
+    request: MempoolTransactionRequest,
+    server_context: RosettaContext,
+) -> ApiResult<MempoolTransactionResponse> {
+    debug!("/mempool/transaction");
+    trace!(
+        request = ?request,
+        server_context = ?server_context,
+        "mempool_transaction",
+    );
+
+    check_network(request.network_identifier, &server_context)?;
+
+    let hash = parse_hash(&request.transaction_identifier.hash)?;
+    let txn = server_context
+        .rest_client
+        .get_transaction(hash)
+        .await
+        .map_err(|_| ApiError::TransactionNotFound)?
+        .into_inner();
+    let pending_txn = match txn {
+        aptos_api_types::Transaction::PendingTransaction(pending_txn) => pending_txn,
+        _ => return Err(ApiError::TransactionNotFound),
+    };
+
+    let operation = InternalOperation::from_api_payload(
+        pending_txn.request.sender.into(),
+        &pending_txn.request.payload,
+    )?;
+
+    Ok(MempoolTransactionResponse {
+        transaction: Transaction {
+            transaction_identifier: request.transaction_identifier,
+            operations: operation.into_operations(None),
+            related_transactions: None,
+        },
+    })
+}
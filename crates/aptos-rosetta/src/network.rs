@@ -2,14 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    common::{
-        check_network, get_genesis_transaction, get_timestamp, handle_request, with_context,
-        with_empty_request, EmptyRequest,
-    },
+    common::{check_network, handle_request, with_context, with_empty_request, EmptyRequest},
     error::ApiError,
     types::{
         Allow, BlockIdentifier, NetworkListResponse, NetworkOptionsResponse, NetworkRequest,
-        NetworkStatusResponse, Peer, Version,
+        NetworkStatusResponse, OperationStatusType, OperationType, Peer, Version,
     },
     RosettaContext, MIDDLEWARE_VERSION, NODE_VERSION, ROSETTA_VERSION,
 };
@@ -82,13 +79,15 @@ async fn network_options(
         middleware_version: MIDDLEWARE_VERSION.to_string(),
     };
 
-    let operation_statuses = vec![
-        // TODO: Add statuses
-    ];
+    let operation_statuses = OperationStatusType::all()
+        .into_iter()
+        .map(|status| status.into())
+        .collect();
 
-    let operation_types = vec![
-        // TODO: Add operations
-    ];
+    let operation_types = OperationType::all()
+        .into_iter()
+        .map(|op| op.to_string())
+        .collect();
 
     let errors = ApiError::all().into_iter().map(|err| err.into()).collect();
 
@@ -126,28 +125,26 @@ async fn network_status(
     check_network(request.network_identifier, &server_context)?;
 
     let rest_client = &server_context.rest_client;
-    let response = get_genesis_transaction(rest_client).await?;
+    let response = rest_client.get_block_by_height(0, false).await?;
     let state = response.state();
-    let transaction = response.inner();
-
-    // TODO: Cache the genesis transaction
-    let genesis_txn = BlockIdentifier::from(transaction.transaction_info()?);
 
-    let response = rest_client
-        .get_transaction_by_version(state.version)
-        .await?;
-    let transaction = response.inner();
-    let latest_txn = BlockIdentifier::from(transaction.transaction_info()?);
+    // TODO: Cache the genesis block
+    let genesis_block = BlockIdentifier::from(response.inner());
 
-    let current_block_timestamp = get_timestamp(&response);
+    let current_block = rest_client
+        .get_block_by_version(state.version, false)
+        .await?
+        .into_inner();
+    // note: timestamps are in microseconds, so we convert to milliseconds
+    let current_block_timestamp = current_block.block_timestamp.0 / 1000;
 
     // TODO: add peers
     let peers: Vec<Peer> = vec![];
 
     let response = NetworkStatusResponse {
-        current_block_identifier: latest_txn,
+        current_block_identifier: BlockIdentifier::from(&current_block),
         current_block_timestamp,
-        genesis_block_identifier: genesis_txn,
+        genesis_block_identifier: genesis_block,
         // TODO: Fill in with oldest block not pruned
         oldest_block_identifier: None,
         sync_status: None,
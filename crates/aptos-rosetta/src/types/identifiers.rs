@@ -5,7 +5,7 @@ use crate::{
     common::BLOCKCHAIN,
     error::{ApiError, ApiResult},
 };
-use aptos_rest_client::{aptos_api_types::TransactionInfo, Transaction};
+use aptos_rest_client::aptos_api_types::Block;
 use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
 use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, str::FromStr};
@@ -24,7 +24,7 @@ impl AccountIdentifier {
     pub fn account_address(&self) -> ApiResult<AccountAddress> {
         // Allow 0x in front of account address
         Ok(AccountAddress::from_str(
-            self.address.strip_prefix("0x").unwrap(),
+            self.address.strip_prefix("0x").unwrap_or(&self.address),
         )?)
     }
 }
@@ -41,32 +41,21 @@ impl From<AccountAddress> for AccountIdentifier {
 /// [API Spec](https://www.rosetta-api.org/docs/models/BlockIdentifier.html)
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct BlockIdentifier {
-    /// Version number usually known as height
+    /// Block height
     pub index: u64,
-    /// Version Hash
+    /// Block hash
     pub hash: String,
 }
 
-impl From<&TransactionInfo> for BlockIdentifier {
-    fn from(info: &TransactionInfo) -> Self {
+impl From<&Block> for BlockIdentifier {
+    fn from(block: &Block) -> Self {
         BlockIdentifier {
-            index: info.version.0,
-            hash: info.hash.to_string(),
+            index: block.block_height.0,
+            hash: block.block_hash.to_string(),
         }
     }
 }
 
-impl TryFrom<Transaction> for BlockIdentifier {
-    type Error = ApiError;
-
-    fn try_from(txn: Transaction) -> Result<Self, Self::Error> {
-        let txn_info = txn
-            .transaction_info()
-            .map_err(|err| ApiError::AptosError(err.to_string()))?;
-        Ok(BlockIdentifier::from(txn_info))
-    }
-}
-
 impl TryFrom<&PartialBlockIdentifier> for BlockIdentifier {
     type Error = ApiError;
 
@@ -1,7 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::error::ApiError;
 use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
 
 /// [API Spec](https://www.rosetta-api.org/docs/models/Error.html)
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -32,6 +34,96 @@ pub struct OperationStatus {
     pub successful: bool,
 }
 
+/// Statuses of [`crate::types::Operation`]s on chain
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OperationStatusType {
+    Success,
+    Failure,
+}
+
+impl OperationStatusType {
+    const SUCCESS: &'static str = "success";
+    const FAILURE: &'static str = "failure";
+
+    pub fn all() -> Vec<OperationStatusType> {
+        vec![OperationStatusType::Success, OperationStatusType::Failure]
+    }
+}
+
+impl fmt::Display for OperationStatusType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OperationStatusType::Success => Self::SUCCESS,
+            OperationStatusType::Failure => Self::FAILURE,
+        })
+    }
+}
+
+impl From<OperationStatusType> for OperationStatus {
+    fn from(status: OperationStatusType) -> Self {
+        OperationStatus {
+            status: status.to_string(),
+            successful: status == OperationStatusType::Success,
+        }
+    }
+}
+
+/// Types of [`crate::types::Operation`]s supported on Aptos
+///
+/// A coin transfer is a `Withdraw` from the sender paired with a `Deposit` to the receiver.
+/// `Fee` is the gas charged to the sender of a transaction, and can't be constructed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OperationType {
+    CreateAccount,
+    Withdraw,
+    Deposit,
+    Fee,
+}
+
+impl OperationType {
+    const CREATE_ACCOUNT: &'static str = "create_account";
+    const WITHDRAW: &'static str = "withdraw";
+    const DEPOSIT: &'static str = "deposit";
+    const FEE: &'static str = "fee";
+
+    pub fn all() -> Vec<OperationType> {
+        vec![
+            OperationType::CreateAccount,
+            OperationType::Withdraw,
+            OperationType::Deposit,
+            OperationType::Fee,
+        ]
+    }
+}
+
+impl FromStr for OperationType {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            Self::CREATE_ACCOUNT => Ok(OperationType::CreateAccount),
+            Self::WITHDRAW => Ok(OperationType::Withdraw),
+            Self::DEPOSIT => Ok(OperationType::Deposit),
+            Self::FEE => Ok(OperationType::Fee),
+            _ => Err(ApiError::BadTransferOperations(format!(
+                "Invalid operation type {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for OperationType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OperationType::CreateAccount => Self::CREATE_ACCOUNT,
+            OperationType::Withdraw => Self::WITHDRAW,
+            OperationType::Deposit => Self::DEPOSIT,
+            OperationType::Fee => Self::FEE,
+        })
+    }
+}
+
 /// [API Spec](https://www.rosetta-api.org/docs/models/Peer.html)
 ///
 /// TODO: Metadata?
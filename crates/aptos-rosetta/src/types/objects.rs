@@ -1,11 +1,20 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::types::{
-    AccountIdentifier, BlockIdentifier, Error, NetworkIdentifier, OperationIdentifier,
-    OperationStatus, TransactionIdentifier,
+use crate::{
+    common::native_coin,
+    error::{ApiError, ApiResult},
+    types::{
+        AccountIdentifier, BlockIdentifier, Error, NetworkIdentifier, OperationIdentifier,
+        OperationStatus, OperationStatusType, OperationType, TransactionIdentifier,
+    },
 };
+use aptos_rest_client::aptos_api_types::{
+    self, Address, MoveStructTag, MoveType, WriteSetChange, U64,
+};
+use aptos_types::{account_address::AccountAddress, event::EventKey, utility_coin::TEST_COIN_TYPE};
 use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, str::FromStr};
 
 ///
 ///
@@ -136,8 +145,6 @@ pub enum ExemptionType {
 }
 
 /// [API Spec](https://www.rosetta-api.org/docs/models/Operation.html)
-///
-/// TODO: Metadata?
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct Operation {
     pub operation_identifier: OperationIdentifier,
@@ -152,6 +159,109 @@ pub struct Operation {
     pub account: Option<AccountIdentifier>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub amount: Option<Amount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<OperationMetadata>,
+}
+
+impl Operation {
+    fn new(
+        index: u64,
+        status: Option<OperationStatusType>,
+        operation_type: OperationType,
+        address: AccountAddress,
+        amount: Option<String>,
+        metadata: Option<OperationMetadata>,
+    ) -> Operation {
+        Operation {
+            operation_identifier: OperationIdentifier {
+                index,
+                network_index: None,
+            },
+            related_operations: None,
+            type_: operation_type.to_string(),
+            status: status.map(|status| status.to_string()),
+            account: Some(address.into()),
+            amount: amount.map(|value| Amount {
+                value,
+                currency: native_coin(),
+            }),
+            metadata,
+        }
+    }
+
+    pub fn create_account(
+        index: u64,
+        status: Option<OperationStatusType>,
+        new_account: AccountAddress,
+        sender: AccountAddress,
+    ) -> Operation {
+        Operation::new(
+            index,
+            status,
+            OperationType::CreateAccount,
+            new_account,
+            None,
+            Some(OperationMetadata {
+                sender: Some(sender.into()),
+            }),
+        )
+    }
+
+    pub fn deposit(
+        index: u64,
+        status: Option<OperationStatusType>,
+        address: AccountAddress,
+        amount: u64,
+    ) -> Operation {
+        Operation::new(
+            index,
+            status,
+            OperationType::Deposit,
+            address,
+            Some(amount.to_string()),
+            None,
+        )
+    }
+
+    pub fn withdraw(
+        index: u64,
+        status: Option<OperationStatusType>,
+        address: AccountAddress,
+        amount: u64,
+    ) -> Operation {
+        Operation::new(
+            index,
+            status,
+            OperationType::Withdraw,
+            address,
+            Some(format!("-{}", amount)),
+            None,
+        )
+    }
+
+    pub fn fee(
+        index: u64,
+        status: Option<OperationStatusType>,
+        address: AccountAddress,
+        amount: u64,
+    ) -> Operation {
+        Operation::new(
+            index,
+            status,
+            OperationType::Fee,
+            address,
+            Some(format!("-{}", amount)),
+            None,
+        )
+    }
+}
+
+/// Aptos specific details of an [`Operation`]
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct OperationMetadata {
+    /// Account paying for a `create_account` operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<AccountIdentifier>,
 }
 
 /// [API Spec](https://www.rosetta-api.org/docs/models/Operator.html)
@@ -227,3 +337,169 @@ pub struct Transaction {
     pub operations: Vec<Operation>,
     pub related_transactions: Option<Vec<RelatedTransaction>>,
 }
+
+impl Transaction {
+    /// Converts a committed transaction into the balance changes of the native coin it made
+    ///
+    /// Every deposit and withdraw event of a `CoinStore<TestCoin>` is an operation, as is the
+    /// gas charged to the sender of a user transaction.  The gas is burned without an event,
+    /// and is charged even when the transaction fails.
+    pub fn from_transaction(txn: &aptos_api_types::Transaction) -> ApiResult<Transaction> {
+        let (info, events, sender, gas_unit_price) = match txn {
+            aptos_api_types::Transaction::UserTransaction(txn) => (
+                &txn.info,
+                txn.events.as_slice(),
+                Some(txn.request.sender),
+                txn.request.gas_unit_price.0,
+            ),
+            aptos_api_types::Transaction::GenesisTransaction(txn) => {
+                (&txn.info, txn.events.as_slice(), None, 0)
+            }
+            aptos_api_types::Transaction::BlockMetadataTransaction(txn) => {
+                (&txn.info, &[][..], None, 0)
+            }
+            aptos_api_types::Transaction::StateCheckpointTransaction(txn) => {
+                (&txn.info, &[][..], None, 0)
+            }
+            aptos_api_types::Transaction::PendingTransaction(_) => {
+                return Err(ApiError::AptosError(
+                    "Pending transactions have no balance changes yet".to_string(),
+                ))
+            }
+        };
+
+        let status = if info.success {
+            OperationStatusType::Success
+        } else {
+            OperationStatusType::Failure
+        };
+        let coin_stores = native_coin_stores(&info.changes)?;
+
+        let mut operations = vec![];
+        for event in events {
+            let coin_event = match coin_event_type(&event.typ) {
+                Some(coin_event) => coin_event,
+                None => continue,
+            };
+            // Events of other coins' stores are skipped, they aren't the native coin
+            let address = match coin_stores.get(&EventKey::from(event.key)) {
+                Some(address) => *address,
+                None => continue,
+            };
+            let amount = event_amount(&event.data)?;
+            let index = operations.len() as u64;
+            operations.push(match coin_event {
+                OperationType::Deposit => Operation::deposit(index, Some(status), address, amount),
+                _ => Operation::withdraw(index, Some(status), address, amount),
+            });
+        }
+
+        if let Some(sender) = sender {
+            let fee = info.gas_used.0 * gas_unit_price;
+            operations.push(Operation::fee(
+                operations.len() as u64,
+                Some(OperationStatusType::Success),
+                sender.into(),
+                fee,
+            ));
+        }
+
+        Ok(Transaction {
+            transaction_identifier: TransactionIdentifier {
+                hash: info.hash.to_string(),
+            },
+            operations,
+            related_transactions: None,
+        })
+    }
+}
+
+/// The `Coin` event types which change a balance, as the [`OperationType`] they map to
+fn coin_event_type(typ: &MoveType) -> Option<OperationType> {
+    match typ {
+        MoveType::Struct(tag)
+            if *tag.address.inner() == AccountAddress::ONE
+                && tag.module.as_str() == "Coin"
+                && tag.generic_type_params.is_empty() =>
+        {
+            match tag.name.as_str() {
+                "DepositEvent" => Some(OperationType::Deposit),
+                "WithdrawEvent" => Some(OperationType::Withdraw),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_native_coin_store(tag: &MoveStructTag) -> bool {
+    *tag.address.inner() == AccountAddress::ONE
+        && tag.module.as_str() == "Coin"
+        && tag.name.as_str() == "CoinStore"
+        && tag.generic_type_params == [MoveType::from(TEST_COIN_TYPE.clone())]
+}
+
+/// Maps the event keys of the native coin stores written by a transaction to their owners
+///
+/// Emitting an event bumps the counter of its handle, so every store with events in the
+/// transaction is in its write set.
+fn native_coin_stores(changes: &[WriteSetChange]) -> ApiResult<HashMap<EventKey, AccountAddress>> {
+    let mut coin_stores = HashMap::new();
+    for change in changes {
+        if let WriteSetChange::WriteResource { address, data, .. } = change {
+            if !is_native_coin_store(&data.typ) {
+                continue;
+            }
+            let coin_store: CoinStoreResource = serde_json::to_value(&data.data)
+                .and_then(serde_json::from_value)
+                .map_err(|_| ApiError::deserialization_failed("CoinStore"))?;
+            for handle in [coin_store.deposit_events, coin_store.withdraw_events] {
+                coin_stores.insert(handle.key(), *address.inner());
+            }
+        }
+    }
+    Ok(coin_stores)
+}
+
+fn event_amount(data: &serde_json::Value) -> ApiResult<u64> {
+    data.get("amount")
+        .and_then(|amount| amount.as_str())
+        .and_then(|amount| u64::from_str(amount).ok())
+        .ok_or_else(|| ApiError::deserialization_failed("Coin event amount"))
+}
+
+/// The parts of `0x1::Coin::CoinStore` needed to identify its events
+#[derive(Deserialize)]
+struct CoinStoreResource {
+    deposit_events: EventHandleResource,
+    withdraw_events: EventHandleResource,
+}
+
+/// `0x1::Event::EventHandle`, whose key is derived from the GUID it wraps
+#[derive(Deserialize)]
+struct EventHandleResource {
+    guid: GuidWrapperResource,
+}
+
+impl EventHandleResource {
+    fn key(&self) -> EventKey {
+        let id = &self.guid.guid.id;
+        EventKey::new_from_address(id.addr.inner(), id.creation_num.0)
+    }
+}
+
+#[derive(Deserialize)]
+struct GuidWrapperResource {
+    guid: GuidResource,
+}
+
+#[derive(Deserialize)]
+struct GuidResource {
+    id: GuidIdResource,
+}
+
+#[derive(Deserialize)]
+struct GuidIdResource {
+    addr: Address,
+    creation_num: U64,
+}
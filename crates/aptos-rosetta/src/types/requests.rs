@@ -117,6 +117,8 @@ pub struct ConstructionMetadataResponse {
 pub struct ConstructionMetadata {
     pub chain_id: u8,
     pub sequence_number: u64,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
 }
 
 /// [API Spec](https://www.rosetta-api.org/docs/models/ConstructionParseRequest.html)
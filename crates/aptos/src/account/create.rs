@@ -6,7 +6,7 @@ use crate::common::{
         CliCommand, CliTypedResult, EncodingOptions, FaucetOptions, ProfileOptions,
         WriteTransactionOptions,
     },
-    utils::{fund_account, submit_transaction},
+    utils::fund_account,
 };
 use aptos_transaction_builder::aptos_stdlib;
 use aptos_types::account_address::AccountAddress;
@@ -63,23 +63,13 @@ impl CliCommand<String> for CreateAccount {
 
 impl CreateAccount {
     async fn create_account_with_key(self, address: AccountAddress) -> CliTypedResult<()> {
-        let sender_key = self.write_options.private_key_options.extract_private_key(
-            self.encoding_options.encoding,
-            &self.profile_options.profile,
-        )?;
-
-        submit_transaction(
-            self.write_options
-                .rest_options
-                .url(&self.profile_options.profile)?,
-            self.write_options
-                .chain_id(&self.profile_options.profile)
-                .await?,
-            sender_key,
-            aptos_stdlib::encode_account_create_account(address),
-            self.write_options.max_gas,
-        )
-        .await?;
+        self.write_options
+            .submit_transaction(
+                self.encoding_options.encoding,
+                &self.profile_options.profile,
+                aptos_stdlib::encode_account_create_account(address),
+            )
+            .await?;
         Ok(())
     }
 }
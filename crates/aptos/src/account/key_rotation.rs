@@ -0,0 +1,127 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    encrypted_key::{load_private_key_file, read_new_passphrase},
+    types::{
+        CliCommand, CliConfig, CliError, CliTypedResult, EncodingOptions, EncodingType,
+        ProfileOptions, TransactionSummary, WriteTransactionOptions,
+    },
+};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+use aptos_types::transaction::authenticator::AuthenticationKey;
+use async_trait::async_trait;
+use cached_framework_packages::aptos_stdlib;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Command to rotate the authentication key of an account
+///
+/// The account keeps its address. Once the rotation transaction is committed, the profile is
+/// updated to sign for the account with the new private key.
+#[derive(Debug, Parser)]
+pub struct RotateKey {
+    #[clap(flatten)]
+    pub(crate) write_options: WriteTransactionOptions,
+
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+
+    /// New private key input file name
+    #[clap(long, group = "new_private_key_input", parse(from_os_str))]
+    pub(crate) new_private_key_file: Option<PathBuf>,
+
+    /// New private key encoded in a type as shown in `encoding`
+    #[clap(long, group = "new_private_key_input")]
+    pub(crate) new_private_key: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for RotateKey {
+    fn command_name(&self) -> &'static str {
+        "RotateKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let profile = &self.profile_options.profile;
+        let new_private_key = self.extract_new_private_key(self.encoding_options.encoding)?;
+        let sender_address = self
+            .write_options
+            .sender_address(self.encoding_options.encoding, profile)?;
+
+        // Check that the profile can be updated before rotating, as the account can't be used
+        // without the new key afterwards
+        let mut config = CliConfig::load()?;
+        let profile_config = config
+            .profiles
+            .as_mut()
+            .and_then(|profiles| profiles.get_mut(profile))
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "Profile {} not found, the new key must be saved to a profile",
+                    profile
+                ))
+            })?;
+        if let Some(account) = profile_config.account {
+            if account != sender_address {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Profile {} is for account {}, not for the sender {}",
+                    profile, account, sender_address
+                )));
+            }
+        }
+
+        // Keep the profile's key encrypted, asking for the new passphrase before rotating too
+        let passphrase = if profile_config.is_encrypted() {
+            Some(read_new_passphrase()?)
+        } else {
+            None
+        };
+
+        let new_public_key = new_private_key.public_key();
+        let new_auth_key = AuthenticationKey::ed25519(&new_public_key);
+        let transaction = self
+            .write_options
+            .submit_transaction(
+                self.encoding_options.encoding,
+                profile,
+                aptos_stdlib::encode_account_rotate_authentication_key(new_auth_key.to_vec()),
+            )
+            .await?;
+        if !transaction.success() {
+            return Err(CliError::ApiError(format!(
+                "Key rotation failed, the profile is not updated: {}",
+                transaction.vm_status()
+            )));
+        }
+
+        profile_config.set_private_key(new_private_key, passphrase.as_deref())?;
+        profile_config.account = Some(sender_address);
+        config.save()?;
+        eprintln!(
+            "Rotated the key of account {} and updated profile {}",
+            sender_address, profile
+        );
+
+        Ok(TransactionSummary::from(transaction))
+    }
+}
+
+impl RotateKey {
+    /// Extract the new private key from CLI args
+    fn extract_new_private_key(&self, encoding: EncodingType) -> CliTypedResult<Ed25519PrivateKey> {
+        if let Some(ref file) = self.new_private_key_file {
+            load_private_key_file(encoding, "--new-private-key-file", file.as_path())
+        } else if let Some(ref key) = self.new_private_key {
+            let key = key.as_bytes().to_vec();
+            encoding.decode_key("--new-private-key", key)
+        } else {
+            Err(CliError::CommandArgumentError(
+                "One of ['--new-private-key', '--new-private-key-file'] must be used".to_string(),
+            ))
+        }
+    }
+}
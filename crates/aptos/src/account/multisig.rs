@@ -0,0 +1,367 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commands for a MultiEd25519 account, whose transactions are signed by a threshold of its keys.
+//!
+//! Every step reads and writes files, so that the signers can sign offline:
+//! 1. `create` saves the public keys and threshold of the account along with its address
+//! 2. `propose` saves an unsigned raw transaction for the account
+//! 3. `sign` saves the signature of the raw transaction by one of the keys
+//! 4. `submit` combines the signatures and submits the transaction once the threshold is met
+
+use crate::{
+    common::{
+        types::{
+            CliCommand, CliError, CliResult, CliTypedResult, EncodingOptions,
+            PrivateKeyInputOptions, ProfileOptions, RestOptions, SaveFile, TransactionSummary,
+            WriteTransactionOptions,
+        },
+        utils::read_from_file,
+    },
+    move_tool::ScriptFunctionOptions,
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PublicKey, Ed25519Signature},
+    multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+    PrivateKey, Signature, SigningKey,
+};
+use aptos_rest_client::Client;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Default time a proposed transaction can wait for its signatures: 1 day
+const DEFAULT_EXPIRATION_SECS: u64 = 86400;
+
+/// Tool for a multisig account, with offline signing
+///
+#[derive(Debug, Subcommand)]
+pub enum MultisigTool {
+    Create(CreateMultisig),
+    Propose(ProposeTransaction),
+    Sign(SignTransaction),
+    Submit(SubmitTransaction),
+}
+
+impl MultisigTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            MultisigTool::Create(tool) => tool.execute_serialized().await,
+            MultisigTool::Propose(tool) => tool.execute_serialized().await,
+            MultisigTool::Sign(tool) => tool.execute_serialized().await,
+            MultisigTool::Submit(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// The keys and threshold of a MultiEd25519 account, as saved by `create`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MultisigAccount {
+    pub address: AccountAddress,
+    pub threshold: u8,
+    pub public_keys: Vec<Ed25519PublicKey>,
+}
+
+impl MultisigAccount {
+    pub fn new(public_keys: Vec<Ed25519PublicKey>, threshold: u8) -> CliTypedResult<Self> {
+        let multi_public_key = MultiEd25519PublicKey::new(public_keys.clone(), threshold)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+        let auth_key = AuthenticationKey::multi_ed25519(&multi_public_key);
+        Ok(MultisigAccount {
+            address: AccountAddress::new(*auth_key.derived_address()),
+            threshold,
+            public_keys,
+        })
+    }
+
+    /// Loads the account, checking that its address is derived from its keys and threshold
+    pub fn load(path: &Path) -> CliTypedResult<Self> {
+        let bytes = read_from_file(path)?;
+        let account: MultisigAccount = serde_yaml::from_slice(&bytes)?;
+        let expected = MultisigAccount::new(account.public_keys.clone(), account.threshold)?;
+        if account.address != expected.address {
+            return Err(CliError::CommandArgumentError(format!(
+                "Multisig account address {} doesn't match its keys and threshold, expected {}",
+                account.address, expected.address
+            )));
+        }
+        Ok(account)
+    }
+
+    pub fn multi_public_key(&self) -> CliTypedResult<MultiEd25519PublicKey> {
+        MultiEd25519PublicKey::new(self.public_keys.clone(), self.threshold)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))
+    }
+
+    /// Loads a raw transaction, checking that it is sent by this account
+    fn load_transaction(&self, path: &Path) -> CliTypedResult<RawTransaction> {
+        let bytes = read_from_file(path)?;
+        let raw_txn: RawTransaction =
+            bcs::from_bytes(&bytes).map_err(|err| CliError::BCS("--transaction-file", err))?;
+        if raw_txn.sender() != self.address {
+            return Err(CliError::CommandArgumentError(format!(
+                "Transaction is sent by {}, not by the multisig account {}",
+                raw_txn.sender(),
+                self.address
+            )));
+        }
+        Ok(raw_txn)
+    }
+
+    /// Returns the index of `public_key` in the account's keys
+    fn key_index(&self, public_key: &Ed25519PublicKey) -> Option<u8> {
+        self.public_keys
+            .iter()
+            .position(|key| key == public_key)
+            .map(|index| index as u8)
+    }
+}
+
+/// A signature of a raw transaction by one of the keys of a multisig account, as saved by `sign`
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PartialSignature {
+    pub public_key: Ed25519PublicKey,
+    pub signature: Ed25519Signature,
+}
+
+/// Command to derive a multisig account from its public keys and threshold
+///
+/// The account can then be created on-chain with `aptos account create --account <ADDRESS>`
+#[derive(Debug, Parser)]
+pub struct CreateMultisig {
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    /// Public keys of the account, encoded in a type as shown in `encoding`
+    #[clap(long, required = true, multiple_values = true)]
+    pub(crate) public_keys: Vec<String>,
+    /// Number of signatures required to sign a transaction
+    #[clap(long)]
+    pub(crate) threshold: u8,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<MultisigAccount> for CreateMultisig {
+    fn command_name(&self) -> &'static str {
+        "CreateMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultisigAccount> {
+        self.save_file.check_file()?;
+        let public_keys = self
+            .public_keys
+            .iter()
+            .map(|key| {
+                self.encoding_options
+                    .encoding
+                    .decode_key("--public-keys", key.as_bytes().to_vec())
+            })
+            .collect::<CliTypedResult<Vec<Ed25519PublicKey>>>()?;
+        let account = MultisigAccount::new(public_keys, self.threshold)?;
+        self.save_file.save_to_file(
+            "Multisig account",
+            serde_yaml::to_string(&account)?.as_bytes(),
+        )?;
+        Ok(account)
+    }
+}
+
+/// Command to propose an unsigned transaction for a multisig account
+///
+/// The raw transaction is saved to a file, to be signed by each signer with `sign`.  Unless given
+/// with `--expiration` or `--expiration-secs`, it expires in a day, leaving time to collect the
+/// signatures.
+#[derive(Debug, Parser)]
+pub struct ProposeTransaction {
+    #[clap(flatten)]
+    pub(crate) txn_options: WriteTransactionOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    /// Multisig account file, as saved by `create`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) multisig_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) script_function_options: ScriptFunctionOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<RawTransaction> for ProposeTransaction {
+    fn command_name(&self) -> &'static str {
+        "ProposeTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<RawTransaction> {
+        self.save_file.check_file()?;
+        let account = MultisigAccount::load(&self.multisig_file)?;
+        let profile = &self.profile_options.profile;
+        let client = Client::new(self.txn_options.rest_options.url(profile)?);
+        self.script_function_options
+            .check_function_args(&client)
+            .await?;
+        let payload = self.script_function_options.create_payload()?;
+
+        let mut txn_options = self.txn_options;
+        if txn_options.expiration.is_none() && txn_options.expiration_secs.is_none() {
+            txn_options.expiration_secs = Some(DEFAULT_EXPIRATION_SECS);
+        }
+        let raw_txn = txn_options
+            .build_transaction(profile, account.address, payload)
+            .await?;
+        let bytes = bcs::to_bytes(&raw_txn).map_err(|err| CliError::BCS("Transaction", err))?;
+        self.save_file.save_to_file("Transaction", &bytes)?;
+        Ok(raw_txn)
+    }
+}
+
+/// Command to sign a proposed transaction with one of the keys of a multisig account
+///
+/// Doesn't connect to the network, so that the key can be kept offline. The transaction is
+/// printed to be reviewed before handing over the signature.
+#[derive(Debug, Parser)]
+pub struct SignTransaction {
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    /// Multisig account file, as saved by `create`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) multisig_file: PathBuf,
+    /// Transaction file, as saved by `propose`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<RawTransaction> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<RawTransaction> {
+        self.save_file.check_file()?;
+        let account = MultisigAccount::load(&self.multisig_file)?;
+        let raw_txn = account.load_transaction(&self.transaction_file)?;
+        let private_key = self.private_key_options.extract_private_key(
+            self.encoding_options.encoding,
+            &self.profile_options.profile,
+        )?;
+        let public_key = private_key.public_key();
+        if account.key_index(&public_key).is_none() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Key {} is not one of the keys of multisig account {}",
+                public_key, account.address
+            )));
+        }
+
+        let signature = private_key.sign(&raw_txn);
+        let partial_signature = PartialSignature {
+            public_key,
+            signature,
+        };
+        self.save_file.save_to_file(
+            "Signature",
+            serde_yaml::to_string(&partial_signature)?.as_bytes(),
+        )?;
+        Ok(raw_txn)
+    }
+}
+
+/// Command to combine the signatures of a proposed transaction and submit it
+///
+/// At least the threshold of the multisig account of signatures must be given.
+#[derive(Debug, Parser)]
+pub struct SubmitTransaction {
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    /// Multisig account file, as saved by `create`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) multisig_file: PathBuf,
+    /// Transaction file, as saved by `propose`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+    /// Signature files, as saved by `sign`
+    #[clap(long, required = true, multiple_values = true, parse(from_os_str))]
+    pub(crate) signature_files: Vec<PathBuf>,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let account = MultisigAccount::load(&self.multisig_file)?;
+        let raw_txn = account.load_transaction(&self.transaction_file)?;
+
+        // Signatures are keyed by the index of their key, so that a key is only counted once
+        let mut signatures = BTreeMap::new();
+        for file in self.signature_files.iter() {
+            let bytes = read_from_file(file)?;
+            let partial_signature: PartialSignature = serde_yaml::from_slice(&bytes)?;
+            let index = account
+                .key_index(&partial_signature.public_key)
+                .ok_or_else(|| {
+                    CliError::CommandArgumentError(format!(
+                        "Signature {} is by key {}, not a key of multisig account {}",
+                        file.display(),
+                        partial_signature.public_key,
+                        account.address
+                    ))
+                })?;
+            partial_signature
+                .signature
+                .verify(&raw_txn, &partial_signature.public_key)
+                .map_err(|err| {
+                    CliError::CommandArgumentError(format!(
+                        "Signature {} is invalid for the transaction: {}",
+                        file.display(),
+                        err
+                    ))
+                })?;
+            signatures.insert(index, partial_signature.signature);
+        }
+        if signatures.len() < account.threshold as usize {
+            return Err(CliError::CommandArgumentError(format!(
+                "Only {} of the {} signatures required by multisig account {} were given",
+                signatures.len(),
+                account.threshold,
+                account.address
+            )));
+        }
+
+        let signature = MultiEd25519Signature::new(
+            signatures
+                .into_iter()
+                .map(|(index, signature)| (signature, index))
+                .collect(),
+        )
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let transaction =
+            SignedTransaction::new_multisig(raw_txn, account.multi_public_key()?, signature);
+
+        let client = Client::new(self.rest_options.url(&self.profile_options.profile)?);
+        let response = client
+            .submit_and_wait(&transaction)
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?;
+        Ok(TransactionSummary::from(response.into_inner()))
+    }
+}
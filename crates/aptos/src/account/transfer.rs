@@ -1,9 +1,9 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::{
-    types::{CliCommand, CliTypedResult, EncodingOptions, ProfileOptions, WriteTransactionOptions},
-    utils::submit_transaction,
+use crate::common::types::{
+    CliCommand, CliTypedResult, EncodingOptions, ProfileOptions, TransactionOutput,
+    WriteTransactionOptions,
 };
 use aptos_rest_client::{aptos_api_types::WriteSetChange, Transaction};
 use aptos_types::account_address::AccountAddress;
@@ -11,7 +11,7 @@ use async_trait::async_trait;
 use cached_framework_packages::aptos_stdlib;
 use clap::Parser;
 use serde::Serialize;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::PathBuf};
 
 /// Command to transfer coins between accounts
 ///
@@ -33,33 +33,29 @@ pub struct TransferCoins {
     /// Amount of coins to transfer
     #[clap(long)]
     pub(crate) amount: u64,
+
+    /// Save the transaction unsigned to this file instead of submitting it
+    ///
+    /// It can then be signed offline with `aptos transaction sign`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) output_unsigned: Option<PathBuf>,
 }
 
 #[async_trait]
-impl CliCommand<TransferSummary> for TransferCoins {
+impl CliCommand<TransactionOutput<TransferSummary>> for TransferCoins {
     fn command_name(&self) -> &'static str {
         "TransferCoins"
     }
 
-    async fn execute(self) -> CliTypedResult<TransferSummary> {
-        let sender_key = self.write_options.private_key_options.extract_private_key(
-            self.encoding_options.encoding,
-            &self.profile_options.profile,
-        )?;
-
-        submit_transaction(
-            self.write_options
-                .rest_options
-                .url(&self.profile_options.profile)?,
-            self.write_options
-                .chain_id(&self.profile_options.profile)
-                .await?,
-            sender_key,
-            aptos_stdlib::encode_test_coin_transfer(self.account, self.amount),
-            self.write_options.max_gas,
-        )
-        .await
-        .map(TransferSummary::from)
+    async fn execute(self) -> CliTypedResult<TransactionOutput<TransferSummary>> {
+        self.write_options
+            .submit_or_save_unsigned(
+                self.encoding_options.encoding,
+                &self.profile_options.profile,
+                aptos_stdlib::encode_test_coin_transfer(self.account, self.amount),
+                self.output_unsigned.as_deref(),
+            )
+            .await
     }
 }
 
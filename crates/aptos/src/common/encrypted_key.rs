@@ -0,0 +1,276 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Private keys encrypted at rest with a passphrase, for profiles and key files.
+//!
+//! The key encrypting the private key is derived from the passphrase with scrypt, and the private
+//! key is encrypted with AES-256-GCM. The format is versioned, so that the algorithms can be
+//! changed while still reading older files.
+//!
+//! The passphrase is read from `APTOS_KEY_PASSPHRASE`, from the file named by
+//! `APTOS_KEY_PASSPHRASE_FILE`, or else prompted for.
+
+use crate::common::{
+    types::{CliError, CliTypedResult, EncodingType},
+    utils::read_from_file,
+};
+use aes_gcm::{
+    aead::{generic_array::GenericArray, Aead, NewAead},
+    Aes256Gcm,
+};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterial};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{convert::TryFrom, path::Path};
+
+/// Environment variable holding the passphrase of encrypted keys
+pub const PASSPHRASE_ENV: &str = "APTOS_KEY_PASSPHRASE";
+/// Environment variable holding the name of a file containing the passphrase of encrypted keys
+pub const PASSPHRASE_FILE_ENV: &str = "APTOS_KEY_PASSPHRASE_FILE";
+
+const VERSION: u8 = 1;
+const SALT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const KEY_LENGTH: usize = 32;
+
+/// scrypt parameters recommended for interactive use: N = 2^15, r = 8, p = 1
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+/// Upper bounds of the scrypt parameters read from key files, so that a crafted file can't make
+/// the key derivation use gigabytes of memory or run for hours: up to 1 GiB with N = 2^20, r = 8
+const MAX_SCRYPT_LOG_N: u8 = 20;
+const MAX_SCRYPT_R: u32 = 8;
+const MAX_SCRYPT_P: u32 = 4;
+
+/// An Ed25519 private key encrypted with a passphrase
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct EncryptedPrivateKey {
+    /// Version of the format
+    version: u8,
+    /// Function deriving the encryption key from the passphrase
+    kdf: Kdf,
+    /// Hex encoded AES-256-GCM nonce
+    nonce: String,
+    /// Hex encoded AES-256-GCM ciphertext of the private key, along with its tag
+    ciphertext: String,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(tag = "algorithm", rename_all = "lowercase")]
+enum Kdf {
+    Scrypt {
+        log_n: u8,
+        r: u32,
+        p: u32,
+        /// Hex encoded salt
+        salt: String,
+    },
+}
+
+impl EncryptedPrivateKey {
+    /// Encrypts `private_key` with `passphrase`
+    pub fn encrypt(private_key: &Ed25519PrivateKey, passphrase: &str) -> CliTypedResult<Self> {
+        Self::encrypt_with_params(private_key, passphrase, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)
+    }
+
+    fn encrypt_with_params(
+        private_key: &Ed25519PrivateKey,
+        passphrase: &str,
+        log_n: u8,
+        r: u32,
+        p: u32,
+    ) -> CliTypedResult<Self> {
+        let mut salt = [0u8; SALT_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        let mut nonce = [0u8; NONCE_LENGTH];
+        OsRng.fill_bytes(&mut nonce);
+        let kdf = Kdf::Scrypt {
+            log_n,
+            r,
+            p,
+            salt: hex::encode(salt),
+        };
+
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&kdf.derive_key(passphrase)?));
+        let ciphertext = cipher
+            .encrypt(
+                GenericArray::from_slice(&nonce),
+                private_key.to_bytes().as_slice(),
+            )
+            .map_err(|_| CliError::UnexpectedError("Failed to encrypt key".to_string()))?;
+        Ok(EncryptedPrivateKey {
+            version: VERSION,
+            kdf,
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the private key with `passphrase`
+    pub fn decrypt(&self, passphrase: &str) -> CliTypedResult<Ed25519PrivateKey> {
+        if self.version != VERSION {
+            return Err(CliError::UnexpectedError(format!(
+                "Unsupported encrypted key version {}, expected {}",
+                self.version, VERSION
+            )));
+        }
+        let nonce = decode_hex("nonce", &self.nonce)?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(CliError::UnableToParse(
+                "Encrypted key",
+                format!("nonce must be {} bytes", NONCE_LENGTH),
+            ));
+        }
+        let ciphertext = decode_hex("ciphertext", &self.ciphertext)?;
+
+        let cipher = Aes256Gcm::new(GenericArray::from_slice(&self.kdf.derive_key(passphrase)?));
+        let bytes = cipher
+            .decrypt(GenericArray::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| {
+                CliError::CommandArgumentError(
+                    "Failed to decrypt key, the passphrase is incorrect".to_string(),
+                )
+            })?;
+        Ed25519PrivateKey::try_from(bytes.as_slice())
+            .map_err(|err| CliError::UnableToParse("Encrypted key", err.to_string()))
+    }
+
+    /// Decrypts the private key, reading the passphrase from the environment or prompting for it
+    pub fn decrypt_with_passphrase(&self, name: &str) -> CliTypedResult<Ed25519PrivateKey> {
+        self.decrypt(&read_passphrase(&format!(
+            "Enter the passphrase of {}",
+            name
+        ))?)
+    }
+
+    /// Parses an encrypted key file, or returns `None` if `bytes` isn't one
+    pub fn from_file_bytes(bytes: &[u8]) -> Option<Self> {
+        serde_yaml::from_slice(bytes).ok()
+    }
+
+    pub fn to_file_bytes(&self) -> CliTypedResult<Vec<u8>> {
+        Ok(serde_yaml::to_string(self)?.into_bytes())
+    }
+}
+
+impl Kdf {
+    fn derive_key(&self, passphrase: &str) -> CliTypedResult<[u8; KEY_LENGTH]> {
+        match self {
+            Kdf::Scrypt { log_n, r, p, salt } => {
+                if *log_n > MAX_SCRYPT_LOG_N || *r > MAX_SCRYPT_R || *p > MAX_SCRYPT_P {
+                    return Err(CliError::UnableToParse(
+                        "scrypt params",
+                        format!(
+                            "log_n {}, r {} and p {} must be at most {}, {} and {}",
+                            log_n, r, p, MAX_SCRYPT_LOG_N, MAX_SCRYPT_R, MAX_SCRYPT_P
+                        ),
+                    ));
+                }
+                let params = scrypt::Params::new(*log_n, *r, *p)
+                    .map_err(|err| CliError::UnableToParse("scrypt params", err.to_string()))?;
+                let mut key = [0u8; KEY_LENGTH];
+                scrypt::scrypt(
+                    passphrase.as_bytes(),
+                    &decode_hex("salt", salt)?,
+                    &params,
+                    &mut key,
+                )
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+                Ok(key)
+            }
+        }
+    }
+}
+
+fn decode_hex(name: &'static str, str: &str) -> CliTypedResult<Vec<u8>> {
+    hex::decode(str).map_err(|err| CliError::UnableToParse(name, err.to_string()))
+}
+
+/// Loads the private key in `path`, decrypting it if it's encrypted, or else decoding it with
+/// `encoding`
+pub fn load_private_key_file(
+    encoding: EncodingType,
+    name: &'static str,
+    path: &Path,
+) -> CliTypedResult<Ed25519PrivateKey> {
+    let bytes = read_from_file(path)?;
+    if let Some(encrypted_key) = EncryptedPrivateKey::from_file_bytes(&bytes) {
+        encrypted_key.decrypt_with_passphrase(&path.display().to_string())
+    } else {
+        encoding.decode_key(name, bytes)
+    }
+}
+
+/// Reads the passphrase from `APTOS_KEY_PASSPHRASE`, the file named by
+/// `APTOS_KEY_PASSPHRASE_FILE`, or else prompts for it with `prompt`
+pub fn read_passphrase(prompt: &str) -> CliTypedResult<String> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase)
+    } else if let Ok(file) = std::env::var(PASSPHRASE_FILE_ENV) {
+        let passphrase = String::from_utf8(read_from_file(Path::new(&file))?)?;
+        Ok(passphrase.trim_end_matches(&['\r', '\n'][..]).to_string())
+    } else {
+        rpassword::read_password_from_tty(Some(&format!("{}: ", prompt)))
+            .map_err(|err| CliError::IO("passphrase".to_string(), err))
+    }
+}
+
+/// Reads a new passphrase like [`read_passphrase`], confirming it when prompted for
+pub fn read_new_passphrase() -> CliTypedResult<String> {
+    let prompted =
+        std::env::var(PASSPHRASE_ENV).is_err() && std::env::var(PASSPHRASE_FILE_ENV).is_err();
+    let passphrase = read_passphrase("Enter a passphrase to encrypt the key with")?;
+    if passphrase.is_empty() {
+        return Err(CliError::CommandArgumentError(
+            "The passphrase must not be empty".to_string(),
+        ));
+    }
+    if prompted && passphrase != read_passphrase("Confirm the passphrase")? {
+        return Err(CliError::CommandArgumentError(
+            "The passphrases don't match".to_string(),
+        ));
+    }
+    Ok(passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EncryptedPrivateKey, Kdf};
+    use crate::common::types::CliError;
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let private_key = Ed25519PrivateKey::generate(&mut StdRng::from_seed([0; 32]));
+        // Weak parameters to keep the test fast
+        let encrypted =
+            EncryptedPrivateKey::encrypt_with_params(&private_key, "passphrase", 4, 8, 1).unwrap();
+
+        let bytes = encrypted.to_file_bytes().unwrap();
+        let parsed = EncryptedPrivateKey::from_file_bytes(&bytes).unwrap();
+        assert_eq!(parsed, encrypted);
+        assert_eq!(parsed.decrypt("passphrase").unwrap(), private_key);
+        assert!(parsed.decrypt("wrong passphrase").is_err());
+
+        // plaintext keys aren't mistaken for encrypted ones
+        assert!(EncryptedPrivateKey::from_file_bytes(b"0x1234").is_none());
+    }
+
+    #[test]
+    fn test_derive_key_rejects_expensive_params() {
+        for (log_n, r, p) in [(21, 8, 1), (4, 9, 1), (4, 8, 5)] {
+            let kdf = Kdf::Scrypt {
+                log_n,
+                r,
+                p,
+                salt: "00".to_string(),
+            };
+            assert!(matches!(
+                kdf.derive_key("passphrase"),
+                Err(CliError::UnableToParse("scrypt params", _))
+            ));
+        }
+    }
+}
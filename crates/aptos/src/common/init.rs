@@ -3,6 +3,7 @@
 
 use crate::{
     common::{
+        encrypted_key::read_new_passphrase,
         types::{
             account_address_from_public_key, CliCommand, CliConfig, CliError, CliTypedResult,
             EncodingOptions, PrivateKeyInputOptions, ProfileConfig, ProfileOptions, PromptOptions,
@@ -40,6 +41,13 @@ pub struct InitTool {
     pub(crate) prompt_options: PromptOptions,
     #[clap(flatten)]
     pub(crate) encoding_options: EncodingOptions,
+    /// Encrypt the private key of the profile with a passphrase
+    ///
+    /// The passphrase is read from `APTOS_KEY_PASSPHRASE`, from the file named by
+    /// `APTOS_KEY_PASSPHRASE_FILE`, or else prompted for.  Profiles whose key is already
+    /// encrypted keep it encrypted.
+    #[clap(long)]
+    pub(crate) encrypt: bool,
 }
 
 #[async_trait]
@@ -127,26 +135,34 @@ impl CliCommand<()> for InitTool {
             eprintln!("Using command line argument for private key");
             private_key
         } else {
-            eprintln!("Enter your private key as a hex literal (0x...) [Current: {} | No input: Generate new key (or keep one if present)]", profile_config.private_key.as_ref().map(|_| "Redacted").unwrap_or("None"));
+            eprintln!("Enter your private key as a hex literal (0x...), or a mnemonic to derive it from [Current: {} | No input: Generate new key (or keep one if present)]", if profile_config.private_key.is_some() || profile_config.is_encrypted() { "Redacted" } else { "None" });
             let input = read_line("Private key")?;
             let input = input.trim();
             if input.is_empty() {
-                if let Some(private_key) = profile_config.private_key {
+                if let Some(private_key) =
+                    profile_config.take_private_key(&self.profile_options.profile)?
+                {
                     eprintln!("No key given, keeping existing key...");
                     private_key
                 } else {
                     eprintln!("No key given, generating key...");
                     GenerateKey::generate_ed25519_in_memory()
                 }
+            } else if input.contains(char::is_whitespace) {
+                eprintln!("Deriving key from mnemonic...");
+                self.private_key_options.derive_private_key(input)?
             } else {
                 Ed25519PrivateKey::from_encoded_string(input)
                     .map_err(|err| CliError::UnableToParse("Ed25519PrivateKey", err.to_string()))?
             }
         };
-        let public_key = private_key.public_key();
-        let address = account_address_from_public_key(&public_key);
-        profile_config.private_key = Some(private_key);
-        profile_config.public_key = Some(public_key);
+        let address = account_address_from_public_key(&private_key.public_key());
+        let passphrase = if self.encrypt || profile_config.is_encrypted() {
+            Some(read_new_passphrase()?)
+        } else {
+            None
+        };
+        profile_config.set_private_key(private_key, passphrase.as_deref())?;
         profile_config.account = Some(address);
 
         // Create account if it doesn't exist
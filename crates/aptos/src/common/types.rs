@@ -3,10 +3,13 @@
 
 use crate::{
     common::{
+        encrypted_key::{load_private_key_file, EncryptedPrivateKey},
         init::{DEFAULT_FAUCET_URL, DEFAULT_REST_URL},
         utils::{
-            check_if_file_exists, read_from_file, to_common_result, to_common_success_result,
-            write_to_file, write_to_file_with_opts, write_to_user_only_file,
+            check_if_file_exists, get_estimated_gas_unit_price, get_sequence_number,
+            read_from_file, sign_transaction, submit_transaction, to_common_result,
+            to_common_success_result, write_to_file, write_to_file_with_opts,
+            write_to_user_only_file,
         },
     },
     genesis::git::from_yaml,
@@ -17,7 +20,14 @@ use aptos_crypto::{
 };
 use aptos_logger::debug;
 use aptos_rest_client::{aptos_api_types::WriteSetChange, Client, Transaction};
-use aptos_types::{chain_id::ChainId, transaction::authenticator::AuthenticationKey};
+use aptos_sdk::{
+    key_derivation::{derive_private_key_from_mnemonic, DerivationPath},
+    transaction_builder::TransactionFactory,
+};
+use aptos_types::{
+    chain_id::ChainId,
+    transaction::{authenticator::AuthenticationKey, RawTransaction, TransactionPayload},
+};
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser};
 use move_deps::move_core_types::account_address::AccountAddress;
@@ -134,6 +144,9 @@ const CONFIG_FOLDER: &str = ".aptos";
 pub struct ProfileConfig {
     /// Private key for commands.
     pub private_key: Option<Ed25519PrivateKey>,
+    /// Private key for commands, encrypted with a passphrase
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encrypted_private_key: Option<EncryptedPrivateKey>,
     /// Public key for commands
     pub public_key: Option<Ed25519PublicKey>,
     /// Account for commands
@@ -144,6 +157,44 @@ pub struct ProfileConfig {
     pub faucet_url: Option<String>,
 }
 
+impl ProfileConfig {
+    /// Takes the private key out of the profile, decrypting it if it's encrypted
+    pub fn take_private_key(&mut self, profile: &str) -> CliTypedResult<Option<Ed25519PrivateKey>> {
+        if let Some(private_key) = self.private_key.take() {
+            Ok(Some(private_key))
+        } else if let Some(ref encrypted_private_key) = self.encrypted_private_key {
+            encrypted_private_key
+                .decrypt_with_passphrase(&format!("the key of profile {}", profile))
+                .map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Sets the private key of the profile, encrypted with `passphrase` if given
+    pub fn set_private_key(
+        &mut self,
+        private_key: Ed25519PrivateKey,
+        passphrase: Option<&str>,
+    ) -> CliTypedResult<()> {
+        self.public_key = Some(private_key.public_key());
+        if let Some(passphrase) = passphrase {
+            self.encrypted_private_key =
+                Some(EncryptedPrivateKey::encrypt(&private_key, passphrase)?);
+            self.private_key = None;
+        } else {
+            self.private_key = Some(private_key);
+            self.encrypted_private_key = None;
+        }
+        Ok(())
+    }
+
+    /// Whether the private key of the profile is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted_private_key.is_some()
+    }
+}
+
 impl Default for CliConfig {
     fn default() -> Self {
         CliConfig {
@@ -445,6 +496,12 @@ pub struct PrivateKeyInputOptions {
     /// Private key encoded in a type as shown in `encoding`
     #[clap(long, group = "private_key_input")]
     private_key: Option<String>,
+    /// BIP-39 mnemonic input file name, to derive the private key from
+    #[clap(long, group = "private_key_input", parse(from_os_str))]
+    mnemonic_file: Option<PathBuf>,
+    /// SLIP-0010 derivation path of the private key derived from a mnemonic
+    #[clap(long, default_value_t = DerivationPath::default())]
+    derivation_path: DerivationPath,
 }
 
 impl PrivateKeyInputOptions {
@@ -455,7 +512,7 @@ impl PrivateKeyInputOptions {
                     .to_encoded_string()
                     .map_err(|err| CliError::UnexpectedError(err.to_string()))?,
             ),
-            private_key_file: None,
+            ..Default::default()
         })
     }
 
@@ -467,13 +524,43 @@ impl PrivateKeyInputOptions {
     ) -> CliTypedResult<Ed25519PrivateKey> {
         if let Some(key) = self.extract_private_key_cli(encoding)? {
             Ok(key)
-        } else if let Some(Some(private_key)) =
-            CliConfig::load_profile(profile)?.map(|p| p.private_key)
+        } else if let Some(private_key) = CliConfig::load_profile(profile)?
+            .unwrap_or_default()
+            .take_private_key(profile)?
         {
             Ok(private_key)
         } else {
             Err(CliError::CommandArgumentError(
-                "One of ['--private-key', '--private-key-file'] must be used".to_string(),
+                "One of ['--private-key', '--private-key-file', '--mnemonic-file'] must be used"
+                    .to_string(),
+            ))
+        }
+    }
+
+    /// Extract private key from CLI args with fallback to config, along with the address of the
+    /// account it signs for
+    ///
+    /// The profile's key signs for the profile's account, whose authentication key may have been
+    /// rotated away from the one its address is derived from.
+    pub fn extract_private_key_and_address(
+        &self,
+        encoding: EncodingType,
+        profile: &str,
+    ) -> CliTypedResult<(Ed25519PrivateKey, AccountAddress)> {
+        if let Some(key) = self.extract_private_key_cli(encoding)? {
+            let address = account_address_from_public_key(&key.public_key());
+            return Ok((key, address));
+        }
+        let mut profile_config = CliConfig::load_profile(profile)?.unwrap_or_default();
+        if let Some(private_key) = profile_config.take_private_key(profile)? {
+            let address = profile_config
+                .account
+                .unwrap_or_else(|| account_address_from_public_key(&private_key.public_key()));
+            Ok((private_key, address))
+        } else {
+            Err(CliError::CommandArgumentError(
+                "One of ['--private-key', '--private-key-file', '--mnemonic-file'] must be used"
+                    .to_string(),
             ))
         }
     }
@@ -484,16 +571,28 @@ impl PrivateKeyInputOptions {
         encoding: EncodingType,
     ) -> CliTypedResult<Option<Ed25519PrivateKey>> {
         if let Some(ref file) = self.private_key_file {
-            Ok(Some(
-                encoding.load_key("--private-key-file", file.as_path())?,
-            ))
+            Ok(Some(load_private_key_file(
+                encoding,
+                "--private-key-file",
+                file.as_path(),
+            )?))
         } else if let Some(ref key) = self.private_key {
             let key = key.as_bytes().to_vec();
             Ok(Some(encoding.decode_key("--private-key", key)?))
+        } else if let Some(ref file) = self.mnemonic_file {
+            let mnemonic = String::from_utf8(read_from_file(file.as_path())?)
+                .map_err(|err| CliError::UnableToParse("--mnemonic-file", err.to_string()))?;
+            Ok(Some(self.derive_private_key(&mnemonic)?))
         } else {
             Ok(None)
         }
     }
+
+    /// Derive the private key from a BIP-39 mnemonic at the `derivation_path`
+    pub fn derive_private_key(&self, mnemonic: &str) -> CliTypedResult<Ed25519PrivateKey> {
+        derive_private_key_from_mnemonic(mnemonic, &self.derivation_path)
+            .map_err(|err| CliError::UnableToParse("Mnemonic", err.to_string()))
+    }
 }
 
 impl ExtractPublicKey for PrivateKeyInputOptions {
@@ -591,7 +690,8 @@ impl RestOptions {
     }
 }
 
-const DEFAULT_MAX_GAS: u64 = 1000;
+pub const DEFAULT_MAX_GAS: u64 = 1000;
+pub const DEFAULT_EXPIRATION_SECS: u64 = 30;
 
 /// Options specific to submitting a private key to the Rest endpoint
 #[derive(Debug, Parser)]
@@ -600,11 +700,42 @@ pub struct WriteTransactionOptions {
     pub private_key_options: PrivateKeyInputOptions,
     #[clap(flatten)]
     pub rest_options: RestOptions,
+    /// Address of the account sending the transaction
+    ///
+    /// Defaults to the account of the profile, or the one derived from the private key.  No
+    /// private key is needed to save an unsigned transaction for it.
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub sender_account: Option<AccountAddress>,
     /// Maximum gas to be used to publish the package
     ///
     /// Defaults to 1000 gas units
     #[clap(long, default_value_t = DEFAULT_MAX_GAS)]
     pub max_gas: u64,
+    /// Gas unit price to pay for the transaction
+    ///
+    /// Defaults to the median gas unit price estimated by the fullnode
+    #[clap(long)]
+    pub gas_unit_price: Option<u64>,
+    /// Sequence number of the transaction
+    ///
+    /// Defaults to the current sequence number of the sender's account
+    #[clap(long)]
+    pub sequence_number: Option<u64>,
+    /// Chain id of the network, by name or number
+    ///
+    /// Defaults to the chain id of the fullnode
+    #[clap(long)]
+    pub chain_id: Option<ChainId>,
+    /// Number of seconds from now until the transaction expires
+    ///
+    /// Defaults to 30 seconds, which is enough for a transaction submitted right away
+    #[clap(long, conflicts_with = "expiration")]
+    pub expiration_secs: Option<u64>,
+    /// Expiration time of the transaction, as a Unix timestamp in seconds
+    ///
+    /// E.g. to leave time to sign and submit a transaction saved unsigned
+    #[clap(long)]
+    pub expiration: Option<u64>,
 }
 
 impl Default for WriteTransactionOptions {
@@ -612,14 +743,23 @@ impl Default for WriteTransactionOptions {
         Self {
             private_key_options: Default::default(),
             rest_options: Default::default(),
+            sender_account: None,
             max_gas: DEFAULT_MAX_GAS,
+            gas_unit_price: None,
+            sequence_number: None,
+            chain_id: None,
+            expiration_secs: None,
+            expiration: None,
         }
     }
 }
 
 impl WriteTransactionOptions {
-    /// Retrieve the chain id from onchain via the Rest API
+    /// Retrieve the chain id from the command line, or from onchain via the Rest API
     pub async fn chain_id(&self, profile: &str) -> CliTypedResult<ChainId> {
+        if let Some(chain_id) = self.chain_id {
+            return Ok(chain_id);
+        }
         let client = Client::new(self.rest_options.url(profile)?);
         let state = client
             .get_ledger_information()
@@ -628,6 +768,143 @@ impl WriteTransactionOptions {
             .into_inner();
         Ok(ChainId::new(state.chain_id))
     }
+
+    /// Retrieve the address of the sender, which only needs the private key if not given
+    pub fn sender_address(
+        &self,
+        encoding: EncodingType,
+        profile: &str,
+    ) -> CliTypedResult<AccountAddress> {
+        if let Some(sender_account) = self.sender_account {
+            Ok(sender_account)
+        } else {
+            self.private_key_options
+                .extract_private_key_and_address(encoding, profile)
+                .map(|(_, address)| address)
+        }
+    }
+
+    /// Retrieve the private key of the sender, along with its address
+    pub fn sender_key_and_address(
+        &self,
+        encoding: EncodingType,
+        profile: &str,
+    ) -> CliTypedResult<(Ed25519PrivateKey, AccountAddress)> {
+        let (private_key, address) = self
+            .private_key_options
+            .extract_private_key_and_address(encoding, profile)?;
+        Ok((private_key, self.sender_account.unwrap_or(address)))
+    }
+
+    /// Build the raw transaction of `payload` for `sender_address`
+    ///
+    /// The sequence number, gas unit price and chain id are retrieved via the Rest API unless
+    /// given, so that with all of them given no network access is needed.  The transaction
+    /// expires at `expiration`, or else `expiration_secs` from now.
+    pub async fn build_transaction(
+        &self,
+        profile: &str,
+        sender_address: AccountAddress,
+        payload: TransactionPayload,
+    ) -> CliTypedResult<RawTransaction> {
+        let client = Client::new(self.rest_options.url(profile)?);
+        let sequence_number = match self.sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => get_sequence_number(&client, sender_address).await?,
+        };
+        let gas_unit_price = match self.gas_unit_price {
+            Some(gas_unit_price) => gas_unit_price,
+            None => get_estimated_gas_unit_price(&client).await?,
+        };
+
+        let mut builder = TransactionFactory::new(self.chain_id(profile).await?)
+            .with_gas_unit_price(gas_unit_price)
+            .with_max_gas_amount(self.max_gas)
+            .with_transaction_expiration_time(
+                self.expiration_secs.unwrap_or(DEFAULT_EXPIRATION_SECS),
+            )
+            .payload(payload)
+            .sender(sender_address)
+            .sequence_number(sequence_number);
+        if let Some(expiration) = self.expiration {
+            builder = builder.expiration_timestamp_secs(expiration);
+        }
+        Ok(builder.build())
+    }
+
+    /// Sign the transaction of `payload` with the sender's private key, and submit it
+    pub async fn submit_transaction(
+        &self,
+        encoding: EncodingType,
+        profile: &str,
+        payload: TransactionPayload,
+    ) -> CliTypedResult<Transaction> {
+        let (sender_key, sender_address) = self.sender_key_and_address(encoding, profile)?;
+        let raw_txn = self
+            .build_transaction(profile, sender_address, payload)
+            .await?;
+        submit_transaction(
+            self.rest_options.url(profile)?,
+            sign_transaction(&sender_key, raw_txn)?,
+        )
+        .await
+    }
+
+    /// Save the unsigned transaction of `payload` to `output_file` in BCS, to be signed offline
+    /// with `aptos transaction sign`
+    pub async fn save_unsigned_transaction(
+        &self,
+        encoding: EncodingType,
+        profile: &str,
+        payload: TransactionPayload,
+        output_file: &Path,
+    ) -> CliTypedResult<RawTransaction> {
+        let sender_address = self.sender_address(encoding, profile)?;
+        let raw_txn = self
+            .build_transaction(profile, sender_address, payload)
+            .await?;
+        let bytes =
+            bcs::to_bytes(&raw_txn).map_err(|err| CliError::BCS("Unsigned transaction", err))?;
+        write_to_file(output_file, "Unsigned transaction", &bytes)?;
+        Ok(raw_txn)
+    }
+
+    /// Either submit the transaction of `payload`, or only save it unsigned to `output_unsigned`
+    pub async fn submit_or_save_unsigned<T: From<Transaction>>(
+        &self,
+        encoding: EncodingType,
+        profile: &str,
+        payload: TransactionPayload,
+        output_unsigned: Option<&Path>,
+    ) -> CliTypedResult<TransactionOutput<T>> {
+        if let Some(output_file) = output_unsigned {
+            self.save_unsigned_transaction(encoding, profile, payload, output_file)
+                .await
+                .map(TransactionOutput::Unsigned)
+        } else {
+            self.submit_transaction(encoding, profile, payload)
+                .await
+                .map(|transaction| TransactionOutput::Submitted(T::from(transaction)))
+        }
+    }
+}
+
+/// The output of a command either submitting its transaction, or saving it unsigned
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum TransactionOutput<T> {
+    Submitted(T),
+    Unsigned(RawTransaction),
+}
+
+impl<T> TransactionOutput<T> {
+    /// The output of the submitted transaction, if it wasn't saved unsigned
+    pub fn submitted(self) -> Option<T> {
+        match self {
+            TransactionOutput::Submitted(output) => Some(output),
+            TransactionOutput::Unsigned(_) => None,
+        }
+    }
 }
 
 /// Options for compiling a move package dir
@@ -685,9 +962,24 @@ pub fn load_account_arg(str: &str) -> Result<AccountAddress, CliError> {
         })
     } else if let Ok(account_address) = AccountAddress::from_str(str) {
         Ok(account_address)
-    } else if let Some(Some(private_key)) = CliConfig::load_profile(str)?.map(|p| p.private_key) {
-        let public_key = private_key.public_key();
-        Ok(account_address_from_public_key(&public_key))
+    } else if let Some(address) = CliConfig::load_profile(str)?.and_then(|profile| {
+        // Fall back to the keys, so the private key needn't be decrypted if it's encrypted
+        profile
+            .account
+            .or_else(|| {
+                profile
+                    .public_key
+                    .as_ref()
+                    .map(account_address_from_public_key)
+            })
+            .or_else(|| {
+                profile
+                    .private_key
+                    .as_ref()
+                    .map(|key| account_address_from_public_key(&key.public_key()))
+            })
+    }) {
+        Ok(address)
     } else {
         Err(CliError::CommandArgumentError(
             "'--account-address' or '--profile' after using aptos init must be provided"
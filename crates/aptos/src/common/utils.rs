@@ -7,12 +7,8 @@ use crate::{
 };
 use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
 use aptos_rest_client::{Client, Transaction};
-use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
 use aptos_telemetry::constants::APTOS_CLI_PUSH_METRICS;
-use aptos_types::{
-    chain_id::ChainId,
-    transaction::{authenticator::AuthenticationKey, TransactionPayload},
-};
+use aptos_types::transaction::{RawTransaction, SignedTransaction};
 use itertools::Itertools;
 use move_deps::move_core_types::account_address::AccountAddress;
 use reqwest::Url;
@@ -252,6 +248,17 @@ pub async fn get_sequence_number(
     Ok(account.sequence_number)
 }
 
+/// Retrieves the median gas unit price estimated by the rest client
+pub async fn get_estimated_gas_unit_price(
+    client: &aptos_rest_client::Client,
+) -> CliTypedResult<u64> {
+    let estimation = client
+        .estimate_gas_price()
+        .await
+        .map_err(|err| CliError::ApiError(err.to_string()))?;
+    Ok(estimation.inner().median.0)
+}
+
 /// Error message for parsing a map
 const PARSE_MAP_SYNTAX_MSG: &str = "Invalid syntax for map. Example: Name=Value,Name2=Value";
 
@@ -286,31 +293,23 @@ where
     Ok(map)
 }
 
-/// Submits a [`TransactionPayload`] as signed by the `sender_key`
+/// Signs a raw transaction with the `sender_key`
+pub fn sign_transaction(
+    sender_key: &Ed25519PrivateKey,
+    raw_txn: RawTransaction,
+) -> CliTypedResult<SignedTransaction> {
+    raw_txn
+        .sign(sender_key, sender_key.public_key())
+        .map(|transaction| transaction.into_inner())
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to sign transaction {}", err)))
+}
+
+/// Submits a signed transaction, and waits for it to be committed
 pub async fn submit_transaction(
     url: Url,
-    chain_id: ChainId,
-    sender_key: Ed25519PrivateKey,
-    payload: TransactionPayload,
-    max_gas: u64,
+    transaction: SignedTransaction,
 ) -> CliTypedResult<Transaction> {
-    let client = Client::new(url);
-
-    // Get sender address
-    let sender_address = AuthenticationKey::ed25519(&sender_key.public_key()).derived_address();
-    let sender_address = AccountAddress::new(*sender_address);
-
-    // Get sequence number for account
-    let sequence_number = get_sequence_number(&client, sender_address).await?;
-
-    // Sign and submit transaction
-    let transaction_factory = TransactionFactory::new(chain_id)
-        .with_gas_unit_price(1)
-        .with_max_gas_amount(max_gas);
-    let sender_account = &mut LocalAccount::new(sender_address, sender_key, sequence_number);
-    let transaction =
-        sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
-    let response = client
+    let response = Client::new(url)
         .submit_and_wait(&transaction)
         .await
         .map_err(|err| CliError::ApiError(err.to_string()))?;
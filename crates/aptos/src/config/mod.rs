@@ -1,7 +1,11 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::common::types::{CliCommand, CliResult};
+use crate::common::{
+    encrypted_key::read_new_passphrase,
+    types::{CliCommand, CliConfig, CliError, CliResult, CliTypedResult, ProfileOptions},
+};
+use async_trait::async_trait;
 use clap::Parser;
 
 /// Tool for configuration of the CLI tool
@@ -9,12 +13,54 @@ use clap::Parser;
 #[derive(Parser)]
 pub enum ConfigTool {
     Init(crate::common::init::InitTool),
+    EncryptProfile(EncryptProfile),
 }
 
 impl ConfigTool {
     pub async fn execute(self) -> CliResult {
         match self {
             ConfigTool::Init(tool) => tool.execute_serialized_success().await,
+            ConfigTool::EncryptProfile(tool) => tool.execute_serialized_success().await,
         }
     }
 }
+
+/// Encrypts the private key of a profile with a passphrase
+///
+/// The plaintext private key is removed from `.aptos/config.yaml`, and commands using the profile
+/// then read the passphrase from `APTOS_KEY_PASSPHRASE`, from the file named by
+/// `APTOS_KEY_PASSPHRASE_FILE`, or else prompt for it.  An encrypted key is re-encrypted with the
+/// new passphrase.
+#[derive(Debug, Parser)]
+pub struct EncryptProfile {
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for EncryptProfile {
+    fn command_name(&self) -> &'static str {
+        "EncryptProfile"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let profile = &self.profile_options.profile;
+        let mut config = CliConfig::load()?;
+        let profile_config = config
+            .profiles
+            .as_mut()
+            .and_then(|profiles| profiles.get_mut(profile))
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!("Profile {} not found", profile))
+            })?;
+        let private_key = profile_config.take_private_key(profile)?.ok_or_else(|| {
+            CliError::CommandArgumentError(format!("Profile {} has no private key", profile))
+        })?;
+
+        let passphrase = read_new_passphrase()?;
+        profile_config.set_private_key(private_key, Some(&passphrase))?;
+        config.save()?;
+        eprintln!("Encrypted the private key of profile {}", profile);
+        Ok(())
+    }
+}
@@ -8,8 +8,10 @@ pub mod common;
 pub mod config;
 pub mod genesis;
 pub mod move_tool;
+pub mod node;
 pub mod op;
 pub mod test;
+pub mod transaction;
 
 use crate::common::types::{CliCommand, CliResult};
 use clap::Parser;
@@ -30,6 +32,10 @@ pub enum Tool {
     Key(op::key::KeyTool),
     #[clap(subcommand)]
     Move(move_tool::MoveTool),
+    #[clap(subcommand)]
+    Node(node::NodeTool),
+    #[clap(subcommand)]
+    Transaction(transaction::TransactionTool),
 }
 
 impl Tool {
@@ -42,6 +48,8 @@ impl Tool {
             Tool::Init(tool) => tool.execute_serialized_success().await,
             Tool::Key(tool) => tool.execute().await,
             Tool::Move(tool) => tool.execute().await,
+            Tool::Node(tool) => tool.execute().await,
+            Tool::Transaction(tool) => tool.execute().await,
         }
     }
 }
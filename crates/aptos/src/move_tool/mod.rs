@@ -7,14 +7,17 @@ use crate::{
     common::{
         types::{
             load_account_arg, AccountAddressWrapper, CliError, CliTypedResult, EncodingOptions,
-            MovePackageDir, ProfileOptions, PromptOptions, TransactionSummary,
+            MovePackageDir, ProfileOptions, PromptOptions, TransactionOutput, TransactionSummary,
             WriteTransactionOptions,
         },
-        utils::{check_if_file_exists, submit_transaction},
+        utils::{check_if_file_exists, read_from_file},
     },
     CliCommand, CliResult,
 };
-use aptos_rest_client::aptos_api_types::MoveType;
+use aptos_rest_client::{
+    aptos_api_types::{MoveFunctionVisibility, MoveType},
+    Client,
+};
 use aptos_types::transaction::{ModuleBundle, ScriptFunction, TransactionPayload};
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
@@ -33,9 +36,11 @@ use move_deps::{
     },
     move_unit_test::UnitTestingConfig,
 };
+use serde::Deserialize;
 use std::{
     collections::BTreeMap,
     convert::TryFrom,
+    fmt::{Display, Formatter},
     fs::create_dir_all,
     io::Write,
     path::{Path, PathBuf},
@@ -270,29 +275,19 @@ impl CliCommand<TransactionSummary> for PublishPackage {
         let compiled_payload = TransactionPayload::ModuleBundle(ModuleBundle::new(compiled_units));
 
         // Now that it's compiled, lets send it
-        let sender_key = self.write_options.private_key_options.extract_private_key(
-            self.encoding_options.encoding,
-            &self.profile_options.profile,
-        )?;
-
-        submit_transaction(
-            self.write_options
-                .rest_options
-                .url(&self.profile_options.profile)?,
-            self.write_options
-                .chain_id(&self.profile_options.profile)
-                .await?,
-            sender_key,
-            compiled_payload,
-            self.write_options.max_gas,
-        )
-        .await
-        .map(TransactionSummary::from)
+        self.write_options
+            .submit_transaction(
+                self.encoding_options.encoding,
+                &self.profile_options.profile,
+                compiled_payload,
+            )
+            .await
+            .map(TransactionSummary::from)
     }
 }
 
 /// Run a Move function
-#[derive(Parser)]
+#[derive(Debug, Parser)]
 pub struct RunFunction {
     #[clap(flatten)]
     encoding_options: EncodingOptions,
@@ -300,34 +295,85 @@ pub struct RunFunction {
     write_options: WriteTransactionOptions,
     #[clap(flatten)]
     profile_options: ProfileOptions,
+    #[clap(flatten)]
+    script_function_options: ScriptFunctionOptions,
+    /// Save the transaction unsigned to this file instead of submitting it
+    ///
+    /// It can then be signed offline with `aptos transaction sign`
+    #[clap(long, parse(from_os_str))]
+    output_unsigned: Option<PathBuf>,
+    /// Skip checking the arguments against the on-chain signature of the function
+    ///
+    /// The check is always skipped with `--output-unsigned`, as the transaction may be built
+    /// offline.
+    #[clap(long)]
+    skip_function_check: bool,
+}
+
+/// Options to call a script function
+#[derive(Debug, Parser)]
+pub struct ScriptFunctionOptions {
     /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
     ///
     /// Example: `0x842ed41fad9640a2ad08fdd7d3e4f7f505319aac7d67e1c0dd6a7cce8732c7e3::Message::set_message`
     #[clap(long, parse(try_from_str = parse_function_name))]
-    function_id: FunctionId,
-    /// Hex encoded arguments separated by spaces.
+    pub(crate) function_id: FunctionId,
+    /// Arguments combined with their type separated by spaces.
+    ///
+    /// Supported types [address, bool, hex, string, u8, u64, u128, vector<TYPE>]
     ///
-    /// Example: `0x01 0x02 0x03`
+    /// Example: `address:0x1 bool:true u8:0 vector<u64>:[1,2,3] vector<hex>:[0x01,0x0203]`
     #[clap(long, multiple_values = true)]
-    args: Vec<ArgWithType>,
+    pub(crate) args: Vec<ArgWithType>,
+    /// JSON file of the arguments, instead of `--args`
+    ///
+    /// The file holds a list of arguments with their type, e.g.
+    /// `[{"type": "vector<u64>", "value": [1, 2, 3]}, {"type": "string", "value": "hello"}]`
+    #[clap(long, parse(from_os_str), conflicts_with = "args")]
+    pub(crate) args_file: Option<PathBuf>,
     /// TypeTag arguments separated by spaces.
     ///
     /// Example: `u8 u64 u128 bool address vector true false signer`
     #[clap(long, multiple_values = true)]
-    type_args: Vec<MoveType>,
+    pub(crate) type_args: Vec<MoveType>,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for RunFunction {
+impl CliCommand<TransactionOutput<TransactionSummary>> for RunFunction {
     fn command_name(&self) -> &'static str {
         "RunFunction"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+    async fn execute(self) -> CliTypedResult<TransactionOutput<TransactionSummary>> {
+        if !self.skip_function_check && self.output_unsigned.is_none() {
+            let client = Client::new(
+                self.write_options
+                    .rest_options
+                    .url(&self.profile_options.profile)?,
+            );
+            self.script_function_options
+                .check_function_args(&client)
+                .await?;
+        }
+        let payload = self.script_function_options.create_payload()?;
+        self.write_options
+            .submit_or_save_unsigned(
+                self.encoding_options.encoding,
+                &self.profile_options.profile,
+                payload,
+                self.output_unsigned.as_deref(),
+            )
+            .await
+    }
+}
+
+impl ScriptFunctionOptions {
+    /// Create the payload calling the script function
+    pub fn create_payload(&self) -> CliTypedResult<TransactionPayload> {
         let args: Vec<Vec<u8>> = self
-            .args
-            .iter()
-            .map(|arg_with_type| arg_with_type.arg.clone())
+            .load_args()?
+            .into_iter()
+            .map(|arg_with_type| arg_with_type.arg)
             .collect();
         let mut type_args: Vec<TypeTag> = Vec::new();
 
@@ -344,27 +390,97 @@ impl CliCommand<TransactionSummary> for RunFunction {
             type_args,
             args,
         );
+        Ok(TransactionPayload::ScriptFunction(script_function))
+    }
 
-        submit_transaction(
-            self.write_options
-                .rest_options
-                .url(&self.profile_options.profile)?,
-            self.write_options
-                .chain_id(&self.profile_options.profile)
-                .await?,
-            self.write_options.private_key_options.extract_private_key(
-                self.encoding_options.encoding,
-                &self.profile_options.profile,
-            )?,
-            TransactionPayload::ScriptFunction(script_function),
-            self.write_options.max_gas,
-        )
-        .await
-        .map(TransactionSummary::from)
+    /// Checks the type arguments and the arguments against the on-chain signature of the
+    /// function, so that mistakes are reported before submitting the transaction
+    pub async fn check_function_args(&self, client: &Client) -> CliTypedResult<()> {
+        let module_id = &self.function_id.module_id;
+        let modules = client
+            .get_account_modules(*module_id.address())
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?
+            .into_inner();
+        let function = modules
+            .into_iter()
+            .filter_map(|module| module.try_parse_abi().ok().and_then(|module| module.abi))
+            .filter(|abi| abi.name.as_str() == module_id.name().as_str())
+            .flat_map(|abi| abi.exposed_functions)
+            .find(|function| function.name == self.function_id.function_id)
+            .ok_or_else(|| {
+                CliError::CommandArgumentError(format!(
+                    "Function {}::{} not found on-chain",
+                    module_id, self.function_id.function_id
+                ))
+            })?;
+        if function.visibility != MoveFunctionVisibility::Script {
+            return Err(CliError::CommandArgumentError(format!(
+                "Function {} is not an entry function",
+                function.name
+            )));
+        }
+        if function.generic_type_params.len() != self.type_args.len() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Function {} takes {} type arguments, but {} were given",
+                function.name,
+                function.generic_type_params.len(),
+                self.type_args.len()
+            )));
+        }
+
+        // The signer is the sender of the transaction, rather than an argument
+        let params: Vec<&MoveType> = function
+            .params
+            .iter()
+            .skip_while(|param| param.is_signer())
+            .collect();
+        let args = self.load_args()?;
+        if params.len() != args.len() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Function {} takes {} arguments ({}), but {} were given",
+                function.name,
+                params.len(),
+                params
+                    .iter()
+                    .map(|param| param.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                args.len()
+            )));
+        }
+        for (index, (param, arg)) in params.iter().zip(args.iter()).enumerate() {
+            // Generic parameters are checked by the VM against the type arguments
+            if matches!(param, MoveType::GenericTypeParam { .. }) {
+                continue;
+            }
+            if !arg.ty.matches(param) {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Argument {} of function {} must be of type {}, but a {} was given",
+                    index, function.name, param, arg.ty
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads the arguments from `--args`, or else from `--args-file`
+    fn load_args(&self) -> CliTypedResult<Vec<ArgWithType>> {
+        if let Some(ref file) = self.args_file {
+            let json_args: Vec<JsonArg> = serde_json::from_slice(&read_from_file(file)?)
+                .map_err(|err| CliError::UnableToParse("--args-file", err.to_string()))?;
+            json_args.into_iter().map(ArgWithType::try_from).collect()
+        } else {
+            Ok(self.args.clone())
+        }
     }
 }
 
-#[derive(Clone, Debug)]
+/// Type of a function argument
+///
+/// `hex` is a `vector<u8>` given as a hex string, and `string` is a `vector<u8>` or a
+/// `0x1::string::String` given as UTF-8.
+#[derive(Clone, Debug, PartialEq)]
 enum FunctionArgType {
     Address,
     Bool,
@@ -373,21 +489,26 @@ enum FunctionArgType {
     U8,
     U64,
     U128,
+    Vector(Box<FunctionArgType>),
 }
 
 impl FunctionArgType {
+    /// Parses `arg` and encodes it in BCS
+    ///
+    /// Vectors are given as comma separated lists in brackets, e.g. `[1,2,3]` or `[[0x1],[]]`.
+    /// The elements of a list are trimmed and can be double quoted, e.g. `["a,b", "c"]`, whatever
+    /// their type. Any other `arg` is taken as is, so `string:" a "` is the string `" a "`,
+    /// quotes included.
     fn parse_arg(&self, arg: &str) -> CliTypedResult<Vec<u8>> {
         match self {
-            FunctionArgType::Address => bcs::to_bytes(
-                &AccountAddress::from_str(arg)
-                    .map_err(|err| CliError::UnableToParse("address", err.to_string()))?,
-            ),
+            FunctionArgType::Address => bcs::to_bytes(&load_account_arg(arg)?),
             FunctionArgType::Bool => bcs::to_bytes(
                 &bool::from_str(arg)
                     .map_err(|err| CliError::UnableToParse("bool", err.to_string()))?,
             ),
             FunctionArgType::Hex => bcs::to_bytes(
-                &hex::decode(arg).map_err(|err| CliError::UnableToParse("hex", err.to_string()))?,
+                &hex::decode(arg.strip_prefix("0x").unwrap_or(arg))
+                    .map_err(|err| CliError::UnableToParse("hex", err.to_string()))?,
             ),
             FunctionArgType::String => bcs::to_bytes(arg),
             FunctionArgType::U8 => bcs::to_bytes(
@@ -401,14 +522,94 @@ impl FunctionArgType {
                 &u128::from_str(arg)
                     .map_err(|err| CliError::UnableToParse("u128", err.to_string()))?,
             ),
+            FunctionArgType::Vector(items) => {
+                let items = split_list(arg)?
+                    .iter()
+                    .map(|item| items.parse_arg(&unquote(item)?))
+                    .collect::<CliTypedResult<Vec<_>>>()?;
+                return Ok(encode_vector(items));
+            }
         }
         .map_err(|err| CliError::BCS("arg", err))
     }
+
+    /// Encodes a JSON `value` in BCS
+    ///
+    /// Scalars can be given as JSON strings as well, e.g. for `u128` values too large for JSON
+    /// numbers, and vectors as JSON arrays or strings parsed like on the command line.
+    fn parse_json_arg(&self, value: &serde_json::Value) -> CliTypedResult<Vec<u8>> {
+        match (self, value) {
+            (FunctionArgType::Vector(items), serde_json::Value::Array(values)) => {
+                let items = values
+                    .iter()
+                    .map(|value| items.parse_json_arg(value))
+                    .collect::<CliTypedResult<Vec<_>>>()?;
+                Ok(encode_vector(items))
+            }
+            (_, serde_json::Value::String(arg)) => self.parse_arg(arg),
+            (_, serde_json::Value::Bool(_)) | (_, serde_json::Value::Number(_)) => {
+                self.parse_arg(&value.to_string())
+            }
+            _ => Err(CliError::CommandArgumentError(format!(
+                "Invalid JSON value {} for argument of type {}",
+                value, self
+            ))),
+        }
+    }
+
+    /// Whether an argument of this type can be passed as a parameter of type `ty`
+    fn matches(&self, ty: &MoveType) -> bool {
+        match (self, ty) {
+            (FunctionArgType::Address, MoveType::Address)
+            | (FunctionArgType::Bool, MoveType::Bool)
+            | (FunctionArgType::U8, MoveType::U8)
+            | (FunctionArgType::U64, MoveType::U64)
+            | (FunctionArgType::U128, MoveType::U128) => true,
+            (FunctionArgType::Hex, MoveType::Vector { items })
+            | (FunctionArgType::String, MoveType::Vector { items }) => {
+                matches!(**items, MoveType::U8)
+            }
+            (FunctionArgType::String, MoveType::Struct(tag)) => tag.to_string() == STRING_STRUCT,
+            (FunctionArgType::Vector(arg_items), MoveType::Vector { items }) => {
+                arg_items.matches(items)
+            }
+            _ => false,
+        }
+    }
+}
+
+const STRING_STRUCT: &str = "0x1::string::String";
+
+impl Display for FunctionArgType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FunctionArgType::Address => write!(f, "address"),
+            FunctionArgType::Bool => write!(f, "bool"),
+            FunctionArgType::Hex => write!(f, "hex"),
+            FunctionArgType::String => write!(f, "string"),
+            FunctionArgType::U8 => write!(f, "u8"),
+            FunctionArgType::U64 => write!(f, "u64"),
+            FunctionArgType::U128 => write!(f, "u128"),
+            FunctionArgType::Vector(items) => write!(f, "vector<{}>", items),
+        }
+    }
 }
 
 impl FromStr for FunctionArgType {
     type Err = CliError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == STRING_STRUCT {
+            return Ok(FunctionArgType::String);
+        }
+        if let Some(items) = s
+            .strip_prefix("vector<")
+            .and_then(|items| items.strip_suffix('>'))
+        {
+            return Ok(FunctionArgType::Vector(Box::new(
+                FunctionArgType::from_str(items)?,
+            )));
+        }
         match s.to_lowercase().as_str() {
             "address" => Ok(FunctionArgType::Address),
             "bool" => Ok(FunctionArgType::Bool),
@@ -417,14 +618,99 @@ impl FromStr for FunctionArgType {
             "u8" => Ok(FunctionArgType::U8),
             "u64" => Ok(FunctionArgType::U64),
             "u128" => Ok(FunctionArgType::U128),
-            str => Err(CliError::CommandArgumentError(format!("Invalid arg type '{}'.  Must be one of: ['address','bool','hex','string','u8','u64','u128']", str))),
+            str => Err(CliError::CommandArgumentError(format!("Invalid arg type '{}'.  Must be one of: ['address','bool','hex','string','u8','u64','u128','vector<TYPE>']", str))),
+        }
+    }
+}
+
+/// Splits a bracketed list into its trimmed elements, e.g. `[[1,2], [3]]` into `[1,2]` and `[3]`
+fn split_list(arg: &str) -> CliTypedResult<Vec<String>> {
+    let inner = arg
+        .trim()
+        .strip_prefix('[')
+        .and_then(|inner| inner.strip_suffix(']'))
+        .ok_or_else(|| {
+            CliError::UnableToParse(
+                "vector",
+                format!("'{}' must be a list in brackets e.g. [1,2,3]", arg),
+            )
+        })?;
+    if inner.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut items = Vec::new();
+    let mut item = String::new();
+    let mut depth = 0usize;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for c in inner.chars() {
+        if in_quotes {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_quotes = false;
+            }
+        } else {
+            match c {
+                '"' => in_quotes = true,
+                '[' => depth += 1,
+                ']' => {
+                    depth = depth.checked_sub(1).ok_or_else(|| {
+                        CliError::UnableToParse("vector", format!("Unbalanced brackets in {}", arg))
+                    })?
+                }
+                ',' if depth == 0 => {
+                    items.push(std::mem::take(&mut item).trim().to_string());
+                    continue;
+                }
+                _ => {}
+            }
         }
+        item.push(c);
+    }
+    if in_quotes || depth != 0 {
+        return Err(CliError::UnableToParse(
+            "vector",
+            format!("Unbalanced brackets or quotes in {}", arg),
+        ));
+    }
+    items.push(item.trim().to_string());
+    Ok(items)
+}
+
+/// Removes the double quotes around `arg`, if any
+fn unquote(arg: &str) -> CliTypedResult<String> {
+    if arg.len() >= 2 && arg.starts_with('"') && arg.ends_with('"') {
+        serde_json::from_str(arg).map_err(|err| CliError::UnableToParse("string", err.to_string()))
+    } else {
+        Ok(arg.to_string())
     }
 }
 
+/// Encodes BCS encoded `items` as a BCS vector, prefixed by its ULEB128 encoded length
+fn encode_vector(items: Vec<Vec<u8>>) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut len = items.len();
+    while len >= 0x80 {
+        bytes.push((len as u8 & 0x7f) | 0x80);
+        len >>= 7;
+    }
+    bytes.push(len as u8);
+    for item in items {
+        bytes.extend(item);
+    }
+    bytes
+}
+
 /// A parseable arg with a type separated by a colon
+///
+/// Example: `vector<u64>:[1,2,3]`
+#[derive(Clone, Debug)]
 pub struct ArgWithType {
-    _ty: FunctionArgType,
+    ty: FunctionArgType,
     arg: Vec<u8>,
 }
 
@@ -432,27 +718,50 @@ impl FromStr for ArgWithType {
     type Err = CliError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<_> = s.split(':').collect();
-        if parts.len() != 2 {
-            return Err(CliError::CommandArgumentError(
-                "Arguments must be pairs of <type>:<arg> e.g. bool:true".to_string(),
-            ));
-        }
+        // The type ends at the first colon that's not part of a `::` in a struct name
+        let bytes = s.as_bytes();
+        let separator = (0..bytes.len()).find(|&i| {
+            bytes[i] == b':' && bytes.get(i + 1) != Some(&b':') && (i == 0 || bytes[i - 1] != b':')
+        });
+        let separator = separator.ok_or_else(|| {
+            CliError::CommandArgumentError(
+                "Arguments must be pairs of <type>:<arg> e.g. bool:true or vector<u64>:[1,2]"
+                    .to_string(),
+            )
+        })?;
+
+        let ty = FunctionArgType::from_str(&s[..separator])?;
+        let arg = ty.parse_arg(&s[separator + 1..])?;
+
+        Ok(ArgWithType { ty, arg })
+    }
+}
+
+/// An argument in a JSON arguments file
+#[derive(Deserialize)]
+struct JsonArg {
+    #[serde(rename = "type")]
+    ty: String,
+    value: serde_json::Value,
+}
 
-        let ty = FunctionArgType::from_str(parts.first().unwrap())?;
-        let arg = parts.last().unwrap();
-        let arg = ty.parse_arg(arg)?;
+impl TryFrom<JsonArg> for ArgWithType {
+    type Error = CliError;
 
-        Ok(ArgWithType { _ty: ty, arg })
+    fn try_from(json_arg: JsonArg) -> Result<Self, Self::Error> {
+        let ty = FunctionArgType::from_str(&json_arg.ty)?;
+        let arg = ty.parse_json_arg(&json_arg.value)?;
+        Ok(ArgWithType { ty, arg })
     }
 }
 
+#[derive(Debug)]
 pub struct FunctionId {
     pub module_id: ModuleId,
     pub function_id: Identifier,
 }
 
-fn parse_function_name(function_id: &str) -> CliTypedResult<FunctionId> {
+pub(crate) fn parse_function_name(function_id: &str) -> CliTypedResult<FunctionId> {
     let ids: Vec<&str> = function_id.split_terminator("::").collect();
     if ids.len() != 3 {
         return Err(CliError::CommandArgumentError(
@@ -471,3 +780,132 @@ fn parse_function_name(function_id: &str) -> CliTypedResult<FunctionId> {
         function_id,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_function_name, ArgWithType, FunctionArgType, RunFunction, ScriptFunctionOptions,
+    };
+    use crate::common::types::{
+        CliCommand, RestOptions, TransactionOutput, WriteTransactionOptions,
+    };
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        chain_id::ChainId,
+        transaction::{RawTransaction, TransactionPayload},
+    };
+    use move_deps::move_core_types::account_address::AccountAddress;
+    use std::str::FromStr;
+
+    fn parse(arg: &str) -> Vec<u8> {
+        ArgWithType::from_str(arg).unwrap().arg
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse("u64:5"), bcs::to_bytes(&5u64).unwrap());
+        assert_eq!(
+            parse("vector<u64>:[1, 2, 3]"),
+            bcs::to_bytes(&vec![1u64, 2, 3]).unwrap()
+        );
+        assert_eq!(
+            parse("vector<address>:[0x1,0x2]"),
+            bcs::to_bytes(&vec![
+                AccountAddress::ONE,
+                AccountAddress::from_hex_literal("0x2").unwrap()
+            ])
+            .unwrap()
+        );
+        assert_eq!(
+            parse("vector<vector<u8>>:[[1,2],[]]"),
+            bcs::to_bytes(&vec![vec![1u8, 2], vec![]]).unwrap()
+        );
+        assert_eq!(
+            parse("vector<hex>:[0x0102,03]"),
+            bcs::to_bytes(&vec![vec![1u8, 2], vec![3]]).unwrap()
+        );
+        assert_eq!(
+            parse(r#"vector<string>:["a,b", "c\"]"]"#),
+            bcs::to_bytes(&vec!["a,b", "c\"]"]).unwrap()
+        );
+        assert_eq!(
+            parse("0x1::string::String:a:b"),
+            bcs::to_bytes("a:b").unwrap()
+        );
+        assert_eq!(parse(r#"string: "a" "#), bcs::to_bytes(r#" "a" "#).unwrap());
+        assert_eq!(
+            parse(r#"vector<bool>:[true, "false"]"#),
+            bcs::to_bytes(&vec![true, false]).unwrap()
+        );
+        assert_eq!(
+            parse(r#"vector<u64>:["1", 2]"#),
+            bcs::to_bytes(&vec![1u64, 2]).unwrap()
+        );
+        assert_eq!(
+            parse("vector<u64>:[]"),
+            bcs::to_bytes(&Vec::<u64>::new()).unwrap()
+        );
+
+        assert!(ArgWithType::from_str("vector<u64>:[1,2").is_err());
+        assert!(ArgWithType::from_str("vector<u8>:[256]").is_err());
+        assert!(ArgWithType::from_str("vector<u256>:[1]").is_err());
+        assert!(ArgWithType::from_str("u64").is_err());
+        assert!(ArgWithType::from_str(r#"u64:"1""#).is_err());
+    }
+
+    #[test]
+    fn test_parse_json_args() {
+        let ty = FunctionArgType::from_str("vector<u128>").unwrap();
+        let value = serde_json::json!([1, "340282366920938463463374607431768211455"]);
+        assert_eq!(
+            ty.parse_json_arg(&value).unwrap(),
+            bcs::to_bytes(&vec![1u128, u128::MAX]).unwrap()
+        );
+        assert!(FunctionArgType::Bool
+            .parse_json_arg(&serde_json::json!({}))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_run_function_unsigned_offline() {
+        let output_file = TempPath::new();
+        let sender = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let run_function = RunFunction {
+            encoding_options: Default::default(),
+            write_options: WriteTransactionOptions {
+                // Nothing listens there, so any request to the node fails
+                rest_options: RestOptions::new(Some("http://127.0.0.1:1".parse().unwrap())),
+                sender_account: Some(sender),
+                gas_unit_price: Some(1),
+                sequence_number: Some(7),
+                chain_id: Some(ChainId::test()),
+                ..Default::default()
+            },
+            profile_options: Default::default(),
+            script_function_options: ScriptFunctionOptions {
+                function_id: parse_function_name("0x1::Coin::transfer").unwrap(),
+                args: vec![ArgWithType::from_str("address:0x1").unwrap()],
+                args_file: None,
+                type_args: vec![],
+            },
+            output_unsigned: Some(output_file.path().to_path_buf()),
+            skip_function_check: false,
+        };
+
+        let raw_txn = match run_function.execute().await.unwrap() {
+            TransactionOutput::Unsigned(raw_txn) => raw_txn,
+            TransactionOutput::Submitted(_) => panic!("Transaction must be saved unsigned"),
+        };
+        assert_eq!(raw_txn.sender(), sender);
+        assert_eq!(
+            bcs::from_bytes::<RawTransaction>(&std::fs::read(output_file.path()).unwrap()).unwrap(),
+            raw_txn
+        );
+        match raw_txn.into_payload() {
+            TransactionPayload::ScriptFunction(script_function) => {
+                assert_eq!(script_function.function().as_str(), "transfer")
+            }
+            _ => panic!("expected a script function payload"),
+        }
+    }
+}
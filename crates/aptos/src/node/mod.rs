@@ -0,0 +1,264 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{
+            account_address_from_public_key, CliCommand, CliConfig, CliError, CliResult,
+            CliTypedResult, PromptOptions,
+        },
+        utils::{fund_account, prompt_yes_with_override},
+    },
+    op::key::GenerateKey,
+};
+use aptos_config::config::{NodeConfig, PersistableConfig};
+use aptos_crypto::PrivateKey;
+use aptos_faucet::{funder_pool::FunderPoolConfig, rate_limit::RateLimitConfig, FaucetArgs};
+use aptos_rest_client::Client;
+use aptos_types::chain_id::ChainId;
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use hex::FromHex;
+use rand::{rngs::StdRng, SeedableRng};
+use reqwest::Url;
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
+
+const NODE_CONFIG_FILE: &str = "0/node.yaml";
+const MINT_KEY_FILE: &str = "mint.key";
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const NUM_DEFAULT_COINS: u64 = 10000;
+
+/// Tool for operations related to nodes
+///
+#[derive(Debug, Subcommand)]
+pub enum NodeTool {
+    RunLocalTestnet(RunLocalTestnet),
+}
+
+impl NodeTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            NodeTool::RunLocalTestnet(tool) => tool.execute_serialized_success().await,
+        }
+    }
+}
+
+/// Run a local testnet, a single validator network with a faucet
+///
+/// The genesis, the node config and the database are kept in `config_path`, so that the testnet
+/// resumes from where it stopped when restarted.  Once the testnet is up, the profile `profile`
+/// is set up with a funded account on it.  The testnet runs until interrupted with ctrl-c.
+#[derive(Debug, Parser)]
+pub struct RunLocalTestnet {
+    /// Directory to keep the config and the database of the testnet in
+    #[clap(long, parse(from_os_str), default_value = ".aptos/testnet")]
+    config_path: PathBuf,
+    /// Wipe the testnet in `config_path` and start over from a new genesis
+    #[clap(long)]
+    force_restart: bool,
+    /// RNG seed of the genesis keys, as 32 hex encoded bytes
+    #[clap(long, parse(try_from_str = FromHex::from_hex))]
+    seed: Option<[u8; 32]>,
+    /// Port to run the faucet on, which only listens on 127.0.0.1
+    #[clap(long, default_value_t = 8081)]
+    faucet_port: u16,
+    /// Don't run a faucet alongside the node
+    #[clap(long)]
+    no_faucet: bool,
+    /// Profile to set up for the testnet
+    #[clap(long, default_value = "local")]
+    profile: String,
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for RunLocalTestnet {
+    fn command_name(&self) -> &'static str {
+        "RunLocalTestnet"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        if self.force_restart && self.config_path.exists() {
+            prompt_yes_with_override(
+                &format!(
+                    "Are you sure you want to delete the testnet in {}?",
+                    self.config_path.display()
+                ),
+                self.prompt_options,
+            )?;
+            std::fs::remove_dir_all(&self.config_path)
+                .map_err(|err| CliError::IO(self.config_path.display().to_string(), err))?;
+            eprintln!("Deleted the testnet in {}", self.config_path.display());
+        }
+
+        // The node runs on its own thread, the test environment blocks until the process exits
+        let config_path = self.config_path.clone();
+        let rng = self
+            .seed
+            .map(StdRng::from_seed)
+            .unwrap_or_else(StdRng::from_entropy);
+        let node_thread = thread::spawn(move || {
+            aptos_node::load_test_environment(
+                Some(config_path),
+                false,
+                false,
+                cached_framework_packages::module_blobs().to_vec(),
+                rng,
+            )
+        });
+
+        // Wait for the node to generate its config and serve the REST API
+        let node_config_file = &self.config_path.join(NODE_CONFIG_FILE);
+        let start = Instant::now();
+        wait_for(start, &node_thread, move || async move {
+            node_config_file.exists()
+        })
+        .await?;
+        let node_config = NodeConfig::load_config(node_config_file).map_err(|err| {
+            CliError::UnexpectedError(format!("Failed to load node config: {}", err))
+        })?;
+        let rest_url = Url::parse(&format!(
+            "http://127.0.0.1:{}",
+            node_config.api.address.port()
+        ))
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        let client = &Client::new(rest_url.clone());
+        wait_for(start, &node_thread, move || async move {
+            client.get_ledger_information().await.is_ok()
+        })
+        .await?;
+        eprintln!("Node API is ready at {}", rest_url);
+
+        let faucet_url = if self.no_faucet {
+            None
+        } else {
+            let faucet_url = Url::parse(&format!("http://127.0.0.1:{}", self.faucet_port))
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            // The faucet mints from the root account, so it's only served locally
+            let faucet = FaucetArgs {
+                address: "127.0.0.1".to_string(),
+                port: self.faucet_port,
+                server_url: rest_url.to_string(),
+                mint_key_file_path: self.config_path.join(MINT_KEY_FILE).display().to_string(),
+                mint_key: None,
+                mint_account_address: None,
+                chain_id: ChainId::test(),
+                maximum_amount: None,
+                do_not_delegate: true,
+                rate_limit_config: RateLimitConfig::default(),
+                funder_pool_config: FunderPoolConfig::default(),
+            };
+            tokio::spawn(faucet.run());
+            let health_url = &faucet_url
+                .join("health")
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            wait_for(start, &node_thread, move || async move {
+                reqwest::get(health_url.clone())
+                    .await
+                    .map(|response| response.status().is_success())
+                    .unwrap_or(false)
+            })
+            .await?;
+            eprintln!("Faucet is ready at {}", faucet_url);
+            Some(faucet_url)
+        };
+
+        self.setup_profile(client, rest_url, faucet_url).await?;
+
+        eprintln!("Local testnet is running, press ctrl-c to exit");
+        tokio::signal::ctrl_c()
+            .await
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        Ok(())
+    }
+}
+
+impl RunLocalTestnet {
+    /// Points the profile at the testnet, with an account funded by the faucet
+    ///
+    /// The profile's key is kept across restarts, and its account is recreated if the testnet was
+    /// restarted from a new genesis.
+    async fn setup_profile(
+        &self,
+        client: &Client,
+        rest_url: Url,
+        faucet_url: Option<Url>,
+    ) -> CliTypedResult<()> {
+        let mut config = if CliConfig::config_exists() {
+            CliConfig::load()?
+        } else {
+            CliConfig::default()
+        };
+        let profiles = config.profiles.get_or_insert_with(HashMap::new);
+        let mut profile_config = profiles.remove(&self.profile).unwrap_or_default();
+
+        let address = match profile_config.account {
+            Some(address)
+                if profile_config.private_key.is_some() || profile_config.is_encrypted() =>
+            {
+                address
+            }
+            _ => {
+                let private_key = GenerateKey::generate_ed25519_in_memory();
+                let address = account_address_from_public_key(&private_key.public_key());
+                profile_config.set_private_key(private_key, None)?;
+                profile_config.account = Some(address);
+                address
+            }
+        };
+        profile_config.rest_url = Some(rest_url.to_string());
+        profile_config.faucet_url = faucet_url.as_ref().map(|url| url.to_string());
+
+        if let Some(faucet_url) = faucet_url {
+            if client.get_account(address).await.is_err() {
+                fund_account(faucet_url, NUM_DEFAULT_COINS, address).await?;
+                eprintln!(
+                    "Created account {} with {} coins",
+                    address, NUM_DEFAULT_COINS
+                );
+            }
+        }
+
+        profiles.insert(self.profile.clone(), profile_config);
+        config.save()?;
+        eprintln!(
+            "Profile {} is set up for account {} on the testnet",
+            self.profile, address
+        );
+        Ok(())
+    }
+}
+
+/// Polls `is_ready` until it's true, failing if the node stops or doesn't start in time
+async fn wait_for<F, Fut>(
+    start: Instant,
+    node_thread: &thread::JoinHandle<()>,
+    is_ready: F,
+) -> CliTypedResult<()>
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = bool>,
+{
+    while !is_ready().await {
+        if node_thread.is_finished() {
+            return Err(CliError::UnexpectedError(
+                "The node stopped while starting, see its log in the config path".to_string(),
+            ));
+        }
+        if start.elapsed() > STARTUP_TIMEOUT {
+            return Err(CliError::UnexpectedError(format!(
+                "The testnet didn't start within {} seconds",
+                STARTUP_TIMEOUT.as_secs()
+            )));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    Ok(())
+}
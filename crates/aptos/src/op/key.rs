@@ -3,16 +3,22 @@
 
 use crate::{
     common::{
+        encrypted_key::{read_new_passphrase, EncryptedPrivateKey},
         types::{
             CliError, CliTypedResult, EncodingOptions, EncodingType, ExtractPublicKey, KeyType,
             PrivateKeyInputOptions, ProfileOptions, SaveFile,
         },
-        utils::{append_file_extension, check_if_file_exists, write_to_file},
+        utils::{
+            append_file_extension, check_if_file_exists, write_to_file, write_to_user_only_file,
+        },
     },
     CliCommand, CliResult,
 };
 use aptos_config::config::{Peer, PeerRole};
 use aptos_crypto::{ed25519, x25519, PrivateKey, Uniform, ValidCryptoMaterial};
+use aptos_sdk::key_derivation::{
+    derive_private_key_from_mnemonic, generate_mnemonic, DerivationPath,
+};
 use aptos_types::account_address::{from_identity_public_key, AccountAddress};
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
@@ -23,11 +29,14 @@ use std::{
 };
 
 pub const PUBLIC_KEY_EXTENSION: &str = "pub";
+pub const MNEMONIC_EXTENSION: &str = "mnemonic";
+const MNEMONIC_WORD_COUNT: usize = 24;
 
 /// CLI tool for generating, inspecting, and interacting with keys.
 #[derive(Debug, Subcommand)]
 pub enum KeyTool {
     Generate(GenerateKey),
+    Encrypt(EncryptKey),
     ExtractPeer(ExtractPeer),
 }
 
@@ -35,6 +44,7 @@ impl KeyTool {
     pub async fn execute(self) -> CliResult {
         match self {
             KeyTool::Generate(tool) => tool.execute_serialized().await,
+            KeyTool::Encrypt(tool) => tool.execute_serialized().await,
             KeyTool::ExtractPeer(tool) => tool.execute_serialized().await,
         }
     }
@@ -98,11 +108,34 @@ impl CliCommand<HashMap<AccountAddress, Peer>> for ExtractPeer {
 /// `output_file` and `output_file.pub`.  `output_file` will contain the private
 /// key encoded with the `encoding` and `output_file.pub` will contain the public
 /// key encoded with the `encoding`.
+///
+/// With `mnemonic`, the key is derived from a new BIP-39 mnemonic saved to `output_file.mnemonic`,
+/// which backs up all the keys derived from it.
+///
+/// With `encrypt`, the `ed25519` private key is encrypted with a passphrase in `output_file`,
+/// which other commands read with `--private-key-file`.  It can't be combined with `mnemonic`, as
+/// the mnemonic would be saved unencrypted.
 #[derive(Debug, Parser)]
 pub struct GenerateKey {
     /// Key type: `x25519` or `ed25519`
     #[clap(long, default_value_t = KeyType::Ed25519)]
     key_type: KeyType,
+    /// Derive the key from a new 24 word mnemonic
+    ///
+    /// More keys can then be derived from the mnemonic with `--mnemonic-file` and
+    /// `--derivation-path` in other commands
+    #[clap(long)]
+    mnemonic: bool,
+    /// SLIP-0010 derivation path of the key derived from the mnemonic
+    #[clap(long, default_value_t = DerivationPath::default())]
+    derivation_path: DerivationPath,
+    /// Encrypt the private key with a passphrase
+    ///
+    /// The passphrase is read from `APTOS_KEY_PASSPHRASE`, from the file named by
+    /// `APTOS_KEY_PASSPHRASE_FILE`, or else prompted for.  Only `ed25519` keys can be encrypted,
+    /// and not along with `--mnemonic`.
+    #[clap(long, conflicts_with = "mnemonic")]
+    encrypt: bool,
     #[clap(flatten)]
     save_params: SaveKey,
 }
@@ -115,20 +148,53 @@ impl CliCommand<HashMap<&'static str, PathBuf>> for GenerateKey {
 
     async fn execute(self) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
         self.save_params.check_key_file()?;
+        let passphrase = if self.encrypt {
+            if !matches!(self.key_type, KeyType::Ed25519) {
+                return Err(CliError::CommandArgumentError(
+                    "Only ed25519 keys can be encrypted".to_string(),
+                ));
+            }
+            Some(read_new_passphrase()?)
+        } else {
+            None
+        };
 
-        // Generate a ed25519 key
-        let ed25519_key = Self::generate_ed25519_in_memory();
+        // Generate a ed25519 key, possibly from a new mnemonic
+        let (ed25519_key, mnemonic_file) = if self.mnemonic {
+            let mnemonic_file = self.save_params.mnemonic_file()?;
+            check_if_file_exists(&mnemonic_file, self.save_params.file_options.prompt_options)?;
+            let mut rng = rand::rngs::StdRng::from_entropy();
+            let mnemonic = generate_mnemonic(&mut rng, MNEMONIC_WORD_COUNT)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            let ed25519_key = derive_private_key_from_mnemonic(&mnemonic, &self.derivation_path)
+                .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+            write_to_user_only_file(&mnemonic_file, "Mnemonic", mnemonic.as_bytes())?;
+            (ed25519_key, Some(mnemonic_file))
+        } else {
+            (Self::generate_ed25519_in_memory(), None)
+        };
 
         // Convert it to the appropriate type and save it
-        match self.key_type {
+        let mut map = match self.key_type {
             KeyType::X25519 => {
                 let private_key =
                     x25519::PrivateKey::from_ed25519_private_bytes(&ed25519_key.to_bytes())
                         .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
                 self.save_params.save_key(&private_key, "x25519")
             }
-            KeyType::Ed25519 => self.save_params.save_key(&ed25519_key, "ed25519"),
+            KeyType::Ed25519 => {
+                if let Some(ref passphrase) = passphrase {
+                    self.save_params
+                        .save_encrypted_key(&ed25519_key, "ed25519", passphrase)
+                } else {
+                    self.save_params.save_key(&ed25519_key, "ed25519")
+                }
+            }
+        }?;
+        if let Some(mnemonic_file) = mnemonic_file {
+            map.insert("Mnemonic Path", mnemonic_file);
         }
+        Ok(map)
     }
 }
 
@@ -191,6 +257,39 @@ impl GenerateKey {
     }
 }
 
+/// Encrypts an existing `ed25519` private key with a passphrase
+///
+/// The private key is read from `private-key`, `private-key-file`, `mnemonic-file` or the
+/// profile, and saved encrypted to `output_file`, along with its public key to
+/// `output_file.pub`.  This is used to migrate plaintext key files.
+#[derive(Debug, Parser)]
+pub struct EncryptKey {
+    #[clap(flatten)]
+    private_key_input_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
+    #[clap(flatten)]
+    save_params: SaveKey,
+}
+
+#[async_trait]
+impl CliCommand<HashMap<&'static str, PathBuf>> for EncryptKey {
+    fn command_name(&self) -> &'static str {
+        "EncryptKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
+        self.save_params.check_key_file()?;
+        let private_key = self.private_key_input_options.extract_private_key(
+            self.save_params.encoding_options.encoding,
+            &self.profile_options.profile,
+        )?;
+        let passphrase = read_new_passphrase()?;
+        self.save_params
+            .save_encrypted_key(&private_key, "ed25519", &passphrase)
+    }
+}
+
 #[derive(Debug, Parser)]
 pub struct SaveKey {
     #[clap(flatten)]
@@ -208,6 +307,11 @@ impl SaveKey {
         )
     }
 
+    /// Mnemonic file name
+    fn mnemonic_file(&self) -> CliTypedResult<PathBuf> {
+        append_file_extension(self.file_options.output_file.as_path(), MNEMONIC_EXTENSION)
+    }
+
     /// Check if the key file exists already
     pub fn check_key_file(&self) -> CliTypedResult<()> {
         // Check if file already exists
@@ -222,15 +326,37 @@ impl SaveKey {
         key_name: &'static str,
     ) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
         let encoded_private_key = self.encoding_options.encoding.encode_key(key_name, key)?;
+        self.save_key_files(key_name, &encoded_private_key, &key.public_key())
+    }
+
+    /// Saves an ed25519 key to a file encrypted with `passphrase`, and its public key encoded in
+    /// a string
+    pub fn save_encrypted_key(
+        &self,
+        key: &ed25519::Ed25519PrivateKey,
+        key_name: &'static str,
+        passphrase: &str,
+    ) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
+        let encrypted_private_key =
+            EncryptedPrivateKey::encrypt(key, passphrase)?.to_file_bytes()?;
+        self.save_key_files(key_name, &encrypted_private_key, &key.public_key())
+    }
+
+    fn save_key_files<PublicKey: ValidCryptoMaterial>(
+        &self,
+        key_name: &'static str,
+        private_key_bytes: &[u8],
+        public_key: &PublicKey,
+    ) -> CliTypedResult<HashMap<&'static str, PathBuf>> {
         let encoded_public_key = self
             .encoding_options
             .encoding
-            .encode_key(key_name, &key.public_key())?;
+            .encode_key(key_name, public_key)?;
 
         // Write private and public keys to files
         let public_key_file = self.public_key_file()?;
         self.file_options
-            .save_to_file_confidential(key_name, &encoded_private_key)?;
+            .save_to_file_confidential(key_name, private_key_bytes)?;
         write_to_file(&public_key_file, key_name, &encoded_public_key)?;
 
         let mut map = HashMap::new();
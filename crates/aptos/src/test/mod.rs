@@ -5,24 +5,39 @@ use crate::{
     account::{
         create::{CreateAccount, DEFAULT_FUNDED_COINS},
         fund::FundAccount,
+        key_rotation::RotateKey,
         list::{ListAccount, ListQuery},
+        multisig::{
+            CreateMultisig, MultisigAccount, ProposeTransaction, SignTransaction, SubmitTransaction,
+        },
         transfer::{TransferCoins, TransferSummary},
     },
     common::{
         init::InitTool,
         types::{
             CliConfig, CliTypedResult, EncodingOptions, PrivateKeyInputOptions, ProfileOptions,
-            PromptOptions, RestOptions, WriteTransactionOptions,
+            PromptOptions, RestOptions, SaveFile, TransactionOutput, TransactionSummary,
+            WriteTransactionOptions,
         },
     },
+    move_tool::{parse_function_name, ArgWithType, ScriptFunctionOptions},
     op::key::GenerateKey,
-    CliCommand,
+    transaction, CliCommand,
+};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    ValidCryptoMaterialStringExt,
 };
-use aptos_crypto::ed25519::Ed25519PrivateKey;
+use aptos_rest_client::aptos_api_types::MoveType;
 use aptos_sdk::move_types::account_address::AccountAddress;
+use aptos_types::transaction::RawTransaction;
 use reqwest::Url;
 use serde_json::Value;
-use std::{str::FromStr, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 use tokio::time::{sleep, Instant};
 
 /// A framework for testing the CLI
@@ -62,7 +77,7 @@ impl CliTestFramework {
             write_options: WriteTransactionOptions {
                 private_key_options: PrivateKeyInputOptions::from_private_key(mint_key)?,
                 rest_options: RestOptions::new(Some(self.endpoint.clone())),
-                max_gas: 1000,
+                ..Default::default()
             },
             profile_options: profile(index),
             account: Self::account_id(index),
@@ -124,6 +139,195 @@ impl CliTestFramework {
             profile_options: profile(sender_index),
             account: receiver_account,
             amount,
+            output_unsigned: None,
+        }
+        .execute()
+        .await
+        .map(|output| output.submitted().expect("Transaction must be submitted"))
+    }
+
+    /// Saves the transfer of coins unsigned, instead of submitting it
+    pub async fn transfer_coins_unsigned(
+        &self,
+        sender_index: usize,
+        receiver_index: usize,
+        amount: u64,
+        output_file: &Path,
+    ) -> CliTypedResult<RawTransaction> {
+        let receiver_account = Self::account_id(receiver_index);
+
+        TransferCoins {
+            write_options: WriteTransactionOptions {
+                sender_account: Some(Self::account_id(sender_index)),
+                ..Default::default()
+            },
+            encoding_options: Default::default(),
+            profile_options: profile(sender_index),
+            account: receiver_account,
+            amount,
+            output_unsigned: Some(output_file.to_path_buf()),
+        }
+        .execute()
+        .await
+        .map(|output| match output {
+            TransactionOutput::Unsigned(raw_txn) => raw_txn,
+            TransactionOutput::Submitted(_) => panic!("Transaction must be saved unsigned"),
+        })
+    }
+
+    pub async fn sign_transaction(
+        &self,
+        index: usize,
+        transaction_file: &Path,
+        output_file: &Path,
+    ) -> CliTypedResult<RawTransaction> {
+        transaction::SignTransaction {
+            encoding_options: Default::default(),
+            private_key_options: Default::default(),
+            profile_options: profile(index),
+            transaction_file: transaction_file.to_path_buf(),
+            save_file: save_file(output_file),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn submit_transaction(
+        &self,
+        transaction_file: &Path,
+    ) -> CliTypedResult<TransactionSummary> {
+        transaction::SubmitTransaction {
+            rest_options: RestOptions::new(Some(self.endpoint.clone())),
+            profile_options: Default::default(),
+            transaction_file: transaction_file.to_path_buf(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn rotate_key(
+        &self,
+        index: usize,
+        new_private_key: &Ed25519PrivateKey,
+    ) -> CliTypedResult<TransactionSummary> {
+        RotateKey {
+            write_options: Default::default(),
+            encoding_options: Default::default(),
+            profile_options: profile(index),
+            new_private_key_file: None,
+            new_private_key: Some(
+                new_private_key
+                    .to_encoded_string()
+                    .expect("Must serialize private key to hex"),
+            ),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn create_multisig(
+        &self,
+        public_keys: &[Ed25519PublicKey],
+        threshold: u8,
+        output_file: &Path,
+    ) -> CliTypedResult<MultisigAccount> {
+        CreateMultisig {
+            encoding_options: Default::default(),
+            public_keys: public_keys
+                .iter()
+                .map(|key| {
+                    key.to_encoded_string()
+                        .expect("Must serialize public key to hex")
+                })
+                .collect(),
+            threshold,
+            save_file: save_file(output_file),
+        }
+        .execute()
+        .await
+    }
+
+    /// Funds the multisig account from the faucet, creating it if it doesn't exist
+    pub async fn fund_multisig(
+        &self,
+        index: usize,
+        multisig_account: &MultisigAccount,
+    ) -> CliTypedResult<String> {
+        FundAccount {
+            profile_options: profile(index),
+            account: multisig_account.address,
+            faucet_options: Default::default(),
+            num_coins: DEFAULT_FUNDED_COINS,
+        }
+        .execute()
+        .await
+    }
+
+    /// Proposes a transfer of coins from the multisig account to the account `receiver_index`
+    pub async fn propose_multisig_transfer(
+        &self,
+        multisig_file: &Path,
+        receiver_index: usize,
+        amount: u64,
+        output_file: &Path,
+    ) -> CliTypedResult<RawTransaction> {
+        let receiver_account = Self::account_id(receiver_index);
+        ProposeTransaction {
+            txn_options: WriteTransactionOptions {
+                rest_options: RestOptions::new(Some(self.endpoint.clone())),
+                expiration_secs: Some(600),
+                ..Default::default()
+            },
+            profile_options: Default::default(),
+            multisig_file: multisig_file.to_path_buf(),
+            script_function_options: ScriptFunctionOptions {
+                function_id: parse_function_name("0x1::Coin::transfer")?,
+                args: vec![
+                    ArgWithType::from_str(&format!("address:{}", receiver_account))?,
+                    ArgWithType::from_str(&format!("u64:{}", amount))?,
+                ],
+                type_args: vec![
+                    MoveType::from_str("0x1::TestCoin::TestCoin").expect("Must parse coin type")
+                ],
+                args_file: None,
+            },
+            save_file: save_file(output_file),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn sign_multisig_transaction(
+        &self,
+        private_key: &Ed25519PrivateKey,
+        multisig_file: &Path,
+        transaction_file: &Path,
+        output_file: &Path,
+    ) -> CliTypedResult<RawTransaction> {
+        SignTransaction {
+            encoding_options: Default::default(),
+            private_key_options: private_key_options(private_key),
+            profile_options: Default::default(),
+            multisig_file: multisig_file.to_path_buf(),
+            transaction_file: transaction_file.to_path_buf(),
+            save_file: save_file(output_file),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn submit_multisig_transaction(
+        &self,
+        multisig_file: &Path,
+        transaction_file: &Path,
+        signature_files: Vec<PathBuf>,
+    ) -> CliTypedResult<TransactionSummary> {
+        SubmitTransaction {
+            rest_options: RestOptions::new(Some(self.endpoint.clone())),
+            profile_options: Default::default(),
+            multisig_file: multisig_file.to_path_buf(),
+            transaction_file: transaction_file.to_path_buf(),
+            signature_files,
         }
         .execute()
         .await
@@ -137,6 +341,7 @@ impl CliTestFramework {
             profile_options: profile(index),
             prompt_options: PromptOptions::yes(),
             encoding_options: EncodingOptions::default(),
+            encrypt: false,
         }
         .execute()
         .await
@@ -206,6 +411,13 @@ impl CliTestFramework {
             .expect("Expected to already be initialized");
         profile.account.expect("Expected to have account address")
     }
+
+    pub fn private_key(index: usize) -> Ed25519PrivateKey {
+        let profile = CliConfig::load_profile(&index.to_string())
+            .expect("Must select account in bounds")
+            .expect("Expected to already be initialized");
+        profile.private_key.expect("Expected to have private key")
+    }
 }
 
 fn profile(index: usize) -> ProfileOptions {
@@ -218,3 +430,10 @@ fn private_key_options(private_key: &Ed25519PrivateKey) -> PrivateKeyInputOption
     PrivateKeyInputOptions::from_private_key(private_key)
         .expect("Must serialize private key to hex")
 }
+
+fn save_file(output_file: &Path) -> SaveFile {
+    SaveFile {
+        output_file: output_file.to_path_buf(),
+        prompt_options: PromptOptions::yes(),
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Commands to sign and submit transactions separately, for the private key to be kept offline.
+//!
+//! The unsigned transaction is saved with `--output-unsigned` by the commands submitting
+//! transactions, e.g. `aptos account transfer`, signed offline with `aptos transaction sign` and
+//! submitted with `aptos transaction submit`.
+
+use crate::common::{
+    types::{
+        CliCommand, CliError, CliResult, CliTypedResult, EncodingOptions, PrivateKeyInputOptions,
+        ProfileOptions, RestOptions, SaveFile, TransactionSummary,
+    },
+    utils::{read_from_file, sign_transaction, submit_transaction},
+};
+use aptos_types::transaction::{RawTransaction, SignedTransaction};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+/// Tool for signing and submitting transactions separately
+///
+#[derive(Debug, Subcommand)]
+pub enum TransactionTool {
+    Sign(SignTransaction),
+    Submit(SubmitTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            TransactionTool::Sign(tool) => tool.execute_serialized().await,
+            TransactionTool::Submit(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Command to sign an unsigned transaction, as saved with `--output-unsigned`
+///
+/// Doesn't connect to the network, so that the private key can be kept offline.  The transaction
+/// is printed to be reviewed before submitting it with `aptos transaction submit`.
+#[derive(Debug, Parser)]
+pub struct SignTransaction {
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    /// Unsigned transaction file, as saved with `--output-unsigned`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<RawTransaction> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<RawTransaction> {
+        self.save_file.check_file()?;
+        let bytes = read_from_file(&self.transaction_file)?;
+        let raw_txn: RawTransaction =
+            bcs::from_bytes(&bytes).map_err(|err| CliError::BCS("--transaction-file", err))?;
+        let private_key = self.private_key_options.extract_private_key(
+            self.encoding_options.encoding,
+            &self.profile_options.profile,
+        )?;
+
+        let transaction = sign_transaction(&private_key, raw_txn.clone())?;
+        let bytes =
+            bcs::to_bytes(&transaction).map_err(|err| CliError::BCS("Signed transaction", err))?;
+        self.save_file.save_to_file("Signed transaction", &bytes)?;
+        Ok(raw_txn)
+    }
+}
+
+/// Command to submit a signed transaction, as saved by `aptos transaction sign`
+///
+#[derive(Debug, Parser)]
+pub struct SubmitTransaction {
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    /// Signed transaction file, as saved by `aptos transaction sign`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let bytes = read_from_file(&self.transaction_file)?;
+        let transaction: SignedTransaction =
+            bcs::from_bytes(&bytes).map_err(|err| CliError::BCS("--transaction-file", err))?;
+        submit_transaction(
+            self.rest_options.url(&self.profile_options.profile)?,
+            transaction,
+        )
+        .await
+        .map(TransactionSummary::from)
+    }
+}
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use ::aptos_logger::*;
-use anyhow::{format_err, Context, Result};
-use aptos_rest_client::{Client as RestClient, PendingTransaction, Response};
+use anyhow::{bail, format_err, Context, Result};
+use aptos_rest_client::Client as RestClient;
 use aptos_sdk::{
     move_types::account_address::AccountAddress,
     transaction_builder::TransactionFactory,
@@ -210,13 +210,23 @@ impl SubmissionWorker {
             let start_time = Instant::now();
             let wait_until = start_time + wait_duration;
             let mut txn_offset_time = 0u64;
-            for request in requests {
+            for batch in requests.chunks(MAX_TXN_BATCH_SIZE) {
                 let cur_time = Instant::now();
-                txn_offset_time += (cur_time - start_time).as_millis() as u64;
-                self.stats.submitted.fetch_add(1, Ordering::Relaxed);
-                let resp = self.client.submit(&request).await;
-                if let Err(e) = resp {
-                    warn!("[{:?}] Failed to submit request: {:?}", self.client, e);
+                txn_offset_time += (cur_time - start_time).as_millis() as u64 * batch.len() as u64;
+                self.stats
+                    .submitted
+                    .fetch_add(batch.len() as u64, Ordering::Relaxed);
+                match self.client.submit_batch(batch).await {
+                    Ok(statuses) => {
+                        for status in statuses.inner() {
+                            if !status.is_accepted() {
+                                debug!("[{:?}] Request rejected: {:?}", self.client, status);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        warn!("[{:?}] Failed to submit requests: {:?}", self.client, e);
+                    }
                 }
             }
             if self.params.wait_committed {
@@ -696,12 +706,14 @@ pub async fn execute_and_wait_transactions(
         account.address()
     );
 
-    let pending_txns: Vec<Response<PendingTransaction>> =
-        try_join_all(txns.iter().map(|t| client.submit(t))).await?;
+    let statuses = client.submit_batch(&txns).await?.into_inner();
+    if let Some(status) = statuses.iter().find(|status| !status.is_accepted()) {
+        bail!("transaction rejected: {:?}", status);
+    }
 
-    for pt in pending_txns {
+    for txn in &txns {
         client
-            .wait_for_transaction(&pt.into_inner())
+            .wait_for_signed_transaction(txn)
             .await
             .context("wait for transactions failed")?;
     }
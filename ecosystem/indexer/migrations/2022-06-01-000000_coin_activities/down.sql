@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+drop table if exists current_coin_balances;
+drop index if exists coin_activities_owner_index;
+drop table if exists coin_activities;
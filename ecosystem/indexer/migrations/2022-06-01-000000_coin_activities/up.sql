@@ -0,0 +1,29 @@
+-- Your SQL goes here
+CREATE TABLE coin_activities
+(
+    transaction_version BIGINT NOT NULL,
+    -- Position of the activity within its transaction: its events in order, then the gas fee
+    activity_index BIGINT NOT NULL,
+    owner_address VARCHAR NOT NULL,
+    coin_type VARCHAR NOT NULL,
+    -- One of 'deposit', 'withdraw' or 'gas_fee'
+    activity_type VARCHAR NOT NULL,
+    amount NUMERIC NOT NULL,
+    is_transaction_success BOOLEAN NOT NULL,
+    transaction_hash VARCHAR(255) NOT NULL,
+    transaction_timestamp TIMESTAMP NOT NULL,
+    inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (transaction_version, activity_index)
+);
+CREATE INDEX coin_activities_owner_index ON coin_activities (owner_address, coin_type, transaction_version);
+
+CREATE TABLE current_coin_balances
+(
+    owner_address VARCHAR NOT NULL,
+    coin_type VARCHAR NOT NULL,
+    amount NUMERIC NOT NULL,
+    last_transaction_version BIGINT NOT NULL,
+    last_transaction_timestamp TIMESTAMP NOT NULL,
+    inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (owner_address, coin_type)
+);
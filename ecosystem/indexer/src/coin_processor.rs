@@ -0,0 +1,350 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    database::{execute_with_better_error, PgDbPool, PgPoolConnection, MAX_INSERT_ROWS},
+    indexer::{
+        errors::TransactionProcessingError, processing_result::ProcessingResult,
+        transaction_processor::TransactionProcessor,
+    },
+    models::coin::{CoinActivityModel, CurrentCoinBalanceModel},
+    schema,
+};
+use aptos_rest_client::Transaction;
+use async_trait::async_trait;
+use diesel::{
+    sql_types::{BigInt, Numeric, Text, Timestamp},
+    Connection, RunQueryDsl,
+};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
+
+pub struct CoinProcessor {
+    connection_pool: PgDbPool,
+}
+
+impl CoinProcessor {
+    pub fn new(connection_pool: PgDbPool) -> Self {
+        Self { connection_pool }
+    }
+}
+
+impl Debug for CoinProcessor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = &self.connection_pool.state();
+        write!(
+            f,
+            "CoinProcessor {{ connections: {:?}  idle_connections: {:?} }}",
+            state.connections, state.idle_connections
+        )
+    }
+}
+
+fn insert_coin_activities(
+    conn: &PgPoolConnection,
+    activities: &[CoinActivityModel],
+) -> diesel::QueryResult<()> {
+    for chunk in activities.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::coin_activities::table)
+                .values(chunk)
+                .on_conflict_do_nothing(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Transactions may be processed out of order, so a balance is only overwritten by one written
+/// at the same or a later version.
+fn upsert_current_coin_balance(
+    conn: &PgPoolConnection,
+    balance: &CurrentCoinBalanceModel,
+) -> diesel::QueryResult<usize> {
+    diesel::sql_query(
+        "INSERT INTO current_coin_balances \
+            (owner_address, coin_type, amount, last_transaction_version, last_transaction_timestamp) \
+         VALUES ($1, $2, $3, $4, $5) \
+         ON CONFLICT (owner_address, coin_type) DO UPDATE SET \
+            amount = EXCLUDED.amount, \
+            last_transaction_version = EXCLUDED.last_transaction_version, \
+            last_transaction_timestamp = EXCLUDED.last_transaction_timestamp \
+         WHERE current_coin_balances.last_transaction_version <= EXCLUDED.last_transaction_version",
+    )
+    .bind::<Text, _>(&balance.owner_address)
+    .bind::<Text, _>(&balance.coin_type)
+    .bind::<Numeric, _>(&balance.amount)
+    .bind::<BigInt, _>(balance.last_transaction_version)
+    .bind::<Timestamp, _>(balance.last_transaction_timestamp)
+    .execute(conn)
+}
+
+#[async_trait]
+impl TransactionProcessor for CoinProcessor {
+    fn name(&self) -> &'static str {
+        "coin_processor"
+    }
+
+    async fn process_transaction(
+        &self,
+        transaction: Arc<Transaction>,
+    ) -> Result<ProcessingResult, TransactionProcessingError> {
+        self.process_transactions(vec![transaction]).await
+    }
+
+    async fn process_transactions(
+        &self,
+        transactions: Vec<Arc<Transaction>>,
+    ) -> Result<ProcessingResult, TransactionProcessingError> {
+        let start_version = transactions.first().unwrap().version().unwrap_or(0);
+        let end_version = transactions.last().unwrap().version().unwrap_or(0);
+
+        let mut activities = vec![];
+        // Only the latest balance of each coin store in the batch needs to be written
+        let mut balances = HashMap::new();
+        for transaction in &transactions {
+            let (txn_activities, txn_balances) = CoinActivityModel::from_transaction(transaction);
+            activities.extend(txn_activities);
+            for balance in txn_balances {
+                balances.insert(
+                    (balance.owner_address.clone(), balance.coin_type.clone()),
+                    balance,
+                );
+            }
+        }
+
+        let conn = self.get_conn();
+        let tx_result = conn.transaction::<(), diesel::result::Error, _>(|| {
+            insert_coin_activities(&conn, &activities)?;
+            for balance in balances.values() {
+                upsert_current_coin_balance(&conn, balance)?;
+            }
+            Ok(())
+        });
+
+        match tx_result {
+            Ok(_) => Ok(ProcessingResult::new(self.name(), end_version)),
+            Err(err) => Err(TransactionProcessingError::TransactionCommitError((
+                anyhow::Error::from(err),
+                start_version,
+                self.name(),
+            ))),
+        }
+    }
+
+    fn connection_pool(&self) -> &PgDbPool {
+        &self.connection_pool
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        indexer::tailer::test::{setup_indexer, DATABASE_LOCK},
+        models::coin::{DEPOSIT_ACTIVITY, GAS_COIN_TYPE, GAS_FEE_ACTIVITY, WITHDRAW_ACTIVITY},
+    };
+    use aptos_types::{account_address::AccountAddress, event::EventKey};
+    use bigdecimal::BigDecimal;
+    use diesel::QueryDsl;
+    use serde_json::json;
+
+    fn coin_store(address: &str, value: u64) -> serde_json::Value {
+        let handle = |creation_num: u64| {
+            json!({
+                "counter": "1",
+                "guid": {
+                    "len_bytes": 40,
+                    "guid": { "id": { "addr": address, "creation_num": creation_num.to_string() } }
+                }
+            })
+        };
+        json!({
+            "type": "write_resource",
+            "address": address,
+            "state_key_hash": "0x0",
+            "data": {
+                "type": "0x1::Coin::CoinStore<0x1::TestCoin::TestCoin>",
+                "data": {
+                    "coin": { "value": value.to_string() },
+                    "deposit_events": handle(2),
+                    "withdraw_events": handle(3)
+                }
+            }
+        })
+    }
+
+    fn event_key(address: &str, creation_num: u64) -> String {
+        let address = AccountAddress::from_hex_literal(address).unwrap();
+        format!("{:#x}", EventKey::new_from_address(&address, creation_num))
+    }
+
+    /// A transfer of 100 coins from 0x2 to 0x3, which leaves them with the given balances
+    fn transfer(version: u64, sender_balance: u64, receiver_balance: u64) -> Arc<Transaction> {
+        let hash = format!("0x{:064x}", version);
+        Arc::new(
+            serde_json::from_value(json!({
+                "type": "user_transaction",
+                "version": version.to_string(),
+                "hash": hash,
+                "state_root_hash": hash,
+                "event_root_hash": hash,
+                "gas_used": "7",
+                "success": true,
+                "vm_status": "Executed successfully",
+                "accumulator_root_hash": hash,
+                "changes": [coin_store("0x2", sender_balance), coin_store("0x3", receiver_balance)],
+                "sender": "0x2",
+                "sequence_number": version.to_string(),
+                "max_gas_amount": "2000",
+                "gas_unit_price": "1",
+                "expiration_timestamp_secs": "1649560602",
+                "payload": {
+                    "type": "script_function_payload",
+                    "function": "0x1::Coin::transfer",
+                    "type_arguments": ["0x1::TestCoin::TestCoin"],
+                    "arguments": ["0x3", "100"]
+                },
+                "events": [
+                    {
+                        "key": event_key("0x2", 3),
+                        "sequence_number": "0",
+                        "type": "0x1::Coin::WithdrawEvent",
+                        "data": { "amount": "100" }
+                    },
+                    {
+                        "key": event_key("0x3", 2),
+                        "sequence_number": "0",
+                        "type": "0x1::Coin::DepositEvent",
+                        "data": { "amount": "100" }
+                    }
+                ],
+                "timestamp": "1649560602763949"
+            }))
+            .unwrap(),
+        )
+    }
+
+    fn balances(conn: &PgPoolConnection) -> Vec<(String, BigDecimal, i64)> {
+        schema::current_coin_balances::table
+            .order(schema::current_coin_balances::owner_address)
+            .load::<CurrentCoinBalanceModel>(conn)
+            .unwrap()
+            .into_iter()
+            .map(|balance| {
+                assert_eq!(balance.coin_type, *GAS_COIN_TYPE);
+                (
+                    balance.owner_address,
+                    balance.amount,
+                    balance.last_transaction_version,
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_coin_processor() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let _lock = DATABASE_LOCK.lock().await;
+        let (conn_pool, _tailer) = setup_indexer().unwrap();
+        let conn = conn_pool.get().unwrap();
+        let processor = CoinProcessor::new(conn_pool.clone());
+
+        processor
+            .process_transaction(transfer(10, 900, 100))
+            .await
+            .unwrap();
+        assert_eq!(
+            balances(&conn),
+            vec![
+                ("0x2".to_string(), BigDecimal::from(900), 10),
+                ("0x3".to_string(), BigDecimal::from(100), 10),
+            ]
+        );
+
+        // Within a batch, the last balance of each coin store is the one kept
+        processor
+            .process_transactions(vec![transfer(12, 700, 300), transfer(13, 600, 400)])
+            .await
+            .unwrap();
+        assert_eq!(
+            balances(&conn),
+            vec![
+                ("0x2".to_string(), BigDecimal::from(600), 13),
+                ("0x3".to_string(), BigDecimal::from(400), 13),
+            ]
+        );
+
+        // An older version processed late adds its activities, but doesn't overwrite the balances
+        processor
+            .process_transaction(transfer(11, 800, 200))
+            .await
+            .unwrap();
+        assert_eq!(
+            balances(&conn),
+            vec![
+                ("0x2".to_string(), BigDecimal::from(600), 13),
+                ("0x3".to_string(), BigDecimal::from(400), 13),
+            ]
+        );
+
+        // Processing a version again doesn't duplicate its activities
+        processor
+            .process_transaction(transfer(10, 900, 100))
+            .await
+            .unwrap();
+        let activities = schema::coin_activities::table
+            .order((
+                schema::coin_activities::transaction_version,
+                schema::coin_activities::activity_index,
+            ))
+            .load::<CoinActivityModel>(&conn)
+            .unwrap();
+        assert_eq!(activities.len(), 12);
+        for (version, activities) in (10..14).zip(activities.chunks(3)) {
+            let activities: Vec<_> = activities
+                .iter()
+                .map(|a| {
+                    (
+                        a.transaction_version,
+                        a.activity_index,
+                        a.owner_address.as_str(),
+                        a.coin_type.as_str(),
+                        a.activity_type.as_str(),
+                        a.amount.clone(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                activities,
+                vec![
+                    (
+                        version,
+                        0,
+                        "0x2",
+                        GAS_COIN_TYPE.as_str(),
+                        WITHDRAW_ACTIVITY,
+                        BigDecimal::from(100)
+                    ),
+                    (
+                        version,
+                        1,
+                        "0x3",
+                        GAS_COIN_TYPE.as_str(),
+                        DEPOSIT_ACTIVITY,
+                        BigDecimal::from(100)
+                    ),
+                    (
+                        version,
+                        2,
+                        "0x2",
+                        GAS_COIN_TYPE.as_str(),
+                        GAS_FEE_ACTIVITY,
+                        BigDecimal::from(7)
+                    ),
+                ]
+            );
+        }
+    }
+}
@@ -5,8 +5,9 @@
 use std::sync::Arc;
 
 use diesel::{
+    connection::SimpleConnection,
     pg::PgConnection,
-    r2d2::{ConnectionManager, PoolError, PooledConnection},
+    r2d2::{ConnectionManager, CustomizeConnection, PoolError, PooledConnection},
     RunQueryDsl,
 };
 
@@ -14,11 +15,34 @@ pub type PgPool = diesel::r2d2::Pool<ConnectionManager<PgConnection>>;
 pub type PgDbPool = Arc<PgPool>;
 pub type PgPoolConnection = PooledConnection<ConnectionManager<PgConnection>>;
 
+/// Postgres allows at most `u16::MAX` bind parameters per statement, so multi-row inserts are
+/// split into chunks of this many rows (our widest tables have fewer than 16 columns)
+pub const MAX_INSERT_ROWS: usize = 4000;
+
 pub fn new_db_pool(database_url: &str) -> Result<PgDbPool, PoolError> {
     let manager = ConnectionManager::<PgConnection>::new(database_url);
     PgPool::builder().build(manager).map(Arc::new)
 }
 
+/// Puts every connection of a pool in read only mode, so its users can never write to the DB
+#[derive(Debug)]
+struct ReadOnlyConnectionCustomizer;
+
+impl CustomizeConnection<PgConnection, diesel::r2d2::Error> for ReadOnlyConnectionCustomizer {
+    fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), diesel::r2d2::Error> {
+        conn.batch_execute("SET SESSION CHARACTERISTICS AS TRANSACTION READ ONLY")
+            .map_err(diesel::r2d2::Error::QueryError)
+    }
+}
+
+pub fn new_read_only_db_pool(database_url: &str) -> Result<PgDbPool, PoolError> {
+    let manager = ConnectionManager::<PgConnection>::new(database_url);
+    PgPool::builder()
+        .connection_customizer(Box::new(ReadOnlyConnectionCustomizer))
+        .build(manager)
+        .map(Arc::new)
+}
+
 pub fn execute_with_better_error<
     T: diesel::Table + diesel::QuerySource,
     U: diesel::query_builder::QueryFragment<diesel::pg::Pg>
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    database::{execute_with_better_error, PgDbPool, PgPoolConnection},
+    database::{execute_with_better_error, PgDbPool, PgPoolConnection, MAX_INSERT_ROWS},
     indexer::{
         errors::TransactionProcessingError, processing_result::ProcessingResult,
         transaction_processor::TransactionProcessor,
@@ -16,7 +16,7 @@ use crate::{
 };
 use aptos_rest_client::Transaction;
 use async_trait::async_trait;
-use diesel::Connection;
+use diesel::{upsert::excluded, Connection, ExpressionMethods};
 use futures::future::Either;
 use std::{fmt::Debug, sync::Arc};
 
@@ -122,6 +122,100 @@ fn insert_block_metadata_transaction(
     .expect("Error inserting row into database");
 }
 
+/// Writes the models of a whole batch of transactions, with one multi-row insert per table (and per
+/// `MAX_INSERT_ROWS` rows). Conflicts are handled as in the per-version inserts above: transactions
+/// which already exist are overwritten, and errors are returned so the batch can be rolled back.
+fn insert_batch(
+    conn: &PgPoolConnection,
+    transaction_models: &[TransactionModel],
+    user_transaction_models: &[UserTransactionModel],
+    block_metadata_transaction_models: &[BlockMetadataTransactionModel],
+    events: &[EventModel],
+    write_set_changes: &[WriteSetChangeModel],
+) -> diesel::QueryResult<()> {
+    use schema::{
+        block_metadata_transactions as bmt, transactions as txns, user_transactions as user_txns,
+    };
+
+    for chunk in transaction_models.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(txns::table)
+                .values(chunk)
+                .on_conflict(txns::hash)
+                .do_update()
+                .set((
+                    txns::type_.eq(excluded(txns::type_)),
+                    txns::payload.eq(excluded(txns::payload)),
+                    txns::version.eq(excluded(txns::version)),
+                    txns::state_root_hash.eq(excluded(txns::state_root_hash)),
+                    txns::event_root_hash.eq(excluded(txns::event_root_hash)),
+                    txns::gas_used.eq(excluded(txns::gas_used)),
+                    txns::success.eq(excluded(txns::success)),
+                    txns::vm_status.eq(excluded(txns::vm_status)),
+                    txns::accumulator_root_hash.eq(excluded(txns::accumulator_root_hash)),
+                    txns::inserted_at.eq(excluded(txns::inserted_at)),
+                )),
+        )?;
+    }
+    for chunk in user_transaction_models.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(user_txns::table)
+                .values(chunk)
+                .on_conflict(user_txns::hash)
+                .do_update()
+                .set((
+                    user_txns::signature.eq(excluded(user_txns::signature)),
+                    user_txns::sender.eq(excluded(user_txns::sender)),
+                    user_txns::sequence_number.eq(excluded(user_txns::sequence_number)),
+                    user_txns::max_gas_amount.eq(excluded(user_txns::max_gas_amount)),
+                    user_txns::expiration_timestamp_secs
+                        .eq(excluded(user_txns::expiration_timestamp_secs)),
+                    user_txns::gas_unit_price.eq(excluded(user_txns::gas_unit_price)),
+                    user_txns::timestamp.eq(excluded(user_txns::timestamp)),
+                    user_txns::inserted_at.eq(excluded(user_txns::inserted_at)),
+                )),
+        )?;
+    }
+    for chunk in block_metadata_transaction_models.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(bmt::table)
+                .values(chunk)
+                .on_conflict(bmt::hash)
+                .do_update()
+                .set((
+                    bmt::id.eq(excluded(bmt::id)),
+                    bmt::round.eq(excluded(bmt::round)),
+                    bmt::previous_block_votes.eq(excluded(bmt::previous_block_votes)),
+                    bmt::proposer.eq(excluded(bmt::proposer)),
+                    bmt::timestamp.eq(excluded(bmt::timestamp)),
+                    bmt::inserted_at.eq(excluded(bmt::inserted_at)),
+                    bmt::epoch.eq(excluded(bmt::epoch)),
+                    bmt::previous_block_votes_bitmap.eq(excluded(bmt::previous_block_votes_bitmap)),
+                )),
+        )?;
+    }
+    for chunk in events.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::events::table)
+                .values(chunk)
+                .on_conflict_do_nothing(),
+        )?;
+    }
+    for chunk in write_set_changes.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::write_set_changes::table)
+                .values(chunk)
+                .on_conflict_do_nothing(),
+        )?;
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl TransactionProcessor for DefaultTransactionProcessor {
     fn name(&self) -> &'static str {
@@ -181,6 +275,62 @@ impl TransactionProcessor for DefaultTransactionProcessor {
         }
     }
 
+    async fn process_transactions(
+        &self,
+        transactions: Vec<Arc<Transaction>>,
+    ) -> Result<ProcessingResult, TransactionProcessingError> {
+        let start_version = transactions.first().unwrap().version().unwrap_or(0);
+        let end_version = transactions.last().unwrap().version().unwrap_or(0);
+
+        let mut transaction_models = vec![];
+        let mut user_transaction_models = vec![];
+        let mut block_metadata_transaction_models = vec![];
+        let mut events = vec![];
+        let mut write_set_changes = vec![];
+        for transaction in &transactions {
+            let (transaction_model, maybe_details_model, maybe_events, maybe_write_set_changes) =
+                TransactionModel::from_transaction(transaction);
+            transaction_models.push(transaction_model);
+            match maybe_details_model {
+                Some(Either::Left(user_transaction_model)) => {
+                    user_transaction_models.push(user_transaction_model)
+                }
+                Some(Either::Right(block_metadata_transaction_model)) => {
+                    block_metadata_transaction_models.push(block_metadata_transaction_model)
+                }
+                None => {}
+            };
+            events.extend(maybe_events.unwrap_or_default());
+            write_set_changes.extend(maybe_write_set_changes.unwrap_or_default());
+        }
+
+        let conn = self.get_conn();
+        aptos_logger::trace!(
+            "[default_processor] inserting versions {} to {}",
+            start_version,
+            end_version
+        );
+        let tx_result = conn.transaction::<(), diesel::result::Error, _>(|| {
+            insert_batch(
+                &conn,
+                &transaction_models,
+                &user_transaction_models,
+                &block_metadata_transaction_models,
+                &events,
+                &write_set_changes,
+            )
+        });
+
+        match tx_result {
+            Ok(_) => Ok(ProcessingResult::new(self.name(), end_version)),
+            Err(err) => Err(TransactionProcessingError::TransactionCommitError((
+                anyhow::Error::from(err),
+                start_version,
+                self.name(),
+            ))),
+        }
+    }
+
     fn connection_pool(&self) -> &PgDbPool {
         &self.connection_pool
     }
@@ -0,0 +1,1219 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! A read-only GraphQL query layer over the tables written by the indexer's processors
+//!
+//! Every list query takes an optional `filter` (all of its fields must match), an optional
+//! `orderByField` and `direction` (ascending by default), and an optional `page`. Rows with the
+//! same `orderByField` are ordered by their primary key, so that pages neither skip nor repeat rows.
+
+use crate::{
+    database::{PgDbPool, PgPoolConnection},
+    models::{
+        coin::{CoinActivityModel, CurrentCoinBalanceModel},
+        collection::Collection,
+        events::EventModel,
+        metadata::Metadata,
+        ownership::Ownership,
+        token::Token,
+        token_activity::TokenActivityModel,
+        transactions::{BlockMetadataTransactionModel, TransactionModel, UserTransactionModel},
+        write_set_changes::WriteSetChangeModel,
+    },
+    schema,
+};
+use async_graphql::{
+    ComplexObject, Context, EmptyMutation, EmptySubscription, Enum, InputObject, Object, Result,
+    Schema,
+};
+use diesel::{
+    helper_types::{Asc, Desc},
+    pg::Pg,
+    query_dsl::methods::{OrderDsl, ThenOrderDsl},
+    ExpressionMethods, OptionalExtension, QueryDsl, QueryResult, RunQueryDsl,
+};
+
+/// Number of rows returned when a query doesn't give a `limit`
+pub const DEFAULT_PAGE_SIZE: i64 = 25;
+/// Largest `limit` a query may ask for
+pub const MAX_PAGE_SIZE: i64 = 100;
+/// Nested relations (e.g. transaction -> events) are cheap, but unbounded nesting is not
+pub const MAX_QUERY_DEPTH: usize = 8;
+
+pub type IndexerSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Builds the schema; `connection_pool` should be read only (see `new_read_only_db_pool`)
+pub fn build_schema(connection_pool: PgDbPool) -> IndexerSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(connection_pool)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .finish()
+}
+
+/// Runs `query` with a connection of the pool, on the blocking thread pool as Diesel and r2d2
+/// block the thread
+async fn run_query<T, F>(ctx: &Context<'_>, query: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&PgPoolConnection) -> QueryResult<T> + Send + 'static,
+{
+    let pool = ctx.data::<PgDbPool>()?.clone();
+    tokio::task::spawn_blocking(move || -> Result<T> { Ok(query(&pool.get()?)?) }).await?
+}
+
+#[derive(Clone, Copy, Debug, Default, InputObject)]
+pub struct Page {
+    /// Defaults to `DEFAULT_PAGE_SIZE`, and can be at most `MAX_PAGE_SIZE`
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl Page {
+    fn limit_and_offset(page: Option<Page>) -> Result<(i64, i64)> {
+        let page = page.unwrap_or_default();
+        let limit = page.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(format!("limit must be between 1 and {}", MAX_PAGE_SIZE).into());
+        }
+        let offset = page.offset.unwrap_or(0);
+        if offset < 0 {
+            return Err("offset must not be negative".into());
+        }
+        Ok((limit, offset))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Enum)]
+pub enum OrderDirection {
+    Asc,
+    Desc,
+}
+
+impl Default for OrderDirection {
+    fn default() -> Self {
+        OrderDirection::Asc
+    }
+}
+
+fn order_by<Q, C>(query: Q, column: C, direction: Option<OrderDirection>) -> Q
+where
+    C: ExpressionMethods,
+    Q: OrderDsl<Asc<C>, Output = Q> + OrderDsl<Desc<C>, Output = Q>,
+{
+    match direction.unwrap_or_default() {
+        OrderDirection::Asc => OrderDsl::order(query, column.asc()),
+        OrderDirection::Desc => OrderDsl::order(query, column.desc()),
+    }
+}
+
+/// Orders the rows which are equal by the previous order by `column`, in the same `direction`
+fn then_order_by<Q, C>(query: Q, column: C, direction: Option<OrderDirection>) -> Q
+where
+    C: ExpressionMethods,
+    Q: ThenOrderDsl<Asc<C>, Output = Q> + ThenOrderDsl<Desc<C>, Output = Q>,
+{
+    match direction.unwrap_or_default() {
+        OrderDirection::Asc => ThenOrderDsl::then_order_by(query, column.asc()),
+        OrderDirection::Desc => ThenOrderDsl::then_order_by(query, column.desc()),
+    }
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct TransactionFilter {
+    pub version_gte: Option<i64>,
+    pub version_lte: Option<i64>,
+    #[graphql(name = "type")]
+    pub type_: Option<String>,
+    pub success: Option<bool>,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct UserTransactionFilter {
+    pub hash: Option<String>,
+    pub sender: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Enum)]
+pub enum UserTransactionOrderField {
+    Timestamp,
+    SequenceNumber,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct BlockMetadataTransactionFilter {
+    pub hash: Option<String>,
+    pub proposer: Option<String>,
+    pub epoch: Option<i64>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Enum)]
+pub enum BlockMetadataTransactionOrderField {
+    Timestamp,
+    Round,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct EventFilter {
+    pub transaction_hash: Option<String>,
+    pub key: Option<String>,
+    #[graphql(name = "type")]
+    pub type_: Option<String>,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct WriteSetChangeFilter {
+    pub transaction_hash: Option<String>,
+    pub address: Option<String>,
+    #[graphql(name = "type")]
+    pub type_: Option<String>,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct TokenFilter {
+    pub token_id: Option<String>,
+    pub creator: Option<String>,
+    pub collection: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Enum)]
+pub enum TokenOrderField {
+    MintedAt,
+    LastMintedAt,
+    Supply,
+    Name,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct CollectionFilter {
+    pub collection_id: Option<String>,
+    pub creator: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Enum)]
+pub enum CollectionOrderField {
+    CreatedAt,
+    Name,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct OwnershipFilter {
+    pub token_id: Option<String>,
+    pub owner: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Enum)]
+pub enum OwnershipOrderField {
+    UpdatedAt,
+    Amount,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct TokenActivityFilter {
+    pub account: Option<String>,
+    pub token_id: Option<String>,
+    pub event_type: Option<String>,
+    pub transaction_hash: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Enum)]
+pub enum TokenActivityOrderField {
+    CreatedAt,
+    SequenceNumber,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct CoinActivityFilter {
+    pub owner_address: Option<String>,
+    pub coin_type: Option<String>,
+    pub activity_type: Option<String>,
+    pub transaction_version: Option<i64>,
+}
+
+#[derive(Debug, Default, InputObject)]
+pub struct CurrentCoinBalanceFilter {
+    pub owner_address: Option<String>,
+    pub coin_type: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Enum)]
+pub enum CurrentCoinBalanceOrderField {
+    LastTransactionVersion,
+    Amount,
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// A single transaction, by version or by hash
+    async fn transaction(
+        &self,
+        ctx: &Context<'_>,
+        version: Option<i64>,
+        hash: Option<String>,
+    ) -> Result<Option<TransactionModel>> {
+        use schema::transactions::dsl;
+
+        let mut query = dsl::transactions.into_boxed::<Pg>();
+        match (version, hash) {
+            (Some(version), None) => query = query.filter(dsl::version.eq(version)),
+            (None, Some(hash)) => query = query.filter(dsl::hash.eq(hash)),
+            _ => return Err("exactly one of version or hash must be given".into()),
+        };
+        run_query(ctx, move |conn| {
+            query.first::<TransactionModel>(conn).optional()
+        })
+        .await
+    }
+
+    /// Transactions, ordered by version
+    async fn transactions(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TransactionFilter>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<TransactionModel>> {
+        use schema::transactions::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::transactions.into_boxed::<Pg>();
+        if let Some(version_gte) = filter.version_gte {
+            query = query.filter(dsl::version.ge(version_gte));
+        }
+        if let Some(version_lte) = filter.version_lte {
+            query = query.filter(dsl::version.le(version_lte));
+        }
+        if let Some(type_) = filter.type_ {
+            query = query.filter(dsl::type_.eq(type_));
+        }
+        if let Some(success) = filter.success {
+            query = query.filter(dsl::success.eq(success));
+        }
+        query = order_by(query, dsl::version, direction);
+        run_query(ctx, move |conn| {
+            query
+                .limit(limit)
+                .offset(offset)
+                .load::<TransactionModel>(conn)
+        })
+        .await
+    }
+
+    async fn user_transactions(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<UserTransactionFilter>,
+        order_by_field: Option<UserTransactionOrderField>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<UserTransactionModel>> {
+        use schema::user_transactions::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::user_transactions.into_boxed::<Pg>();
+        if let Some(hash) = filter.hash {
+            query = query.filter(dsl::hash.eq(hash));
+        }
+        if let Some(sender) = filter.sender {
+            query = query.filter(dsl::sender.eq(sender));
+        }
+        query = match order_by_field.unwrap_or(UserTransactionOrderField::Timestamp) {
+            UserTransactionOrderField::Timestamp => order_by(query, dsl::timestamp, direction),
+            UserTransactionOrderField::SequenceNumber => {
+                order_by(query, dsl::sequence_number, direction)
+            }
+        };
+        query = then_order_by(query, dsl::hash, direction);
+        run_query(ctx, move |conn| {
+            query
+                .limit(limit)
+                .offset(offset)
+                .load::<UserTransactionModel>(conn)
+        })
+        .await
+    }
+
+    async fn block_metadata_transactions(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<BlockMetadataTransactionFilter>,
+        order_by_field: Option<BlockMetadataTransactionOrderField>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<BlockMetadataTransactionModel>> {
+        use schema::block_metadata_transactions::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::block_metadata_transactions.into_boxed::<Pg>();
+        if let Some(hash) = filter.hash {
+            query = query.filter(dsl::hash.eq(hash));
+        }
+        if let Some(proposer) = filter.proposer {
+            query = query.filter(dsl::proposer.eq(proposer));
+        }
+        if let Some(epoch) = filter.epoch {
+            query = query.filter(dsl::epoch.eq(epoch));
+        }
+        query = match order_by_field.unwrap_or(BlockMetadataTransactionOrderField::Timestamp) {
+            BlockMetadataTransactionOrderField::Timestamp => {
+                order_by(query, dsl::timestamp, direction)
+            }
+            BlockMetadataTransactionOrderField::Round => order_by(query, dsl::round, direction),
+        };
+        query = then_order_by(query, dsl::hash, direction);
+        run_query(ctx, move |conn| {
+            query
+                .limit(limit)
+                .offset(offset)
+                .load::<BlockMetadataTransactionModel>(conn)
+        })
+        .await
+    }
+
+    /// Events, ordered by sequence number
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<EventFilter>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<EventModel>> {
+        use schema::events::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::events.into_boxed::<Pg>();
+        if let Some(transaction_hash) = filter.transaction_hash {
+            query = query.filter(dsl::transaction_hash.eq(transaction_hash));
+        }
+        if let Some(key) = filter.key {
+            query = query.filter(dsl::key.eq(key));
+        }
+        if let Some(type_) = filter.type_ {
+            query = query.filter(dsl::type_.eq(type_));
+        }
+        query = order_by(query, dsl::sequence_number, direction);
+        query = then_order_by(query, dsl::key, direction);
+        run_query(ctx, move |conn| {
+            query.limit(limit).offset(offset).load::<EventModel>(conn)
+        })
+        .await
+    }
+
+    /// Write set changes, ordered by address
+    async fn write_set_changes(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<WriteSetChangeFilter>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<WriteSetChangeModel>> {
+        use schema::write_set_changes::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::write_set_changes.into_boxed::<Pg>();
+        if let Some(transaction_hash) = filter.transaction_hash {
+            query = query.filter(dsl::transaction_hash.eq(transaction_hash));
+        }
+        if let Some(address) = filter.address {
+            query = query.filter(dsl::address.eq(address));
+        }
+        if let Some(type_) = filter.type_ {
+            query = query.filter(dsl::type_.eq(type_));
+        }
+        query = order_by(query, dsl::address, direction);
+        query = then_order_by(query, dsl::transaction_hash, direction);
+        query = then_order_by(query, dsl::hash, direction);
+        run_query(ctx, move |conn| {
+            query
+                .limit(limit)
+                .offset(offset)
+                .load::<WriteSetChangeModel>(conn)
+        })
+        .await
+    }
+
+    async fn tokens(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TokenFilter>,
+        order_by_field: Option<TokenOrderField>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<Token>> {
+        use schema::tokens::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::tokens.into_boxed::<Pg>();
+        if let Some(token_id) = filter.token_id {
+            query = query.filter(dsl::token_id.eq(token_id));
+        }
+        if let Some(creator) = filter.creator {
+            query = query.filter(dsl::creator.eq(creator));
+        }
+        if let Some(collection) = filter.collection {
+            query = query.filter(dsl::collection.eq(collection));
+        }
+        if let Some(name) = filter.name {
+            query = query.filter(dsl::name.eq(name));
+        }
+        query = match order_by_field.unwrap_or(TokenOrderField::MintedAt) {
+            TokenOrderField::MintedAt => order_by(query, dsl::minted_at, direction),
+            TokenOrderField::LastMintedAt => order_by(query, dsl::last_minted_at, direction),
+            TokenOrderField::Supply => order_by(query, dsl::supply, direction),
+            TokenOrderField::Name => order_by(query, dsl::name, direction),
+        };
+        query = then_order_by(query, dsl::token_id, direction);
+        run_query(ctx, move |conn| {
+            query.limit(limit).offset(offset).load::<Token>(conn)
+        })
+        .await
+    }
+
+    async fn collections(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<CollectionFilter>,
+        order_by_field: Option<CollectionOrderField>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<Collection>> {
+        use schema::collections::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::collections.into_boxed::<Pg>();
+        if let Some(collection_id) = filter.collection_id {
+            query = query.filter(dsl::collection_id.eq(collection_id));
+        }
+        if let Some(creator) = filter.creator {
+            query = query.filter(dsl::creator.eq(creator));
+        }
+        if let Some(name) = filter.name {
+            query = query.filter(dsl::name.eq(name));
+        }
+        query = match order_by_field.unwrap_or(CollectionOrderField::CreatedAt) {
+            CollectionOrderField::CreatedAt => order_by(query, dsl::created_at, direction),
+            CollectionOrderField::Name => order_by(query, dsl::name, direction),
+        };
+        query = then_order_by(query, dsl::collection_id, direction);
+        run_query(ctx, move |conn| {
+            query.limit(limit).offset(offset).load::<Collection>(conn)
+        })
+        .await
+    }
+
+    async fn ownerships(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<OwnershipFilter>,
+        order_by_field: Option<OwnershipOrderField>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<Ownership>> {
+        use schema::ownerships::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::ownerships.into_boxed::<Pg>();
+        if let Some(token_id) = filter.token_id {
+            query = query.filter(dsl::token_id.eq(token_id));
+        }
+        if let Some(owner) = filter.owner {
+            query = query.filter(dsl::owner.eq(owner));
+        }
+        query = match order_by_field.unwrap_or(OwnershipOrderField::UpdatedAt) {
+            OwnershipOrderField::UpdatedAt => order_by(query, dsl::updated_at, direction),
+            OwnershipOrderField::Amount => order_by(query, dsl::amount, direction),
+        };
+        query = then_order_by(query, dsl::ownership_id, direction);
+        run_query(ctx, move |conn| {
+            query.limit(limit).offset(offset).load::<Ownership>(conn)
+        })
+        .await
+    }
+
+    async fn token_activities(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<TokenActivityFilter>,
+        order_by_field: Option<TokenActivityOrderField>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<TokenActivityModel>> {
+        use schema::token_activities::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::token_activities.into_boxed::<Pg>();
+        if let Some(account) = filter.account {
+            query = query.filter(dsl::account.eq(account));
+        }
+        if let Some(token_id) = filter.token_id {
+            query = query.filter(dsl::token_id.eq(token_id));
+        }
+        if let Some(event_type) = filter.event_type {
+            query = query.filter(dsl::event_type.eq(event_type));
+        }
+        if let Some(transaction_hash) = filter.transaction_hash {
+            query = query.filter(dsl::transaction_hash.eq(transaction_hash));
+        }
+        query = match order_by_field.unwrap_or(TokenActivityOrderField::CreatedAt) {
+            TokenActivityOrderField::CreatedAt => order_by(query, dsl::created_at, direction),
+            TokenActivityOrderField::SequenceNumber => {
+                order_by(query, dsl::sequence_number, direction)
+            }
+        };
+        query = then_order_by(query, dsl::event_key, direction);
+        query = then_order_by(query, dsl::sequence_number, direction);
+        run_query(ctx, move |conn| {
+            query
+                .limit(limit)
+                .offset(offset)
+                .load::<TokenActivityModel>(conn)
+        })
+        .await
+    }
+
+    /// The off-chain metadata of a token, fetched from its URI
+    async fn token_metadata(
+        &self,
+        ctx: &Context<'_>,
+        token_id: String,
+    ) -> Result<Option<Metadata>> {
+        use schema::metadatas::dsl;
+
+        run_query(ctx, move |conn| {
+            dsl::metadatas
+                .filter(dsl::token_id.eq(token_id))
+                .first::<Metadata>(conn)
+                .optional()
+        })
+        .await
+    }
+
+    /// Coin activities, ordered by transaction version (then by their index in the transaction)
+    async fn coin_activities(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<CoinActivityFilter>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<CoinActivityModel>> {
+        use schema::coin_activities::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::coin_activities.into_boxed::<Pg>();
+        if let Some(owner_address) = filter.owner_address {
+            query = query.filter(dsl::owner_address.eq(owner_address));
+        }
+        if let Some(coin_type) = filter.coin_type {
+            query = query.filter(dsl::coin_type.eq(coin_type));
+        }
+        if let Some(activity_type) = filter.activity_type {
+            query = query.filter(dsl::activity_type.eq(activity_type));
+        }
+        if let Some(transaction_version) = filter.transaction_version {
+            query = query.filter(dsl::transaction_version.eq(transaction_version));
+        }
+        query = match direction.unwrap_or_default() {
+            OrderDirection::Asc => {
+                query.order((dsl::transaction_version.asc(), dsl::activity_index.asc()))
+            }
+            OrderDirection::Desc => {
+                query.order((dsl::transaction_version.desc(), dsl::activity_index.desc()))
+            }
+        };
+        run_query(ctx, move |conn| {
+            query
+                .limit(limit)
+                .offset(offset)
+                .load::<CoinActivityModel>(conn)
+        })
+        .await
+    }
+
+    async fn current_coin_balances(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<CurrentCoinBalanceFilter>,
+        order_by_field: Option<CurrentCoinBalanceOrderField>,
+        direction: Option<OrderDirection>,
+        page: Option<Page>,
+    ) -> Result<Vec<CurrentCoinBalanceModel>> {
+        use schema::current_coin_balances::dsl;
+
+        let filter = filter.unwrap_or_default();
+        let (limit, offset) = Page::limit_and_offset(page)?;
+        let mut query = dsl::current_coin_balances.into_boxed::<Pg>();
+        if let Some(owner_address) = filter.owner_address {
+            query = query.filter(dsl::owner_address.eq(owner_address));
+        }
+        if let Some(coin_type) = filter.coin_type {
+            query = query.filter(dsl::coin_type.eq(coin_type));
+        }
+        query = match order_by_field.unwrap_or(CurrentCoinBalanceOrderField::LastTransactionVersion)
+        {
+            CurrentCoinBalanceOrderField::LastTransactionVersion => {
+                order_by(query, dsl::last_transaction_version, direction)
+            }
+            CurrentCoinBalanceOrderField::Amount => order_by(query, dsl::amount, direction),
+        };
+        query = then_order_by(query, dsl::owner_address, direction);
+        query = then_order_by(query, dsl::coin_type, direction);
+        run_query(ctx, move |conn| {
+            query
+                .limit(limit)
+                .offset(offset)
+                .load::<CurrentCoinBalanceModel>(conn)
+        })
+        .await
+    }
+}
+
+// The relations are looked up by the hash of the transaction, so that the queries own their
+// parameters when run on the blocking thread pool
+#[ComplexObject]
+impl TransactionModel {
+    async fn user_transaction(&self, ctx: &Context<'_>) -> Result<Option<UserTransactionModel>> {
+        use schema::user_transactions::dsl;
+
+        let hash = self.hash.clone();
+        run_query(ctx, move |conn| {
+            dsl::user_transactions
+                .filter(dsl::hash.eq(hash))
+                .first::<UserTransactionModel>(conn)
+                .optional()
+        })
+        .await
+    }
+
+    async fn block_metadata_transaction(
+        &self,
+        ctx: &Context<'_>,
+    ) -> Result<Option<BlockMetadataTransactionModel>> {
+        use schema::block_metadata_transactions::dsl;
+
+        let hash = self.hash.clone();
+        run_query(ctx, move |conn| {
+            dsl::block_metadata_transactions
+                .filter(dsl::hash.eq(hash))
+                .first::<BlockMetadataTransactionModel>(conn)
+                .optional()
+        })
+        .await
+    }
+
+    async fn events(&self, ctx: &Context<'_>) -> Result<Vec<EventModel>> {
+        use schema::events::dsl;
+
+        let hash = self.hash.clone();
+        run_query(ctx, move |conn| {
+            dsl::events
+                .filter(dsl::transaction_hash.eq(hash))
+                .order(dsl::sequence_number.asc())
+                .load::<EventModel>(conn)
+        })
+        .await
+    }
+
+    async fn write_set_changes(&self, ctx: &Context<'_>) -> Result<Vec<WriteSetChangeModel>> {
+        use schema::write_set_changes::dsl;
+
+        let hash = self.hash.clone();
+        run_query(ctx, move |conn| {
+            dsl::write_set_changes
+                .filter(dsl::transaction_hash.eq(hash))
+                .load::<WriteSetChangeModel>(conn)
+        })
+        .await
+    }
+}
+
+// `bigdecimal` can't be used by async-graphql directly, so amounts are rendered as strings, which
+// also keeps their full precision for JavaScript clients
+#[ComplexObject]
+impl CoinActivityModel {
+    async fn amount(&self) -> String {
+        self.amount.to_string()
+    }
+}
+
+#[ComplexObject]
+impl CurrentCoinBalanceModel {
+    async fn amount(&self) -> String {
+        self.amount.to_string()
+    }
+}
+
+#[ComplexObject]
+impl TokenActivityModel {
+    async fn amount(&self) -> Option<String> {
+        self.amount.as_ref().map(ToString::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        default_processor::DefaultTransactionProcessor,
+        indexer::{
+            tailer::test::{setup_indexer, DATABASE_LOCK},
+            transaction_processor::TransactionProcessor,
+        },
+    };
+    use aptos_rest_client::Transaction;
+    use bigdecimal::BigDecimal;
+    use diesel::sql_types::{BigInt, Text, Timestamp};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn timestamp() -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::from_timestamp(1649395495, 0)
+    }
+
+    fn transaction_hash(version: u64) -> String {
+        format!("0x{:064x}", version)
+    }
+
+    fn block_metadata_transaction(version: u64, round: u64) -> Arc<Transaction> {
+        Arc::new(
+            serde_json::from_value(json!(
+                {
+                  "type": "block_metadata_transaction",
+                  "version": version.to_string(),
+                  "hash": transaction_hash(version),
+                  "state_root_hash": "0x3ead9eb40582fbc7df5e02f72280931dc3e6f1aae45dc832966b4cd972dac4b8",
+                  "event_root_hash": "0x2e481956dea9c59b6fc9f823fe5f4c45efce173e42c551c1fe073b5d76a65504",
+                  "gas_used": "0",
+                  "success": true,
+                  "vm_status": "Executed successfully",
+                  "accumulator_root_hash": "0xb0ad602f805eb20c398f0f29a3504a9ef38bcc52c9c451deb9ec4a2d18807b49",
+                  "id": "0xeef99391a3fc681f16963a6c03415bc0b1b12b56c00429308fa8bf46ac9eddf0",
+                  "round": round.to_string(),
+                  "previous_block_votes": [],
+                  "epoch": "1",
+                  "proposer": "0x68f04222bd9f8846cda028ea5ba3846a806b04a47e1f1a4f0939f350d713b2eb",
+                  "timestamp": "1649395495746947",
+                  "changes": []
+                }
+            ))
+            .unwrap(),
+        )
+    }
+
+    fn token(name: &str, supply: i64) -> Token {
+        let timestamp = timestamp();
+        Token {
+            token_id: format!("0x1::Collection::{}", name),
+            creator: "0x1".to_string(),
+            collection: "Collection".to_string(),
+            name: name.to_string(),
+            description: format!("The {} token", name),
+            max_amount: None,
+            supply,
+            uri: format!("https://aptos.dev/{}", name),
+            minted_at: timestamp,
+            last_minted_at: timestamp,
+            inserted_at: timestamp,
+        }
+    }
+
+    async fn setup_schema() -> IndexerSchema {
+        let (conn_pool, _tailer) = setup_indexer().unwrap();
+        let processor = DefaultTransactionProcessor::new(conn_pool.clone());
+        let transactions = (10..13)
+            .map(|version| block_metadata_transaction(version, 57600 + version))
+            .collect();
+        processor.process_transactions(transactions).await.unwrap();
+        diesel::insert_into(schema::tokens::table)
+            .values(&vec![
+                token("Alpha", 5),
+                token("Beta", 50),
+                token("Gamma", 1),
+            ])
+            .execute(&conn_pool.get().unwrap())
+            .unwrap();
+        seed_token_tables(&conn_pool.get().unwrap());
+        seed_event_tables(&conn_pool.get().unwrap());
+        build_schema(conn_pool)
+    }
+
+    fn seed_token_tables(conn: &PgPoolConnection) {
+        diesel::insert_into(schema::collections::table)
+            .values(&vec![
+                Collection::new(
+                    "0x1".to_string(),
+                    "Collection".to_string(),
+                    "The collection".to_string(),
+                    None,
+                    "https://aptos.dev".to_string(),
+                    timestamp(),
+                    timestamp(),
+                ),
+                Collection::new(
+                    "0x2".to_string(),
+                    "Another collection".to_string(),
+                    "Another collection".to_string(),
+                    Some(10),
+                    "https://aptos.dev/another".to_string(),
+                    timestamp(),
+                    timestamp(),
+                ),
+            ])
+            .execute(conn)
+            .unwrap();
+        // The ownerships are all updated at the same time
+        diesel::insert_into(schema::ownerships::table)
+            .values(&vec![
+                Ownership::new(
+                    "0x1::Collection::Beta".to_string(),
+                    "0xb".to_string(),
+                    2,
+                    timestamp(),
+                    timestamp(),
+                ),
+                Ownership::new(
+                    "0x1::Collection::Alpha".to_string(),
+                    "0xa".to_string(),
+                    1,
+                    timestamp(),
+                    timestamp(),
+                ),
+                Ownership::new(
+                    "0x1::Collection::Alpha".to_string(),
+                    "0xb".to_string(),
+                    4,
+                    timestamp(),
+                    timestamp(),
+                ),
+            ])
+            .execute(conn)
+            .unwrap();
+        for (sequence_number, event_type) in
+            ["0x1::Token::DepositEvent", "0x1::Token::WithdrawEvent"]
+                .iter()
+                .enumerate()
+        {
+            diesel::sql_query(
+                "INSERT INTO token_activities \
+                    (event_key, sequence_number, account, token_id, event_type, amount, created_at, \
+                     transaction_hash) \
+                 VALUES ('0x0000000000000000a', $1, '0xa', '0x1::Collection::Alpha', $2, 1, $3, $4)",
+            )
+            .bind::<BigInt, _>(sequence_number as i64)
+            .bind::<Text, _>(*event_type)
+            .bind::<Timestamp, _>(timestamp())
+            .bind::<Text, _>(transaction_hash(11))
+            .execute(conn)
+            .unwrap();
+        }
+    }
+
+    fn seed_event_tables(conn: &PgPoolConnection) {
+        // The events all have the same sequence number
+        let events: Vec<_> = [
+            "0x0000000000000000c",
+            "0x0000000000000000a",
+            "0x0000000000000000b",
+        ]
+        .iter()
+        .map(|key| EventModel {
+            transaction_hash: transaction_hash(10),
+            key: key.to_string(),
+            sequence_number: 0,
+            type_: "0x1::Coin::DepositEvent".to_string(),
+            data: json!({ "amount": "100" }),
+            inserted_at: timestamp(),
+        })
+        .collect();
+        diesel::insert_into(schema::events::table)
+            .values(&events)
+            .execute(conn)
+            .unwrap();
+
+        let coin_activity =
+            |activity_index: i64, owner_address: &str, activity_type: &str| CoinActivityModel {
+                transaction_version: 10,
+                activity_index,
+                owner_address: owner_address.to_string(),
+                coin_type: "0x1::TestCoin::TestCoin".to_string(),
+                activity_type: activity_type.to_string(),
+                amount: BigDecimal::from(100),
+                is_transaction_success: true,
+                transaction_hash: transaction_hash(10),
+                transaction_timestamp: timestamp(),
+                inserted_at: timestamp(),
+            };
+        diesel::insert_into(schema::coin_activities::table)
+            .values(&vec![
+                coin_activity(0, "0xa", "withdraw"),
+                coin_activity(1, "0xb", "deposit"),
+            ])
+            .execute(conn)
+            .unwrap();
+
+        let balance = |owner_address: &str, amount: i64| CurrentCoinBalanceModel {
+            owner_address: owner_address.to_string(),
+            coin_type: "0x1::TestCoin::TestCoin".to_string(),
+            amount: BigDecimal::from(amount),
+            last_transaction_version: 10,
+            last_transaction_timestamp: timestamp(),
+            inserted_at: timestamp(),
+        };
+        diesel::insert_into(schema::current_coin_balances::table)
+            .values(&vec![balance("0xa", 900), balance("0xb", 1100)])
+            .execute(conn)
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_transaction_queries() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let _lock = DATABASE_LOCK.lock().await;
+        let schema = setup_schema().await;
+
+        let response = schema
+            .execute(
+                r#"{
+                    transactions(filter: {versionGte: 11}, direction: DESC, page: {limit: 1}) {
+                        version
+                        type
+                        blockMetadataTransaction { round }
+                        userTransaction { sender }
+                    }
+                }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "transactions": [{
+                    "version": 12,
+                    "type": "block_metadata_transaction",
+                    "blockMetadataTransaction": { "round": 57612 },
+                    "userTransaction": null,
+                }]
+            })
+        );
+
+        let response = schema
+            .execute(format!(
+                r#"{{ transaction(hash: "{}") {{ version }} }}"#,
+                transaction_hash(10)
+            ))
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({ "transaction": { "version": 10 } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_queries() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let _lock = DATABASE_LOCK.lock().await;
+        let schema = setup_schema().await;
+
+        let response = schema
+            .execute(
+                r#"{
+                    tokens(
+                        filter: {creator: "0x1"}
+                        orderByField: SUPPLY
+                        direction: DESC
+                        page: {limit: 2, offset: 1}
+                    ) {
+                        name
+                        supply
+                    }
+                }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "tokens": [
+                    { "name": "Alpha", "supply": 5 },
+                    { "name": "Gamma", "supply": 1 },
+                ]
+            })
+        );
+
+        let response = schema
+            .execute(r#"{ tokens(page: {limit: 1000}) { name } }"#)
+            .await;
+        assert_eq!(response.errors.len(), 1);
+        assert_eq!(
+            response.errors[0].message,
+            format!("limit must be between 1 and {}", MAX_PAGE_SIZE)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_collection_and_ownership_queries() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let _lock = DATABASE_LOCK.lock().await;
+        let schema = setup_schema().await;
+
+        let response = schema
+            .execute(r#"{ collections(orderByField: NAME) { collectionId maxAmount } }"#)
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "collections": [
+                    { "collectionId": "0x2::Another collection", "maxAmount": 10 },
+                    { "collectionId": "0x1::Collection", "maxAmount": null },
+                ]
+            })
+        );
+
+        // Ownerships updated at the same time are paged through by their id
+        let mut ownerships = vec![];
+        for offset in 0..4 {
+            let response = schema
+                .execute(format!(
+                    r#"{{ ownerships(page: {{limit: 1, offset: {}}}) {{ ownershipId amount }} }}"#,
+                    offset
+                ))
+                .await;
+            assert!(response.errors.is_empty(), "{:?}", response.errors);
+            let page = response.data.into_json().unwrap()["ownerships"].clone();
+            ownerships.extend(page.as_array().unwrap().clone());
+        }
+        assert_eq!(
+            json!(ownerships),
+            json!([
+                { "ownershipId": "0x1::Collection::Alpha::0xa", "amount": 1 },
+                { "ownershipId": "0x1::Collection::Alpha::0xb", "amount": 4 },
+                { "ownershipId": "0x1::Collection::Beta::0xb", "amount": 2 },
+            ])
+        );
+
+        let response = schema
+            .execute(
+                r#"{
+                    tokenActivities(filter: {account: "0xa"}, orderByField: SEQUENCE_NUMBER, direction: DESC) {
+                        sequenceNumber
+                        eventType
+                        amount
+                        transactionHash
+                    }
+                }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "tokenActivities": [
+                    {
+                        "sequenceNumber": 1,
+                        "eventType": "0x1::Token::WithdrawEvent",
+                        "amount": "1",
+                        "transactionHash": transaction_hash(11),
+                    },
+                    {
+                        "sequenceNumber": 0,
+                        "eventType": "0x1::Token::DepositEvent",
+                        "amount": "1",
+                        "transactionHash": transaction_hash(11),
+                    },
+                ]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_event_and_coin_queries() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let _lock = DATABASE_LOCK.lock().await;
+        let schema = setup_schema().await;
+
+        // Events with the same sequence number are paged through by their key
+        let mut keys = vec![];
+        for offset in 0..3 {
+            let response = schema
+                .execute(format!(
+                    r#"{{ events(direction: DESC, page: {{limit: 2, offset: {}}}) {{ key }} }}"#,
+                    offset * 2
+                ))
+                .await;
+            assert!(response.errors.is_empty(), "{:?}", response.errors);
+            let page = response.data.into_json().unwrap()["events"].clone();
+            keys.extend(page.as_array().unwrap().iter().map(|e| e["key"].clone()));
+        }
+        assert_eq!(
+            json!(keys),
+            json!([
+                "0x0000000000000000c",
+                "0x0000000000000000b",
+                "0x0000000000000000a",
+            ])
+        );
+
+        let response = schema
+            .execute(
+                r#"{
+                    coinActivities(filter: {transactionVersion: 10}, direction: DESC) {
+                        activityIndex
+                        ownerAddress
+                        activityType
+                        amount
+                    }
+                    currentCoinBalances(orderByField: AMOUNT, direction: DESC, page: {limit: 1}) {
+                        ownerAddress
+                        amount
+                        lastTransactionVersion
+                    }
+                }"#,
+            )
+            .await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(
+            response.data.into_json().unwrap(),
+            json!({
+                "coinActivities": [
+                    {
+                        "activityIndex": 1,
+                        "ownerAddress": "0xb",
+                        "activityType": "deposit",
+                        "amount": "100",
+                    },
+                    {
+                        "activityIndex": 0,
+                        "ownerAddress": "0xa",
+                        "activityType": "withdraw",
+                        "amount": "100",
+                    },
+                ],
+                "currentCoinBalances": [
+                    { "ownerAddress": "0xb", "amount": "1100", "lastTransactionVersion": 10 },
+                ]
+            })
+        );
+    }
+}
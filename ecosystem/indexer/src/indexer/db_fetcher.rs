@@ -0,0 +1,335 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters::{FETCHED_TRANSACTION, UNABLE_TO_FETCH_TRANSACTION},
+    indexer::fetcher::{Fetcher, RETRY_TIME_MILLIS, TRANSACTION_FETCH_BATCH_SIZE},
+};
+use anyhow::{ensure, format_err};
+use aptos_config::config::RocksdbConfig;
+use aptos_rest_client::{
+    aptos_api_types::{AsConverter, TransactionOnChainData},
+    Transaction,
+};
+use aptos_temppath::TempPath;
+use aptos_vm::data_cache::IntoMoveResolver;
+use aptosdb::{AptosDB, LEDGER_DB_NAME, STATE_MERKLE_DB_NAME};
+use async_trait::async_trait;
+use std::{fmt::Debug, path::Path, sync::Arc, time::Duration};
+use storage_interface::{state_view::LatestDbStateCheckpointView, DbReader};
+
+/// Reads transactions directly from the DB of a node running on the same machine, instead of
+/// going through its REST API. The DB is opened as a RocksDB secondary instance, so the node
+/// keeps writing to it while we read.
+pub struct DbTransactionFetcher {
+    db: SecondaryDb,
+    version: u64,
+    transactions_buffer: Vec<Transaction>,
+    // The secondary instance keeps its own (small) state in this directory
+    _secondary_path: TempPath,
+}
+
+impl DbTransactionFetcher {
+    pub fn new(db_path: &Path, starting_version: Option<u64>) -> anyhow::Result<Self> {
+        let secondary_path = TempPath::new();
+        let db = Arc::new(AptosDB::open_as_secondary(
+            db_path,
+            secondary_path.path().join(LEDGER_DB_NAME).as_path(),
+            secondary_path.path().join(STATE_MERKLE_DB_NAME).as_path(),
+            RocksdbConfig::default(),
+        )?);
+
+        Ok(Self {
+            db: SecondaryDb {
+                reader: db.clone(),
+                db,
+            },
+            version: starting_version.unwrap_or(0),
+            transactions_buffer: vec![],
+            _secondary_path: secondary_path,
+        })
+    }
+
+    /// Runs [`SecondaryDb::read_transactions`] on the blocking thread pool, so the RocksDB reads
+    /// don't stall the runtime workers shared with the processors
+    async fn read_transactions(
+        &self,
+        start_version: u64,
+        limit: u64,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || db.read_transactions(start_version, limit)).await?
+    }
+}
+
+/// Handles to the secondary instance, cheap to move into a blocking task
+#[derive(Clone)]
+struct SecondaryDb {
+    db: Arc<AptosDB>,
+    reader: Arc<dyn DbReader>,
+}
+
+impl SecondaryDb {
+    /// Reads up to `limit` transactions starting at `start_version`, converting them the same way
+    /// the REST API does. Returns an empty list if the node has not committed `start_version` yet.
+    fn read_transactions(
+        &self,
+        start_version: u64,
+        limit: u64,
+    ) -> anyhow::Result<Vec<Transaction>> {
+        self.db.try_catch_up_with_primary()?;
+        let ledger_version = self.reader.get_latest_version()?;
+        if start_version > ledger_version {
+            return Ok(vec![]);
+        }
+        let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+
+        let data = self
+            .reader
+            .get_transaction_outputs(start_version, limit, ledger_version)?;
+        let txn_start_version = data
+            .first_transaction_output_version
+            .ok_or_else(|| format_err!("no start version from database"))?;
+        ensure!(
+            txn_start_version == start_version,
+            "invalid start version from database: {} != {}",
+            txn_start_version,
+            start_version
+        );
+        let infos = data.proof.transaction_infos;
+        let transactions_and_outputs = data.transactions_and_outputs;
+        ensure!(
+            transactions_and_outputs.len() == infos.len(),
+            "invalid data size from database: {}, {}",
+            transactions_and_outputs.len(),
+            infos.len(),
+        );
+
+        let resolver = self
+            .reader
+            .latest_state_checkpoint_view()?
+            .into_move_resolver();
+        let converter = resolver.as_converter();
+        let mut timestamp = self.reader.get_block_timestamp(start_version)?;
+        transactions_and_outputs
+            .into_iter()
+            .zip(infos.into_iter())
+            .enumerate()
+            .map(|(i, ((txn, txn_output), info))| {
+                let version = start_version + i as u64;
+                let (write_set, events, _, _) = txn_output.unpack();
+                let accumulator_root_hash = self.reader.get_accumulator_root_hash(version)?;
+                let data: TransactionOnChainData =
+                    (version, txn, info, events, accumulator_root_hash, write_set).into();
+                let txn = converter.try_into_onchain_transaction(timestamp, data)?;
+                // Block metadata transactions update the timestamp of the following transactions
+                timestamp = txn.timestamp();
+                Ok(txn)
+            })
+            .collect()
+    }
+}
+
+impl Debug for DbTransactionFetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "DbTransactionFetcher {{ version: {:?}  buffered: {:?} }}",
+            self.version,
+            self.transactions_buffer.len()
+        )
+    }
+}
+
+#[async_trait]
+impl Fetcher for DbTransactionFetcher {
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+        self.transactions_buffer.clear();
+    }
+
+    /// Reads TRANSACTION_FETCH_BATCH_SIZE versions in bulk (when needed), and uses that buffer to feed out
+    /// Once caught up with the node, checks for new versions every RETRY_TIME_MILLIS ms
+    async fn fetch_next(&mut self) -> Transaction {
+        while self.transactions_buffer.is_empty() {
+            match self
+                .read_transactions(self.version, TRANSACTION_FETCH_BATCH_SIZE)
+                .await
+            {
+                Ok(transactions) if transactions.is_empty() => {
+                    aptos_logger::debug!(
+                        "Could not read transactions starting at {}: all caught up. Will check again in {}ms.",
+                        self.version,
+                        RETRY_TIME_MILLIS,
+                    );
+                }
+                Ok(mut transactions) => {
+                    FETCHED_TRANSACTION.inc();
+                    transactions.reverse();
+                    self.transactions_buffer = transactions;
+                    break;
+                }
+                Err(err) => {
+                    UNABLE_TO_FETCH_TRANSACTION.inc();
+                    aptos_logger::error!(
+                        "Could not read {} transactions starting at {}, will retry in {}ms. Err: {:?}",
+                        TRANSACTION_FETCH_BATCH_SIZE,
+                        self.version,
+                        RETRY_TIME_MILLIS,
+                        err
+                    );
+                }
+            };
+            tokio::time::sleep(Duration::from_millis(RETRY_TIME_MILLIS)).await;
+        }
+        // At this point we're guaranteed to have something in the buffer
+        let transaction = self.transactions_buffer.pop().unwrap();
+        self.version += 1;
+        transaction
+    }
+
+    async fn fetch_version(&self, version: u64) -> Transaction {
+        loop {
+            match self.read_transactions(version, 1).await {
+                Ok(mut transactions) if !transactions.is_empty() => {
+                    FETCHED_TRANSACTION.inc();
+                    return transactions.remove(0);
+                }
+                Ok(_) => {
+                    UNABLE_TO_FETCH_TRANSACTION.inc();
+                    aptos_logger::error!(
+                        "Version {} is not in the database yet, will retry in {}ms.",
+                        version,
+                        RETRY_TIME_MILLIS,
+                    );
+                }
+                Err(err) => {
+                    UNABLE_TO_FETCH_TRANSACTION.inc();
+                    aptos_logger::error!(
+                        "Could not read version {}, will retry in {}ms. Err: {:?}",
+                        version,
+                        RETRY_TIME_MILLIS,
+                        err
+                    );
+                }
+            };
+            tokio::time::sleep(Duration::from_millis(RETRY_TIME_MILLIS)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::PrivateKey;
+    use aptos_transaction_builder::aptos_stdlib;
+    use aptos_types::{
+        account_address::AccountAddress,
+        account_config::aptos_root_address,
+        block_metadata::BlockMetadata,
+        transaction::{Transaction as TransactionEnum, WriteSetPayload},
+        validator_signer::ValidatorSigner,
+    };
+    use executor_test_helpers::{
+        gen_block_id, gen_ledger_info_with_sigs, get_test_signed_transaction,
+        integration_test_impl::create_db_and_executor,
+    };
+    use executor_types::BlockExecutorTrait;
+
+    #[tokio::test]
+    async fn test_read_transactions_matches_rest_converter() {
+        let path = TempPath::new();
+        path.create_as_dir().unwrap();
+        let (genesis, validators) = vm_genesis::test_genesis_change_set_and_validators(Some(1));
+        let genesis_txn = TransactionEnum::GenesisTransaction(WriteSetPayload::Direct(genesis));
+        let genesis_key = &vm_genesis::GENESIS_KEYPAIR.0;
+        let (aptos_db, _, executor, _) = create_db_and_executor(path.path(), &genesis_txn);
+        let signer = ValidatorSigner::new(validators[0].data.address, validators[0].key.clone());
+
+        // One block, so the user transactions take their timestamp from the block metadata
+        let new_account = AccountAddress::random();
+        let block = vec![
+            TransactionEnum::BlockMetadata(BlockMetadata::new(
+                gen_block_id(1),
+                0,
+                1,
+                vec![false],
+                signer.author(),
+                300000001,
+            )),
+            get_test_signed_transaction(
+                aptos_root_address(),
+                0,
+                genesis_key.clone(),
+                genesis_key.public_key(),
+                Some(aptos_stdlib::encode_account_create_account(new_account)),
+            ),
+            get_test_signed_transaction(
+                aptos_root_address(),
+                1,
+                genesis_key.clone(),
+                genesis_key.public_key(),
+                Some(aptos_stdlib::encode_test_coin_mint(new_account, 1_000)),
+            ),
+            TransactionEnum::StateCheckpoint,
+        ];
+        let output = executor
+            .execute_block((gen_block_id(1), block), executor.committed_block_id())
+            .unwrap();
+        executor
+            .commit_blocks(
+                vec![gen_block_id(1)],
+                gen_ledger_info_with_sigs(1, &output, gen_block_id(1), vec![&signer]),
+            )
+            .unwrap();
+
+        // What the REST API returns for each version
+        let reader: Arc<dyn DbReader> = aptos_db;
+        let ledger_version = reader.get_latest_version().unwrap();
+        assert_eq!(ledger_version, 4);
+        let resolver = reader
+            .latest_state_checkpoint_view()
+            .unwrap()
+            .into_move_resolver();
+        let converter = resolver.as_converter();
+        let expected: Vec<Transaction> = (0..=ledger_version)
+            .map(|version| {
+                let txn = reader
+                    .get_transaction_by_version(version, ledger_version, true)
+                    .unwrap();
+                let write_set = reader
+                    .get_write_sets(version, version + 1)
+                    .unwrap()
+                    .remove(0);
+                let data: TransactionOnChainData = (
+                    version,
+                    txn.transaction,
+                    txn.proof.transaction_info,
+                    txn.events.unwrap(),
+                    reader.get_accumulator_root_hash(version).unwrap(),
+                    write_set,
+                )
+                    .into();
+                converter
+                    .try_into_onchain_transaction(
+                        reader.get_block_timestamp(version).unwrap(),
+                        data,
+                    )
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(expected[2].timestamp(), 300000001);
+
+        let fetcher = DbTransactionFetcher::new(path.path(), None).unwrap();
+        assert_eq!(fetcher.read_transactions(0, 100).await.unwrap(), expected);
+        assert_eq!(
+            fetcher.read_transactions(2, 2).await.unwrap(),
+            expected[2..4].to_vec()
+        );
+        assert!(fetcher
+            .read_transactions(ledger_version + 1, 100)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -3,13 +3,31 @@
 
 use crate::counters::{FETCHED_TRANSACTION, UNABLE_TO_FETCH_TRANSACTION};
 use aptos_rest_client::{Client as RestClient, Transaction};
-use std::time::Duration;
+use async_trait::async_trait;
+use std::{fmt::Debug, time::Duration};
 use tokio::sync::Mutex;
 use url::Url;
 
 // TODO: make this configurable
-const RETRY_TIME_MILLIS: u64 = 5000;
-const TRANSACTION_FETCH_BATCH_SIZE: u64 = 500;
+pub(crate) const RETRY_TIME_MILLIS: u64 = 5000;
+pub(crate) const TRANSACTION_FETCH_BATCH_SIZE: u64 = 500;
+
+/// A source of transactions for the `Tailer`
+#[async_trait]
+pub trait Fetcher: Send + Sync + Debug {
+    /// Sets the version `fetch_next` will return next
+    fn set_version(&mut self, version: u64);
+
+    /// Fetches the next version based on its internal version counter
+    /// In the event it can't fetch, it will keep retrying every RETRY_TIME_MILLIS ms
+    async fn fetch_next(&mut self) -> Transaction;
+
+    /// Fetches one version; this is used for error checking/repair/etc
+    /// In the event it can't, it will keep retrying every RETRY_TIME_MILLIS ms
+    async fn fetch_version(&self, version: u64) -> Transaction;
+}
+
+/// Fetches transactions from the REST API of a node
 
 #[derive(Debug)]
 pub struct TransactionFetcher {
@@ -28,15 +46,18 @@ impl TransactionFetcher {
             transactions_buffer: Default::default(),
         }
     }
+}
 
-    pub fn set_version(&mut self, version: u64) {
+#[async_trait]
+impl Fetcher for TransactionFetcher {
+    fn set_version(&mut self, version: u64) {
         self.version = version;
     }
 
     /// Fetches the next version based on its internal version counter
     /// Under the hood, it fetches TRANSACTION_FETCH_BATCH_SIZE versions in bulk (when needed), and uses that buffer to feed out
     /// In the event it can't fetch, it will keep retrying every RETRY_TIME_MILLIS ms
-    pub async fn fetch_next(&mut self) -> Transaction {
+    async fn fetch_next(&mut self) -> Transaction {
         let mut transactions_buffer = self.transactions_buffer.lock().await;
         if transactions_buffer.is_empty() {
             // Fill it up!
@@ -87,7 +108,7 @@ impl TransactionFetcher {
 
     /// fetches one version; this used for error checking/repair/etc
     /// In the event it can't, it will keep retrying every RETRY_TIME_MILLIS ms
-    pub async fn fetch_version(&self, version: u64) -> Transaction {
+    async fn fetch_version(&self, version: u64) -> Transaction {
         loop {
             let res = self.client.get_transaction_by_version(version).await;
             match res {
@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod db_fetcher;
 pub mod errors;
 pub mod fetcher;
 pub mod metadata_fetcher;
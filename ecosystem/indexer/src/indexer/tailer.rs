@@ -3,14 +3,17 @@
 use crate::{
     database::PgDbPool,
     indexer::{
-        errors::TransactionProcessingError, fetcher::TransactionFetcher,
-        processing_result::ProcessingResult, transaction_processor::TransactionProcessor,
+        db_fetcher::DbTransactionFetcher,
+        errors::TransactionProcessingError,
+        fetcher::{Fetcher, TransactionFetcher},
+        processing_result::ProcessingResult,
+        transaction_processor::TransactionProcessor,
     },
 };
 use aptos_logger::info;
 use aptos_rest_client::Transaction;
 use serde_json::Value;
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, path::Path, sync::Arc};
 use tokio::{sync::Mutex, task::JoinHandle};
 use url::{ParseError, Url};
 
@@ -51,7 +54,7 @@ pub fn remove_null_bytes_from_txn(txn: Arc<Transaction>) -> Arc<Transaction> {
 
 #[derive(Clone)]
 pub struct Tailer {
-    transaction_fetcher: Arc<Mutex<TransactionFetcher>>,
+    transaction_fetcher: Arc<Mutex<dyn Fetcher>>,
     processors: Vec<Arc<dyn TransactionProcessor>>,
     connection_pool: PgDbPool,
 }
@@ -67,6 +70,17 @@ impl Tailer {
         })
     }
 
+    /// Creates a `Tailer` which reads transactions directly from the DB at `db_path`, which
+    /// belongs to a node running on the same machine
+    pub fn new_with_db(db_path: &Path, connection_pool: PgDbPool) -> anyhow::Result<Tailer> {
+        let transaction_fetcher = DbTransactionFetcher::new(db_path, None)?;
+        Ok(Self {
+            transaction_fetcher: Arc::new(Mutex::new(transaction_fetcher)),
+            processors: vec![],
+            connection_pool,
+        })
+    }
+
     pub fn run_migrations(&self) {
         info!("Running migrations...");
         embedded_migrations::run_with_output(
@@ -168,18 +182,35 @@ impl Tailer {
         self.process_transaction(txn).await
     }
 
+    /// Fetches the next `batch_size` versions, and hands them to every processor as a single batch
+    /// Returns the results of each processor
     pub async fn process_next_batch(
         &mut self,
         batch_size: u8,
-    ) -> Vec<anyhow::Result<Vec<Result<ProcessingResult, TransactionProcessingError>>>> {
-        let mut tasks = vec![];
+    ) -> Vec<Vec<Result<ProcessingResult, TransactionProcessingError>>> {
+        let mut transactions = Vec::with_capacity(batch_size as usize);
         for _ in 0..batch_size {
-            let mut self2 = self.clone();
-            let task = tokio::task::spawn(async move { self2.process_next().await });
+            transactions.push(remove_null_bytes_from_txn(self.get_next_txn().await));
+        }
+        self.process_transactions(transactions).await
+    }
+
+    pub async fn process_transactions(
+        &self,
+        transactions: Vec<Arc<Transaction>>,
+    ) -> Vec<Vec<Result<ProcessingResult, TransactionProcessingError>>> {
+        let mut tasks = vec![];
+        for processor in &self.processors {
+            let processor2 = processor.clone();
+            let transactions2 = transactions.clone();
+            let task = tokio::task::spawn(async move {
+                processor2
+                    .process_transactions_with_status(transactions2)
+                    .await
+            });
             tasks.push(task);
         }
-        let results = await_tasks(tasks).await;
-        results
+        await_tasks(tasks).await
     }
 
     pub async fn process_transaction(
@@ -230,7 +261,7 @@ pub async fn await_tasks<T: Debug>(tasks: Vec<JoinHandle<T>>) -> Vec<T> {
 }
 
 #[cfg(test)]
-mod test {
+pub mod test {
     use super::*;
     use crate::{
         database::{new_db_pool, PgPoolConnection},
@@ -239,10 +270,22 @@ mod test {
         token_processor::TokenTransactionProcessor,
     };
     use diesel::Connection;
+    use once_cell::sync::Lazy;
     use serde_json::json;
 
+    /// Tests share the database at `INDEXER_DATABASE_URL`, so they must hold this while using it
+    pub static DATABASE_LOCK: Lazy<tokio::sync::Mutex<()>> =
+        Lazy::new(|| tokio::sync::Mutex::new(()));
+
     pub fn wipe_database(conn: &PgPoolConnection) {
         for table in [
+            "token_activities",
+            "metadatas",
+            "ownerships",
+            "tokens",
+            "collections",
+            "coin_activities",
+            "current_coin_balances",
             "write_set_changes",
             "events",
             "user_transactions",
@@ -277,6 +320,7 @@ mod test {
         if crate::should_skip_pg_tests() {
             return;
         }
+        let _lock = DATABASE_LOCK.lock().await;
         let (conn_pool, tailer) = setup_indexer().unwrap();
         // An abridged genesis transaction
         let genesis_txn: Transaction = serde_json::from_value(json!(
@@ -747,4 +791,69 @@ mod test {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn test_batch_processing() {
+        if crate::should_skip_pg_tests() {
+            return;
+        }
+        let _lock = DATABASE_LOCK.lock().await;
+        let (conn_pool, _tailer) = setup_indexer().unwrap();
+        let processor = DefaultTransactionProcessor::new(conn_pool.clone());
+
+        let block_metadata_transaction = |version: u64, hash: &str| -> Arc<Transaction> {
+            Arc::new(
+                serde_json::from_value(json!(
+                    {
+                      "type": "block_metadata_transaction",
+                      "version": version.to_string(),
+                      "hash": hash,
+                      "state_root_hash": "0x3ead9eb40582fbc7df5e02f72280931dc3e6f1aae45dc832966b4cd972dac4b8",
+                      "event_root_hash": "0x2e481956dea9c59b6fc9f823fe5f4c45efce173e42c551c1fe073b5d76a65504",
+                      "gas_used": "0",
+                      "success": true,
+                      "vm_status": "Executed successfully",
+                      "accumulator_root_hash": "0xb0ad602f805eb20c398f0f29a3504a9ef38bcc52c9c451deb9ec4a2d18807b49",
+                      "id": "0xeef99391a3fc681f16963a6c03415bc0b1b12b56c00429308fa8bf46ac9eddf0",
+                      "round": "57600",
+                      "previous_block_votes": [],
+                      "epoch": "1",
+                      "proposer": "0x68f04222bd9f8846cda028ea5ba3846a806b04a47e1f1a4f0939f350d713b2eb",
+                      "timestamp": "1649395495746947",
+                      "changes": []
+                    }
+                ))
+                .unwrap(),
+            )
+        };
+        let batch = vec![
+            block_metadata_transaction(
+                10,
+                "0x2b7c58ed8524d228f9d0543a82e2793d04e8871df322f976b0e7bb8c5ced4f10",
+            ),
+            block_metadata_transaction(
+                11,
+                "0x2b7c58ed8524d228f9d0543a82e2793d04e8871df322f976b0e7bb8c5ced4f11",
+            ),
+        ];
+
+        // Processing the same batch twice must be idempotent
+        for _ in 0..2 {
+            let results = processor
+                .process_transactions_with_status(batch.clone())
+                .await;
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].as_ref().unwrap().version, 11);
+        }
+
+        for version in [10, 11] {
+            let (tx, _, bmt, _, _) =
+                TransactionModel::get_by_version(version, &conn_pool.get().unwrap()).unwrap();
+            assert_eq!(tx.type_, "block_metadata_transaction");
+            assert!(bmt.is_some());
+        }
+        // A single status watermark is written for the whole batch
+        assert_eq!(processor.get_max_version(), Some(11));
+        assert!(processor.get_error_versions().is_empty());
+    }
 }
@@ -32,6 +32,23 @@ pub trait TransactionProcessor: Send + Sync + Debug {
         transaction: Arc<Transaction>,
     ) -> Result<ProcessingResult, TransactionProcessingError>;
 
+    /// Accepts a batch of consecutive transactions, and processes them. This method will be called from
+    /// `process_transactions_with_status`, and on success returns the result for the last version of the batch.
+    /// Processors should override it to write the whole batch in a single DB transaction; by default, each transaction
+    /// is processed in order with `process_transaction`.
+    /// On error, the version of the returned error must be the first version which was not committed: every transaction
+    /// from that version onwards will be processed again, one by one.
+    async fn process_transactions(
+        &self,
+        transactions: Vec<Arc<Transaction>>,
+    ) -> Result<ProcessingResult, TransactionProcessingError> {
+        let mut last_result = None;
+        for transaction in transactions {
+            last_result = Some(self.process_transaction(transaction).await?);
+        }
+        Ok(last_result.expect("Cannot process an empty batch of transactions"))
+    }
+
     /// Gets a reference to the connection pool
     /// This is used by the `get_conn()` helper below
     fn connection_pool(&self) -> &PgDbPool;
@@ -79,6 +96,45 @@ pub trait TransactionProcessor: Send + Sync + Debug {
         res
     }
 
+    /// This is a helper method, like `process_transaction_with_status` but for a batch of consecutive transactions.
+    /// A successful batch only writes a single status for its last version, which acts as a watermark: nothing is
+    /// written before the batch is committed, so a crash mid-batch resumes from the previous watermark.
+    /// If the batch fails, falls back to `process_transaction_with_status` for every version which was not committed.
+    async fn process_transactions_with_status(
+        &self,
+        transactions: Vec<Arc<Transaction>>,
+    ) -> Vec<Result<ProcessingResult, TransactionProcessingError>> {
+        if transactions.is_empty() {
+            return vec![];
+        }
+        PROCESSOR_INVOCATIONS
+            .with_label_values(&[self.name()])
+            .inc();
+
+        match self.process_transactions(transactions.clone()).await {
+            Ok(processing_result) => {
+                self.update_status_success(&processing_result);
+                vec![Ok(processing_result)]
+            }
+            Err(tpe) => {
+                let (err, failed_version, _) = tpe.inner();
+                aptos_logger::warn!(
+                    "[{}] Could not process batch from version {}, falling back to processing versions one by one. Err: {:?}",
+                    self.name(),
+                    failed_version,
+                    err
+                );
+                let mut results = vec![];
+                for transaction in transactions {
+                    if transaction.version().unwrap() >= *failed_version {
+                        results.push(self.process_transaction_with_status(transaction).await);
+                    }
+                }
+                results
+            }
+        }
+    }
+
     /// Writes that a version has been started for this `TransactionProcessor` to the DB
     fn mark_version_started(&self, version: u64) {
         aptos_logger::debug!(
@@ -11,9 +11,11 @@ extern crate diesel_migrations;
 #[macro_use]
 extern crate diesel;
 
+pub mod coin_processor;
 pub mod counters;
 pub mod database;
 pub mod default_processor;
+pub mod graphql;
 pub mod indexer;
 pub mod models;
 pub mod schema;
@@ -9,10 +9,11 @@
 
 use aptos_logger::info;
 use clap::Parser;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use aptos_indexer::{
-    database::new_db_pool, default_processor::DefaultTransactionProcessor, indexer::tailer::Tailer,
+    coin_processor::CoinProcessor, database::new_db_pool,
+    default_processor::DefaultTransactionProcessor, indexer::tailer::Tailer,
     token_processor::TokenTransactionProcessor,
 };
 
@@ -24,8 +25,13 @@ struct IndexerArgs {
     pg_uri: String,
 
     /// URL of an Aptos node, ex: "https://fullnode.devnet.aptoslabs.com"
-    #[clap(long)]
-    node_url: String,
+    #[clap(long, required_unless_present = "db-path", conflicts_with = "db-path")]
+    node_url: Option<String>,
+
+    /// Path to the DB of an Aptos node running on the same machine, ex: "/opt/aptos/data/db".
+    /// If set, transactions are read from it directly instead of from `--node-url`.
+    #[clap(long, parse(from_os_str))]
+    db_path: Option<PathBuf>,
 
     /// If set, don't run any migrations
     #[clap(long)]
@@ -44,7 +50,7 @@ struct IndexerArgs {
     #[clap(long)]
     start_from_version: Option<u64>,
 
-    /// How many versions to fetch and process from a node in parallel
+    /// How many versions to fetch from a node and write to the DB in a single batch
     #[clap(long, default_value_t = 10)]
     batch_size: u8,
 
@@ -58,6 +64,11 @@ struct IndexerArgs {
     /// in the postgres DB tables.
     #[clap(long)]
     index_token_data: bool,
+
+    /// Turn on the indexer to collect coin activities (deposits, withdrawals and gas fees) and
+    /// current coin balances and store them in the postgres DB tables.
+    #[clap(long)]
+    index_coin_data: bool,
 }
 
 #[tokio::main]
@@ -70,7 +81,11 @@ async fn main() -> std::io::Result<()> {
     let conn_pool = new_db_pool(&args.pg_uri).unwrap();
     info!("Created the connection pool... ");
 
-    let mut tailer = Tailer::new(&args.node_url, conn_pool.clone()).unwrap();
+    let mut tailer = match (&args.node_url, &args.db_path) {
+        (_, Some(db_path)) => Tailer::new_with_db(db_path, conn_pool.clone()).unwrap(),
+        (Some(node_url), None) => Tailer::new(node_url, conn_pool.clone()).unwrap(),
+        (None, None) => unreachable!("clap requires one of --node-url or --db-path"),
+    };
 
     if !args.skip_migrations {
         tailer.run_migrations();
@@ -82,6 +97,10 @@ async fn main() -> std::io::Result<()> {
         let token_transaction_processor = TokenTransactionProcessor::new(conn_pool.clone());
         tailer.add_processor(Arc::new(token_transaction_processor));
     }
+    if args.index_coin_data {
+        let coin_processor = CoinProcessor::new(conn_pool.clone());
+        tailer.add_processor(Arc::new(coin_processor));
+    }
 
     let starting_version = match args.start_from_version {
         None => tailer.set_fetcher_to_lowest_processor_version().await,
@@ -99,15 +118,33 @@ async fn main() -> std::io::Result<()> {
 
     info!("Indexing loop started!");
     let mut processed: usize = starting_version as usize;
+    let mut failed: usize = 0;
     let mut base: usize = 0;
     loop {
-        let res = tailer.process_next_batch(args.batch_size).await;
-        processed += res.len();
+        let results = tailer.process_next_batch(args.batch_size).await;
+        // Failed versions are marked as such in the DB, and retried on the next start
+        for res in results.iter().flatten() {
+            if let Err(tpe) = res {
+                let (err, version, processor_name) = tpe.inner();
+                aptos_logger::error!(
+                    "[{}] Could not process version {}. Err: {:?}",
+                    processor_name,
+                    version,
+                    err
+                );
+                failed += 1;
+            }
+        }
+        processed += args.batch_size as usize;
         if args.emit_every != 0 {
             let new_base: usize = processed / args.emit_every;
             if base != new_base {
                 base = new_base;
-                aptos_logger::info!("Indexer has processed {} versions", processed);
+                aptos_logger::info!(
+                    "Indexer has processed {} versions, {} failed to be processed",
+                    processed,
+                    failed
+                );
             }
         }
     }
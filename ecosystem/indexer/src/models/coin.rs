@@ -0,0 +1,380 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::unused_unit)]
+
+use crate::{
+    models::transactions::parse_timestamp,
+    schema::{coin_activities as coin_activitys, current_coin_balances},
+};
+use aptos_rest_client::aptos_api_types::{
+    Address, Event as APIEvent, MoveResource, MoveType, Transaction as APITransaction,
+    TransactionInfo, WriteSetChange as APIWriteSetChange, U64,
+};
+use aptos_types::{account_address::AccountAddress, event::EventKey, utility_coin::TEST_COIN_TYPE};
+use async_graphql::SimpleObject;
+use bigdecimal::BigDecimal;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Gas is always paid in the test coin. Formatted the way the API formats the coin type of a
+/// `CoinStore`, so gas fees and balances of the same coin match.
+pub static GAS_COIN_TYPE: Lazy<String> =
+    Lazy::new(|| MoveType::from(TEST_COIN_TYPE.clone()).to_string());
+
+pub const DEPOSIT_ACTIVITY: &str = "deposit";
+pub const WITHDRAW_ACTIVITY: &str = "withdraw";
+pub const GAS_FEE_ACTIVITY: &str = "gas_fee";
+
+/// A change of a coin balance: a deposit or withdraw event of a `CoinStore`, or the gas paid
+/// by the sender of a user transaction.
+#[derive(Debug, Identifiable, Insertable, Queryable, Clone, PartialEq, SimpleObject)]
+#[diesel(table_name = "coin_activities")]
+#[primary_key(transaction_version, activity_index)]
+#[graphql(complex)]
+pub struct CoinActivity {
+    pub transaction_version: i64,
+    pub activity_index: i64,
+    pub owner_address: String,
+    pub coin_type: String,
+    pub activity_type: String,
+    #[graphql(skip)]
+    pub amount: BigDecimal,
+    pub is_transaction_success: bool,
+    pub transaction_hash: String,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+    pub inserted_at: chrono::NaiveDateTime,
+}
+
+/// The balance of a `CoinStore` as of the last transaction which wrote it
+#[derive(Debug, Identifiable, Insertable, Queryable, Clone, PartialEq, SimpleObject)]
+#[diesel(table_name = "current_coin_balances")]
+#[primary_key(owner_address, coin_type)]
+#[graphql(complex)]
+pub struct CurrentCoinBalance {
+    pub owner_address: String,
+    pub coin_type: String,
+    #[graphql(skip)]
+    pub amount: BigDecimal,
+    pub last_transaction_version: i64,
+    pub last_transaction_timestamp: chrono::NaiveDateTime,
+    pub inserted_at: chrono::NaiveDateTime,
+}
+
+impl CoinActivity {
+    /// Extracts the coin activities and the resulting balances of a transaction
+    pub fn from_transaction(
+        transaction: &APITransaction,
+    ) -> (Vec<CoinActivity>, Vec<CurrentCoinBalance>) {
+        let (info, events, gas_fee_payer) = match transaction {
+            APITransaction::UserTransaction(tx) => (
+                &tx.info,
+                tx.events.as_slice(),
+                Some((tx.request.sender, tx.request.gas_unit_price)),
+            ),
+            APITransaction::GenesisTransaction(tx) => (&tx.info, tx.events.as_slice(), None),
+            APITransaction::BlockMetadataTransaction(tx) => (&tx.info, &[][..], None),
+            APITransaction::StateCheckpointTransaction(tx) => (&tx.info, &[][..], None),
+            APITransaction::PendingTransaction(..) => return (vec![], vec![]),
+        };
+        let timestamp = parse_timestamp(U64::from(transaction.timestamp()), info.version);
+        let version = *info.version.inner() as i64;
+
+        let coin_stores = CoinStoreChange::from_write_set_changes(&info.changes);
+        let mut stores_by_event_key = HashMap::new();
+        for coin_store in &coin_stores {
+            stores_by_event_key.insert(coin_store.deposit_events, (coin_store, DEPOSIT_ACTIVITY));
+            stores_by_event_key.insert(coin_store.withdraw_events, (coin_store, WITHDRAW_ACTIVITY));
+        }
+
+        let mut activities = vec![];
+        for event in events {
+            let (coin_store, activity_type) =
+                match stores_by_event_key.get(&EventKey::from(event.key)) {
+                    Some(store) => *store,
+                    None => continue,
+                };
+            let amount = match event_amount(event) {
+                Some(amount) => amount,
+                None => continue,
+            };
+            activities.push(Self::new(
+                info,
+                activities.len() as i64,
+                coin_store.owner_address.clone(),
+                coin_store.coin_type.clone(),
+                activity_type,
+                amount,
+                timestamp,
+            ));
+        }
+
+        if let Some((sender, gas_unit_price)) = gas_fee_payer {
+            let gas_fee =
+                BigDecimal::from(*info.gas_used.inner()) * BigDecimal::from(gas_unit_price.0);
+            activities.push(Self::new(
+                info,
+                activities.len() as i64,
+                sender.inner().to_hex_literal(),
+                GAS_COIN_TYPE.clone(),
+                GAS_FEE_ACTIVITY,
+                gas_fee,
+                timestamp,
+            ));
+        }
+
+        let balances = coin_stores
+            .into_iter()
+            .map(|coin_store| CurrentCoinBalance {
+                owner_address: coin_store.owner_address,
+                coin_type: coin_store.coin_type,
+                amount: BigDecimal::from(coin_store.balance),
+                last_transaction_version: version,
+                last_transaction_timestamp: timestamp,
+                inserted_at: chrono::Utc::now().naive_utc(),
+            })
+            .collect();
+
+        (activities, balances)
+    }
+
+    fn new(
+        info: &TransactionInfo,
+        activity_index: i64,
+        owner_address: String,
+        coin_type: String,
+        activity_type: &str,
+        amount: BigDecimal,
+        transaction_timestamp: chrono::NaiveDateTime,
+    ) -> Self {
+        CoinActivity {
+            transaction_version: *info.version.inner() as i64,
+            activity_index,
+            owner_address,
+            coin_type,
+            activity_type: activity_type.to_string(),
+            amount,
+            is_transaction_success: info.success,
+            transaction_hash: info.hash.to_string(),
+            transaction_timestamp,
+            inserted_at: chrono::Utc::now().naive_utc(),
+        }
+    }
+}
+
+fn event_amount(event: &APIEvent) -> Option<BigDecimal> {
+    let amount = event.data.get("amount")?.as_str()?;
+    amount.parse::<u64>().ok().map(BigDecimal::from)
+}
+
+/// A `0x1::Coin::CoinStore` written by a transaction
+struct CoinStoreChange {
+    owner_address: String,
+    coin_type: String,
+    balance: u64,
+    deposit_events: EventKey,
+    withdraw_events: EventKey,
+}
+
+impl CoinStoreChange {
+    fn from_write_set_changes(changes: &[APIWriteSetChange]) -> Vec<Self> {
+        changes
+            .iter()
+            .filter_map(|change| match change {
+                APIWriteSetChange::WriteResource { address, data, .. } => {
+                    Self::from_resource(address, data)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn from_resource(address: &Address, resource: &MoveResource) -> Option<Self> {
+        let typ = &resource.typ;
+        if *typ.address.inner() != AccountAddress::ONE
+            || typ.module.as_str() != "Coin"
+            || typ.name.as_str() != "CoinStore"
+        {
+            return None;
+        }
+        let coin_type = typ.generic_type_params.first()?.to_string();
+        let data: CoinStoreResource =
+            serde_json::from_value(serde_json::to_value(&resource.data).ok()?).ok()?;
+        Some(CoinStoreChange {
+            owner_address: address.inner().to_hex_literal(),
+            coin_type,
+            balance: *data.coin.value.inner(),
+            deposit_events: data.deposit_events.key(),
+            withdraw_events: data.withdraw_events.key(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct CoinStoreResource {
+    coin: CoinResource,
+    deposit_events: EventHandleResource,
+    withdraw_events: EventHandleResource,
+}
+
+#[derive(Deserialize)]
+struct CoinResource {
+    value: U64,
+}
+
+#[derive(Deserialize)]
+struct EventHandleResource {
+    guid: GuidWrapperResource,
+}
+
+impl EventHandleResource {
+    /// The key of the events emitted to the handle, derived from its GUID
+    fn key(&self) -> EventKey {
+        let id = &self.guid.guid.id;
+        EventKey::new_from_address(id.addr.inner(), *id.creation_num.inner())
+    }
+}
+
+#[derive(Deserialize)]
+struct GuidWrapperResource {
+    guid: GuidResource,
+}
+
+#[derive(Deserialize)]
+struct GuidResource {
+    id: GuidIdResource,
+}
+
+#[derive(Deserialize)]
+struct GuidIdResource {
+    addr: Address,
+    creation_num: U64,
+}
+
+// Prevent conflicts with other things named `CoinActivity`
+pub type CoinActivityModel = CoinActivity;
+pub type CurrentCoinBalanceModel = CurrentCoinBalance;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn coin_store(
+        address: &str,
+        value: &str,
+        deposit_num: &str,
+        withdraw_num: &str,
+    ) -> serde_json::Value {
+        json!({
+            "type": "write_resource",
+            "address": address,
+            "state_key_hash": "0x0",
+            "data": {
+                "type": "0x1::Coin::CoinStore<0x1::TestCoin::TestCoin>",
+                "data": {
+                    "coin": { "value": value },
+                    "deposit_events": {
+                        "counter": "1",
+                        "guid": { "len_bytes": 40, "guid": { "id": { "addr": address, "creation_num": deposit_num } } }
+                    },
+                    "withdraw_events": {
+                        "counter": "1",
+                        "guid": { "len_bytes": 40, "guid": { "id": { "addr": address, "creation_num": withdraw_num } } }
+                    }
+                }
+            }
+        })
+    }
+
+    fn event_key(address: &str, creation_num: u64) -> String {
+        let address = AccountAddress::from_hex_literal(address).unwrap();
+        format!("{:#x}", EventKey::new_from_address(&address, creation_num))
+    }
+
+    #[test]
+    fn test_coin_transfer() {
+        let hash = format!("0x{}", "a".repeat(64));
+        let transaction: APITransaction = serde_json::from_value(json!({
+            "type": "user_transaction",
+            "version": "10",
+            "hash": hash,
+            "state_root_hash": hash,
+            "event_root_hash": hash,
+            "gas_used": "7",
+            "success": true,
+            "vm_status": "Executed successfully",
+            "accumulator_root_hash": hash,
+            "changes": [coin_store("0x2", "900", "2", "3"), coin_store("0x3", "100", "4", "5")],
+            "sender": "0x2",
+            "sequence_number": "0",
+            "max_gas_amount": "2000",
+            "gas_unit_price": "3",
+            "expiration_timestamp_secs": "1649560602",
+            "payload": {
+                "type": "script_function_payload",
+                "function": "0x1::Coin::transfer",
+                "type_arguments": ["0x1::TestCoin::TestCoin"],
+                "arguments": ["0x3", "100"]
+            },
+            "events": [
+                {
+                    "key": event_key("0x2", 3),
+                    "sequence_number": "0",
+                    "type": "0x1::Coin::WithdrawEvent",
+                    "data": { "amount": "100" }
+                },
+                {
+                    "key": event_key("0x3", 4),
+                    "sequence_number": "0",
+                    "type": "0x1::Coin::DepositEvent",
+                    "data": { "amount": "100" }
+                }
+            ],
+            "timestamp": "1649560602763949"
+        }))
+        .unwrap();
+
+        let (activities, balances) = CoinActivity::from_transaction(&transaction);
+
+        let activities: Vec<_> = activities
+            .iter()
+            .map(|a| {
+                (
+                    a.activity_index,
+                    a.owner_address.as_str(),
+                    a.activity_type.as_str(),
+                    a.amount.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            activities,
+            vec![
+                (0, "0x2", WITHDRAW_ACTIVITY, BigDecimal::from(100)),
+                (1, "0x3", DEPOSIT_ACTIVITY, BigDecimal::from(100)),
+                (2, "0x2", GAS_FEE_ACTIVITY, BigDecimal::from(21)),
+            ]
+        );
+
+        let balances: Vec<_> = balances
+            .iter()
+            .map(|b| {
+                (
+                    b.owner_address.as_str(),
+                    b.coin_type.as_str(),
+                    b.amount.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            balances,
+            vec![
+                ("0x2", GAS_COIN_TYPE.as_str(), BigDecimal::from(900)),
+                ("0x3", GAS_COIN_TYPE.as_str(), BigDecimal::from(100)),
+            ]
+        );
+    }
+}
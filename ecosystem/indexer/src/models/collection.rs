@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::schema::collections;
+use async_graphql::SimpleObject;
 use serde::Serialize;
 
-#[derive(Associations, Debug, Identifiable, Insertable, Queryable, Serialize, Clone)]
+#[derive(
+    Associations, Debug, Identifiable, Insertable, Queryable, Serialize, Clone, SimpleObject,
+)]
 #[diesel(table_name = "collections")]
 #[primary_key(collection_id)]
 pub struct Collection {
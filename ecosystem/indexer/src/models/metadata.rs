@@ -2,9 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::schema::metadatas;
+use async_graphql::SimpleObject;
 use serde::{Deserialize, Serialize};
 
-#[derive(Associations, Debug, Identifiable, Insertable, Queryable, Serialize, Clone)]
+#[derive(
+    Associations, Debug, Identifiable, Insertable, Queryable, Serialize, Clone, SimpleObject,
+)]
 #[diesel(table_name = "metadata")]
 #[primary_key(token_id)]
 pub struct Metadata {
@@ -1,11 +1,13 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod coin;
 pub mod collection;
 pub mod events;
 pub mod metadata;
 pub mod ownership;
 pub mod processor_statuses;
 pub mod token;
+pub mod token_activity;
 pub mod transactions;
 pub mod write_set_changes;
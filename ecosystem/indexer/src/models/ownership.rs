@@ -2,15 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::schema::ownerships;
+use async_graphql::SimpleObject;
 use serde::Serialize;
 
-#[derive(Associations, Debug, Identifiable, Insertable, Queryable, Serialize, Clone)]
+#[derive(
+    Associations, Debug, Identifiable, Insertable, Queryable, Serialize, Clone, SimpleObject,
+)]
 #[diesel(table_name = "ownerships")]
 #[primary_key(ownership_id)]
 pub struct Ownership {
     pub ownership_id: String,
-    pub token_id: String,
-    pub owner: String,
+    pub token_id: Option<String>,
+    pub owner: Option<String>,
     pub amount: i64,
     pub updated_at: chrono::NaiveDateTime,
     pub inserted_at: chrono::NaiveDateTime,
@@ -27,8 +30,8 @@ impl Ownership {
         let ownership_id = format!("{}::{}", token_id, owner);
         Ownership {
             ownership_id,
-            token_id,
-            owner,
+            token_id: Some(token_id),
+            owner: Some(owner),
             amount,
             updated_at,
             inserted_at,
@@ -3,11 +3,14 @@
 
 use crate::{models::events::Event, schema::tokens};
 use aptos_rest_client::types;
+use async_graphql::SimpleObject;
 use std::{collections::HashMap, fmt, fmt::Formatter, str::FromStr};
 
 use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Associations, Debug, Identifiable, Insertable, Queryable, Serialize, Clone)]
+#[derive(
+    Associations, Debug, Identifiable, Insertable, Queryable, Serialize, Clone, SimpleObject,
+)]
 #[diesel(table_name = "tokens")]
 #[primary_key(token_id)]
 pub struct Token {
@@ -20,8 +23,8 @@ pub struct Token {
     pub supply: i64,
     pub uri: String,
     pub minted_at: chrono::NaiveDateTime,
-    pub inserted_at: chrono::NaiveDateTime,
     pub last_minted_at: chrono::NaiveDateTime,
+    pub inserted_at: chrono::NaiveDateTime,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
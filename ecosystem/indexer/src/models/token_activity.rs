@@ -1,2 +1,28 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
+
+use crate::{models::transactions::Transaction, schema::token_activities as token_activitys};
+use async_graphql::SimpleObject;
+use bigdecimal::BigDecimal;
+
+/// A token event emitted by an account
+#[derive(Associations, Debug, Identifiable, Queryable, Clone, SimpleObject)]
+#[diesel(table_name = "token_activities")]
+#[belongs_to(Transaction, foreign_key = "transaction_hash")]
+#[primary_key(event_key, sequence_number)]
+#[graphql(complex)]
+pub struct TokenActivity {
+    pub event_key: String,
+    pub sequence_number: i64,
+    pub account: String,
+    pub token_id: Option<String>,
+    pub event_type: Option<String>,
+    #[graphql(skip)]
+    pub amount: Option<BigDecimal>,
+    pub created_at: chrono::NaiveDateTime,
+    pub inserted_at: chrono::NaiveDateTime,
+    pub transaction_hash: String,
+}
+
+// Prevent conflicts with other things named `TokenActivity`
+pub type TokenActivityModel = TokenActivity;
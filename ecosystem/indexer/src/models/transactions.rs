@@ -13,17 +13,20 @@ use aptos_rest_client::aptos_api_types::{
     Address, BlockMetadataTransaction as APIBlockMetadataTransaction,
     Transaction as APITransaction, TransactionInfo, UserTransaction as APIUserTransaction, U64,
 };
+use async_graphql::SimpleObject;
 use diesel::{
     BelongingToDsl, ExpressionMethods, GroupedBy, OptionalExtension, QueryDsl, RunQueryDsl,
 };
 use futures::future::Either;
 use serde::Serialize;
 
-#[derive(AsChangeset, Debug, Identifiable, Insertable, Queryable, Serialize)]
+#[derive(AsChangeset, Debug, Identifiable, Insertable, Queryable, Serialize, SimpleObject)]
 #[primary_key(hash)]
 #[diesel(table_name = "transactions")]
+#[graphql(complex)]
 pub struct Transaction {
     #[diesel(column_name = type)]
+    #[graphql(name = "type")]
     pub type_: String,
     pub payload: serde_json::Value,
     pub version: i64,
@@ -275,7 +278,9 @@ impl Transaction {
     }
 }
 
-#[derive(AsChangeset, Associations, Debug, Identifiable, Insertable, Queryable, Serialize)]
+#[derive(
+    AsChangeset, Associations, Debug, Identifiable, Insertable, Queryable, Serialize, SimpleObject,
+)]
 #[belongs_to(Transaction, foreign_key = "hash")]
 #[primary_key(hash)]
 #[diesel(table_name = "user_transactions")]
@@ -316,7 +321,9 @@ impl UserTransaction {
     }
 }
 
-#[derive(AsChangeset, Associations, Debug, Identifiable, Insertable, Queryable, Serialize)]
+#[derive(
+    AsChangeset, Associations, Debug, Identifiable, Insertable, Queryable, Serialize, SimpleObject,
+)]
 #[belongs_to(Transaction, foreign_key = "hash")]
 #[primary_key("hash")]
 #[diesel(table_name = "block_metadata_transactions")]
@@ -352,7 +359,7 @@ impl BlockMetadataTransaction {
     }
 }
 
-fn parse_timestamp(ts: U64, version: U64) -> chrono::NaiveDateTime {
+pub fn parse_timestamp(ts: U64, version: U64) -> chrono::NaiveDateTime {
     chrono::NaiveDateTime::from_timestamp_opt(*ts.inner() as i64 / 1000000, 0)
         .unwrap_or_else(|| panic!("Could not parse timestamp {:?} for version {}", ts, version))
 }
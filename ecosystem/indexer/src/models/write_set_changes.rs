@@ -3,10 +3,13 @@
 
 use crate::{models::transactions::Transaction, schema::write_set_changes};
 use aptos_rest_client::aptos_api_types::WriteSetChange as APIWriteSetChange;
+use async_graphql::SimpleObject;
 use serde::Serialize;
 use serde_json::json;
 
-#[derive(AsChangeset, Associations, Debug, Identifiable, Insertable, Queryable, Serialize)]
+#[derive(
+    AsChangeset, Associations, Debug, Identifiable, Insertable, Queryable, Serialize, SimpleObject,
+)]
 #[diesel(table_name = "write_set_changes")]
 #[belongs_to(Transaction, foreign_key = "transaction_hash")]
 #[primary_key(transaction_hash, hash)]
@@ -14,6 +17,7 @@ pub struct WriteSetChange {
     pub transaction_hash: String,
     pub hash: String,
     #[diesel(column_name = type)]
+    #[graphql(name = "type")]
     pub type_: String,
     pub address: String,
     pub module: serde_json::Value,
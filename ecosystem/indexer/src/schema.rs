@@ -15,6 +15,21 @@ table! {
     }
 }
 
+table! {
+    coin_activities (transaction_version, activity_index) {
+        transaction_version -> Int8,
+        activity_index -> Int8,
+        owner_address -> Varchar,
+        coin_type -> Varchar,
+        activity_type -> Varchar,
+        amount -> Numeric,
+        is_transaction_success -> Bool,
+        transaction_hash -> Varchar,
+        transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
 table! {
     collections (collection_id) {
         collection_id -> Varchar,
@@ -28,6 +43,17 @@ table! {
     }
 }
 
+table! {
+    current_coin_balances (owner_address, coin_type) {
+        owner_address -> Varchar,
+        coin_type -> Varchar,
+        amount -> Numeric,
+        last_transaction_version -> Int8,
+        last_transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
 table! {
     events (key, sequence_number) {
         transaction_hash -> Varchar,
@@ -155,7 +181,9 @@ table! {
 
 allow_tables_to_appear_in_same_query!(
     block_metadata_transactions,
+    coin_activities,
     collections,
+    current_coin_balances,
     events,
     metadatas,
     ownerships,
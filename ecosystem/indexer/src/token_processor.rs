@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    database::{execute_with_better_error, PgDbPool, PgPoolConnection},
+    database::{execute_with_better_error, PgDbPool, PgPoolConnection, MAX_INSERT_ROWS},
     indexer::{
         errors::TransactionProcessingError, metadata_fetcher::MetaDataFetcher,
         processing_result::ProcessingResult, transaction_processor::TransactionProcessor,
@@ -23,9 +23,13 @@ use crate::{
 };
 use aptos_rest_client::Transaction;
 use async_trait::async_trait;
-use diesel::{Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
+use diesel::{upsert::excluded, Connection, ExpressionMethods, QueryDsl, RunQueryDsl};
 use futures::future::Either;
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    collections::{btree_map::Entry, BTreeMap},
+    fmt::Debug,
+    sync::Arc,
+};
 
 pub struct TokenTransactionProcessor {
     connection_pool: PgDbPool,
@@ -48,15 +52,6 @@ impl Debug for TokenTransactionProcessor {
     }
 }
 
-fn update_mint_token(conn: &PgPoolConnection, event_data: MintEventType, txn: &UserTransaction) {
-    let last_mint_time = txn.timestamp;
-    let query = diesel::update(tokens.find(event_data.id.to_string())).set((
-        supply.eq(supply + event_data.amount),
-        last_minted_at.eq(last_mint_time),
-    ));
-    query.execute(conn).expect("Error updating row in token");
-}
-
 async fn get_all_metadata(uris: &Vec<(String, String)>, res: &mut Vec<Metadata>) {
     let fetcher = MetaDataFetcher::new();
     for (tid, uri) in uris {
@@ -70,115 +65,151 @@ async fn get_all_metadata(uris: &Vec<(String, String)>, res: &mut Vec<Metadata>)
     }
 }
 
-fn insert_token(conn: &PgPoolConnection, event_data: CreationEventType, txn: &UserTransaction) {
-    let token = Token {
-        token_id: event_data.id.to_string(),
-        creator: event_data.id.creator,
-        collection: event_data.id.collection,
-        name: event_data.id.name,
-        description: event_data.token_data.description,
-        max_amount: event_data.token_data.maximum.value,
-        supply: 1, //TODO add initial balance to event
-        uri: event_data.token_data.uri,
-        minted_at: txn.timestamp,
-        inserted_at: chrono::Utc::now().naive_utc(),
-        last_minted_at: txn.timestamp,
-    };
-    execute_with_better_error(
-        conn,
-        diesel::insert_into(schema::tokens::table)
-            .values(&token)
-            .on_conflict_do_nothing(),
-    )
-    .expect("Error inserting row into token");
+/// The token rows written by a batch of transactions, merged so that every row is written once
+#[derive(Default)]
+struct TokenChanges {
+    tokens: Vec<Token>,
+    collections: Vec<Collection>,
+    /// Amount minted and time of the last mint, by token id
+    mints: BTreeMap<String, (i64, chrono::NaiveDateTime)>,
+    /// Ownerships as first inserted, with the total change of their amount, by ownership id
+    ownerships: BTreeMap<String, Ownership>,
+    /// Token ids and URIs of the created tokens, whose metadata is to be fetched
+    uris: Vec<(String, String)>,
 }
 
-fn update_token_ownership(
-    conn: &PgPoolConnection,
-    token_id: String,
-    txn: &UserTransaction,
-    amount_update: i64,
-) {
-    let ownership = Ownership::new(
-        token_id,
-        txn.sender.clone(),
-        amount_update,
-        txn.timestamp,
-        chrono::Utc::now().naive_utc(),
-    );
-    execute_with_better_error(
-        conn,
-        diesel::insert_into(schema::ownerships::table)
-            .values(&ownership)
-            .on_conflict(ownership_id)
-            .do_update()
-            .set(ownership_amount.eq(ownership_amount + ownership.amount)),
-    )
-    .expect("Error update token ownership");
-}
+impl TokenChanges {
+    fn add_token(&mut self, event_data: CreationEventType, txn: &UserTransaction) {
+        self.uris
+            .push((event_data.id.to_string(), event_data.token_data.uri.clone()));
+        self.tokens.push(Token {
+            token_id: event_data.id.to_string(),
+            creator: event_data.id.creator,
+            collection: event_data.id.collection,
+            name: event_data.id.name,
+            description: event_data.token_data.description,
+            max_amount: event_data.token_data.maximum.value,
+            supply: 1, //TODO add initial balance to event
+            uri: event_data.token_data.uri,
+            minted_at: txn.timestamp,
+            inserted_at: chrono::Utc::now().naive_utc(),
+            last_minted_at: txn.timestamp,
+        });
+    }
 
-fn insert_collection(
-    conn: &PgPoolConnection,
-    event_data: CreateCollectionEventType,
-    txn: &UserTransaction,
-) {
-    let collection = Collection::new(
-        event_data.creator,
-        event_data.collection_name,
-        event_data.description,
-        event_data.maximum.value,
-        event_data.uri,
-        txn.timestamp,
-        chrono::Utc::now().naive_utc(),
-    );
-    execute_with_better_error(
-        conn,
-        diesel::insert_into(schema::collections::table)
-            .values(&collection)
-            .on_conflict_do_nothing(),
-    )
-    .expect("Error inserting row into collections");
-}
+    fn add_mint(&mut self, event_data: MintEventType, txn: &UserTransaction) {
+        let mint = self
+            .mints
+            .entry(event_data.id.to_string())
+            .or_insert((0, txn.timestamp));
+        mint.0 += event_data.amount;
+        mint.1 = txn.timestamp;
+    }
 
-fn process_token_on_chain_data(
-    conn: &PgPoolConnection,
-    events: &[EventModel],
-    txn: &UserTransaction,
-    uris: &mut Vec<(String, String)>,
-) {
-    // filter events to only keep token events
-    let token_events = events
-        .iter()
-        .map(TokenEvent::from_event)
-        .filter(|e| e.is_some())
-        .collect::<Vec<Option<TokenEvent>>>();
-    // for create token event, insert a new token to token table,
-    // if token exists, increase the supply
-    for event in token_events {
-        match event.unwrap() {
-            TokenEvent::CreationEvent(event_data) => {
-                let uri = event_data.token_data.uri.clone();
-                let tid = event_data.id.to_string();
-                insert_token(conn, event_data, txn);
-                uris.push((tid, uri));
-            }
-            TokenEvent::MintEvent(event_data) => {
-                update_mint_token(conn, event_data, txn);
-            }
-            TokenEvent::CollectionCreationEvent(event_data) => {
-                insert_collection(conn, event_data, txn);
-            }
-            TokenEvent::DepositEvent(event_data) => {
-                update_token_ownership(conn, event_data.id.to_string(), txn, event_data.amount);
+    fn add_collection(&mut self, event_data: CreateCollectionEventType, txn: &UserTransaction) {
+        self.collections.push(Collection::new(
+            event_data.creator,
+            event_data.collection_name,
+            event_data.description,
+            event_data.maximum.value,
+            event_data.uri,
+            txn.timestamp,
+            chrono::Utc::now().naive_utc(),
+        ));
+    }
+
+    fn update_ownership(&mut self, token_id: String, txn: &UserTransaction, amount_update: i64) {
+        let ownership = Ownership::new(
+            token_id,
+            txn.sender.clone(),
+            amount_update,
+            txn.timestamp,
+            chrono::Utc::now().naive_utc(),
+        );
+        match self.ownerships.entry(ownership.ownership_id.clone()) {
+            Entry::Occupied(mut entry) => entry.get_mut().amount += amount_update,
+            Entry::Vacant(entry) => {
+                entry.insert(ownership);
             }
-            TokenEvent::WithdrawEvent(event_data) => {
-                update_token_ownership(conn, event_data.id.to_string(), txn, -event_data.amount);
+        }
+    }
+
+    fn add_events(&mut self, events: &[EventModel], txn: &UserTransaction) {
+        // for create token event, insert a new token to token table,
+        // if token exists, increase the supply
+        for event in events.iter().filter_map(TokenEvent::from_event) {
+            match event {
+                TokenEvent::CreationEvent(event_data) => self.add_token(event_data, txn),
+                TokenEvent::MintEvent(event_data) => self.add_mint(event_data, txn),
+                TokenEvent::CollectionCreationEvent(event_data) => {
+                    self.add_collection(event_data, txn)
+                }
+                TokenEvent::DepositEvent(event_data) => {
+                    self.update_ownership(event_data.id.to_string(), txn, event_data.amount)
+                }
+                TokenEvent::WithdrawEvent(event_data) => {
+                    self.update_ownership(event_data.id.to_string(), txn, -event_data.amount)
+                }
+                _ => (),
             }
-            _ => (),
         }
     }
 }
 
+/// Writes the token changes of a whole batch of transactions, with one multi-row insert per table
+/// (and per `MAX_INSERT_ROWS` rows). Tokens are inserted before their mints are applied, and the
+/// amount of an existing ownership is increased by the batch's total change.
+fn insert_token_changes(
+    conn: &PgPoolConnection,
+    changes: &TokenChanges,
+    metadatas: &[Metadata],
+) -> diesel::QueryResult<()> {
+    for chunk in changes.tokens.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::tokens::table)
+                .values(chunk)
+                .on_conflict_do_nothing(),
+        )?;
+    }
+    for chunk in changes.collections.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::collections::table)
+                .values(chunk)
+                .on_conflict_do_nothing(),
+        )?;
+    }
+    for (token_id, (amount, last_mint_time)) in &changes.mints {
+        diesel::update(tokens.find(token_id))
+            .set((
+                supply.eq(supply + *amount),
+                last_minted_at.eq(*last_mint_time),
+            ))
+            .execute(conn)?;
+    }
+    let ownerships: Vec<_> = changes.ownerships.values().cloned().collect();
+    for chunk in ownerships.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::ownerships::table)
+                .values(chunk)
+                .on_conflict(ownership_id)
+                .do_update()
+                .set(ownership_amount.eq(ownership_amount + excluded(ownership_amount))),
+        )?;
+    }
+    for chunk in metadatas.chunks(MAX_INSERT_ROWS) {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::metadatas::table)
+                .values(chunk)
+                .on_conflict_do_nothing(),
+        )?;
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl TransactionProcessor for TokenTransactionProcessor {
     fn name(&self) -> &'static str {
@@ -189,50 +220,41 @@ impl TransactionProcessor for TokenTransactionProcessor {
         &self,
         transaction: Arc<Transaction>,
     ) -> Result<ProcessingResult, TransactionProcessingError> {
-        let version = transaction.version().unwrap_or(0);
-
-        let (_, maybe_details_model, maybe_events, _) =
-            TransactionModel::from_transaction(&transaction);
+        self.process_transactions(vec![transaction]).await
+    }
 
-        let conn = self.get_conn();
-        let mut token_uris: Vec<(String, String)> = vec![];
+    async fn process_transactions(
+        &self,
+        transactions: Vec<Arc<Transaction>>,
+    ) -> Result<ProcessingResult, TransactionProcessingError> {
+        let start_version = transactions.first().unwrap().version().unwrap_or(0);
+        let end_version = transactions.last().unwrap().version().unwrap_or(0);
 
-        let tx_result = conn.transaction::<(), diesel::result::Error, _>(|| {
-            if let Some(Either::Left(user_txn)) = maybe_details_model {
-                if let Some(events) = maybe_events {
-                    process_token_on_chain_data(&conn, &events, &user_txn, &mut token_uris);
-                }
+        let mut changes = TokenChanges::default();
+        for transaction in &transactions {
+            let (_, maybe_details_model, maybe_events, _) =
+                TransactionModel::from_transaction(transaction);
+            if let (Some(Either::Left(user_txn)), Some(events)) =
+                (maybe_details_model, maybe_events)
+            {
+                changes.add_events(&events, &user_txn);
             }
-            Ok(())
-        });
+        }
 
-        if let Err(err) = tx_result {
-            return Err(TransactionProcessingError::TransactionCommitError((
-                anyhow::Error::from(err),
-                version,
-                self.name(),
-            )));
-        };
+        // The metadata is fetched before the DB transaction, so that it isn't held open meanwhile
+        let mut metadatas: Vec<Metadata> = vec![];
+        get_all_metadata(&changes.uris, &mut metadatas).await;
 
-        let mut res: Vec<Metadata> = vec![];
-        get_all_metadata(&token_uris, &mut res).await;
+        let conn = self.get_conn();
         let tx_result = conn.transaction::<(), diesel::result::Error, _>(|| {
-            for metadata in res {
-                execute_with_better_error(
-                    &conn,
-                    diesel::insert_into(schema::metadatas::table)
-                        .values(&metadata)
-                        .on_conflict_do_nothing(),
-                )
-                .expect("Error inserting row into metadatas");
-            }
-            Ok(())
+            insert_token_changes(&conn, &changes, &metadatas)
         });
+
         match tx_result {
-            Ok(_) => Ok(ProcessingResult::new(self.name(), version)),
+            Ok(_) => Ok(ProcessingResult::new(self.name(), end_version)),
             Err(err) => Err(TransactionProcessingError::TransactionCommitError((
                 anyhow::Error::from(err),
-                version,
+                start_version,
                 self.name(),
             ))),
         }
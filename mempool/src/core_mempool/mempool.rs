@@ -123,6 +123,11 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    /// Returns the gas unit prices of up to `limit` transactions that are ready for consensus.
+    pub(crate) fn get_gas_prices(&self, limit: usize) -> Vec<u64> {
+        self.transactions.get_gas_prices(limit)
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -102,6 +102,22 @@ impl TransactionStore {
         }
     }
 
+    /// Returns the gas unit prices of up to `limit` transactions in the priority queue, ordered
+    /// from the highest ranked transaction down.
+    pub(crate) fn get_gas_prices(&self, limit: usize) -> Vec<u64> {
+        self.priority_index
+            .iter()
+            .take(limit)
+            .filter_map(|key| {
+                self.get_mempool_txn(
+                    &key.address,
+                    key.sequence_number.transaction_sequence_number,
+                )
+            })
+            .map(|txn| txn.get_gas_price())
+            .collect()
+    }
+
     /// Fetch mempool transaction by account address + sequence_number.
     pub(crate) fn get_mempool_txn(
         &self,
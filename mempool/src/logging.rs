@@ -130,6 +130,7 @@ pub enum LogEntry {
     ReconfigUpdate,
     JsonRpc,
     GetTransaction,
+    GetGasPrices,
     GetBlock,
     QuorumStore,
     StateSyncCommit,
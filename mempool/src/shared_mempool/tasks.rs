@@ -142,6 +142,27 @@ pub(crate) async fn process_client_get_transaction<V>(
     }
 }
 
+/// Processes get gas prices request by client.
+pub(crate) async fn process_client_get_gas_prices<V>(
+    smp: SharedMempool<V>,
+    limit: usize,
+    callback: oneshot::Sender<Vec<u64>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let gas_prices = smp.mempool.lock().get_gas_prices(limit);
+
+    if callback.send(gas_prices).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetGasPrices,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<V>(
     smp: SharedMempool<V>,
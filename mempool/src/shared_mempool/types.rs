@@ -208,6 +208,7 @@ pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    GetGasPrices(usize, oneshot::Sender<Vec<u64>>),
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
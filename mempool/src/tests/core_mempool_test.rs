@@ -66,6 +66,24 @@ fn test_transaction_ordering_only_seqnos() {
     }
 }
 
+#[test]
+fn test_get_gas_prices() {
+    let (mut mempool, _) = setup_mempool();
+    add_txns_to_mempool(
+        &mut mempool,
+        vec![
+            TestTransaction::new(0, 0, 3),
+            TestTransaction::new(1, 0, 5),
+            TestTransaction::new(2, 0, 1),
+            // not ready: sequence number 0 of this account is missing
+            TestTransaction::new(3, 1, 10),
+        ],
+    );
+
+    assert_eq!(mempool.get_gas_prices(10), vec![5, 3, 1]);
+    assert_eq!(mempool.get_gas_prices(2), vec![5, 3]);
+}
+
 #[test]
 fn test_transaction_ordering_only_crsns() {
     let (mut mempool, mut consensus) = setup_mempool();
@@ -0,0 +1,273 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for the `0x1::Coin` module: registering coin stores, transferring coins and reading
+//! balances.
+
+use crate::{
+    move_types::{
+        account_address::AccountAddress,
+        ident_str,
+        identifier::IdentStr,
+        language_storage::{ModuleId, TypeTag},
+    },
+    rest_client::{aptos_api_types::U64, Client, Response, RestError, Transaction},
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{ScriptFunction, TransactionPayload},
+        utility_coin::TEST_COIN_TYPE,
+        LocalAccount,
+    },
+};
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+const BALANCE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The `0x1::Coin::Coin<CoinType>` held in a coin store
+#[derive(Clone, Debug, Deserialize)]
+pub struct Coin {
+    pub value: U64,
+}
+
+/// The event handles of a resource, only their counters are decoded
+#[derive(Clone, Debug, Deserialize)]
+pub struct EventHandle {
+    pub counter: U64,
+}
+
+/// The `0x1::Coin::CoinStore<CoinType>` resource of an account
+#[derive(Clone, Debug, Deserialize)]
+pub struct CoinStore {
+    pub coin: Coin,
+    pub deposit_events: EventHandle,
+    pub withdraw_events: EventHandle,
+}
+
+/// The `0x1::Coin::CoinInfo<CoinType>` resource, stored on the account of the coin's creator
+#[derive(Clone, Debug, Deserialize)]
+pub struct CoinInfo {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: U64,
+    pub supply: MoveOption<U64>,
+}
+
+/// A Move `0x1::Option::Option<T>`, which the API represents as a vector of up to one element
+#[derive(Clone, Debug, Deserialize)]
+pub struct MoveOption<T> {
+    pub vec: Vec<T>,
+}
+
+impl<T> MoveOption<T> {
+    pub fn into_option(mut self) -> Option<T> {
+        self.vec.pop()
+    }
+}
+
+/// Builds, signs and submits `0x1::Coin` transactions, and reads coin resources
+pub struct CoinClient {
+    rest_client: Client,
+    transaction_factory: TransactionFactory,
+}
+
+impl CoinClient {
+    pub fn new(rest_client: Client, transaction_factory: TransactionFactory) -> Self {
+        Self {
+            rest_client,
+            transaction_factory,
+        }
+    }
+
+    /// Publishes a `CoinStore<coin_type>` on `account`, required to receive `coin_type`
+    pub async fn register(
+        &self,
+        account: &mut LocalAccount,
+        coin_type: TypeTag,
+    ) -> Result<Transaction> {
+        self.submit(account, register_payload(coin_type)).await
+    }
+
+    /// Transfers `amount` of the test coin from `from` to `to`
+    pub async fn transfer(
+        &self,
+        from: &mut LocalAccount,
+        to: AccountAddress,
+        amount: u64,
+    ) -> Result<Transaction> {
+        self.transfer_coin(from, to, amount, TEST_COIN_TYPE.clone())
+            .await
+    }
+
+    /// Transfers `amount` of `coin_type` from `from` to `to`
+    pub async fn transfer_coin(
+        &self,
+        from: &mut LocalAccount,
+        to: AccountAddress,
+        amount: u64,
+        coin_type: TypeTag,
+    ) -> Result<Transaction> {
+        self.submit(from, transfer_payload(to, amount, coin_type))
+            .await
+    }
+
+    /// Returns the `CoinStore<coin_type>` of `address`, or `None` if it isn't registered
+    pub async fn get_coin_store(
+        &self,
+        address: AccountAddress,
+        coin_type: &TypeTag,
+    ) -> Result<Option<CoinStore>> {
+        let resource_type = format!("0x1::Coin::CoinStore<{}>", coin_type);
+        let resource = self
+            .rest_client
+            .get_account_resource(address, &resource_type)
+            .await?
+            .into_inner();
+        resource
+            .map(|resource| serde_json::from_value(resource.data))
+            .transpose()
+            .map_err(|err| anyhow!("deserialize {} failed: {}", resource_type, err))
+    }
+
+    /// Returns the balance of `coin_type` held by `address`
+    pub async fn get_balance(&self, address: AccountAddress, coin_type: &TypeTag) -> Result<u64> {
+        let coin_store = self
+            .get_coin_store(address, coin_type)
+            .await?
+            .ok_or_else(|| {
+                anyhow!(
+                    "account {} is not registered for coin {}",
+                    address,
+                    coin_type
+                )
+            })?;
+        Ok(*coin_store.coin.value.inner())
+    }
+
+    /// Returns the `CoinInfo<coin_type>` from the account which defines `coin_type`
+    pub async fn get_coin_info(&self, coin_type: &TypeTag) -> Result<CoinInfo> {
+        let creator = match coin_type {
+            TypeTag::Struct(struct_tag) => struct_tag.address,
+            _ => return Err(anyhow!("{} is not a coin type", coin_type)),
+        };
+        self.rest_client
+            .get_resource(creator, &format!("0x1::Coin::CoinInfo<{}>", coin_type))
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Polls the balance of `address` until it's at least `minimum`, failing after `timeout`
+    ///
+    /// An account which isn't registered for `coin_type` yet is treated as having no balance, any
+    /// other error fails the wait.
+    pub async fn wait_for_balance(
+        &self,
+        address: AccountAddress,
+        coin_type: &TypeTag,
+        minimum: u64,
+        timeout: Duration,
+    ) -> Result<u64> {
+        let start = Instant::now();
+        loop {
+            let balance = match self.get_coin_store(address, coin_type).await {
+                Ok(Some(coin_store)) => *coin_store.coin.value.inner(),
+                Ok(None) => 0,
+                Err(err) if is_not_found(&err) => 0,
+                Err(err) => return Err(err),
+            };
+            if balance >= minimum {
+                return Ok(balance);
+            }
+            if start.elapsed() > timeout {
+                return Err(anyhow!(
+                    "balance of {} in {} is {}, still below {} after {} seconds",
+                    coin_type,
+                    address,
+                    balance,
+                    minimum,
+                    timeout.as_secs()
+                ));
+            }
+            tokio::time::sleep(BALANCE_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn submit(
+        &self,
+        account: &mut LocalAccount,
+        payload: TransactionPayload,
+    ) -> Result<Transaction> {
+        let txn = account.sign_with_transaction_builder(self.transaction_factory.payload(payload));
+        self.rest_client
+            .submit_and_wait(&txn)
+            .await
+            .map(Response::into_inner)
+    }
+}
+
+fn coin_function(function: &IdentStr, coin_type: TypeTag, args: Vec<Vec<u8>>) -> ScriptFunction {
+    ScriptFunction::new(
+        ModuleId::new(AccountAddress::ONE, ident_str!("Coin").to_owned()),
+        function.to_owned(),
+        vec![coin_type],
+        args,
+    )
+}
+
+/// Payload of `0x1::Coin::register<coin_type>`
+pub fn register_payload(coin_type: TypeTag) -> TransactionPayload {
+    TransactionPayload::ScriptFunction(coin_function(ident_str!("register"), coin_type, vec![]))
+}
+
+/// Payload of `0x1::Coin::transfer<coin_type>`
+pub fn transfer_payload(to: AccountAddress, amount: u64, coin_type: TypeTag) -> TransactionPayload {
+    TransactionPayload::ScriptFunction(coin_function(
+        ident_str!("transfer"),
+        coin_type,
+        vec![bcs::to_bytes(&to).unwrap(), bcs::to_bytes(&amount).unwrap()],
+    ))
+}
+
+/// Whether `err` is the API replying that the requested resource doesn't exist
+fn is_not_found(err: &anyhow::Error) -> bool {
+    matches!(err.downcast_ref::<RestError>(), Some(err) if err.code == 404)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script_function(payload: TransactionPayload) -> ScriptFunction {
+        match payload {
+            TransactionPayload::ScriptFunction(script_function) => script_function,
+            _ => panic!("expected a script function payload"),
+        }
+    }
+
+    #[test]
+    fn test_register_payload() {
+        let register = script_function(register_payload(TEST_COIN_TYPE.clone()));
+        assert_eq!(register.module().address(), &AccountAddress::ONE);
+        assert_eq!(register.module().name().as_str(), "Coin");
+        assert_eq!(register.function().as_str(), "register");
+        assert_eq!(register.ty_args(), &[TEST_COIN_TYPE.clone()]);
+        assert!(register.args().is_empty());
+    }
+
+    #[test]
+    fn test_transfer_payload() {
+        let to = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let transfer = script_function(transfer_payload(to, 100, TEST_COIN_TYPE.clone()));
+        assert_eq!(transfer.module().address(), &AccountAddress::ONE);
+        assert_eq!(transfer.module().name().as_str(), "Coin");
+        assert_eq!(transfer.function().as_str(), "transfer");
+        assert_eq!(transfer.ty_args(), &[TEST_COIN_TYPE.clone()]);
+        assert_eq!(transfer.args().len(), 2);
+        assert_eq!(
+            bcs::from_bytes::<AccountAddress>(&transfer.args()[0]).unwrap(),
+            to
+        );
+        assert_eq!(bcs::from_bytes::<u64>(&transfer.args()[1]).unwrap(), 100);
+    }
+}
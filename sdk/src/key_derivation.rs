@@ -0,0 +1,219 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Derivation of account keys from a BIP-39 mnemonic, so that any number of account keys can be
+//! backed up as a single human-recordable phrase.
+//!
+//! The keys are derived from the seed of the mnemonic with [SLIP-0010] for Ed25519, which only
+//! supports hardened indexes. The default path of an account is `m/44'/637'/{index}'/0'/0'`, 637
+//! being the SLIP-0044 coin type of Aptos.
+//!
+//! [SLIP-0010]: https://github.com/satoshilabs/slips/blob/master/slip-0010.md
+
+use crate::crypto::ed25519::Ed25519PrivateKey;
+use anyhow::{bail, ensure, format_err, Result};
+use bip39::{Language, Mnemonic, Seed};
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha512;
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+/// The SLIP-0044 coin type of Aptos
+pub const APTOS_COIN_TYPE: u32 = 637;
+
+const BIP44_PURPOSE: u32 = 44;
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// A path of hardened indexes, e.g. `m/44'/637'/0'/0'/0'`
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DerivationPath {
+    /// The indexes, without the hardened offset
+    indexes: Vec<u32>,
+}
+
+impl DerivationPath {
+    pub fn new(indexes: Vec<u32>) -> Result<Self> {
+        for index in &indexes {
+            ensure!(
+                *index < HARDENED_OFFSET,
+                "Index {} is too large to be hardened",
+                index
+            );
+        }
+        Ok(Self { indexes })
+    }
+
+    /// The path of the account `index`: `m/44'/637'/{index}'/0'/0'`
+    pub fn aptos(index: u32) -> Result<Self> {
+        Self::new(vec![BIP44_PURPOSE, APTOS_COIN_TYPE, index, 0, 0])
+    }
+
+    pub fn indexes(&self) -> &[u32] {
+        &self.indexes
+    }
+}
+
+impl Default for DerivationPath {
+    fn default() -> Self {
+        Self::aptos(0).expect("Account 0 must have a path")
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut segments = s.trim().split('/');
+        if segments.next() != Some("m") {
+            bail!("Derivation path {} must start with 'm'", s);
+        }
+        let indexes = segments
+            .map(|segment| {
+                let index = segment
+                    .strip_suffix('\'')
+                    .or_else(|| segment.strip_suffix('h'))
+                    .ok_or_else(|| {
+                        format_err!(
+                            "Index {} of derivation path {} must be hardened, e.g. {}'",
+                            segment,
+                            s,
+                            segment
+                        )
+                    })?;
+                Ok(index.parse::<u32>()?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::new(indexes)
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.indexes {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates a new English mnemonic of `word_count` words: 12, 15, 18, 21 or 24
+pub fn generate_mnemonic<R>(rng: &mut R, word_count: usize) -> Result<String>
+where
+    R: ::rand_core::RngCore + ::rand_core::CryptoRng,
+{
+    ensure!(
+        [12, 15, 18, 21, 24].contains(&word_count),
+        "A mnemonic must have 12, 15, 18, 21 or 24 words, not {}",
+        word_count
+    );
+    // Every 3 words encode 4 bytes of entropy, along with a bit of checksum
+    let mut entropy = vec![0u8; word_count / 3 * 4];
+    rng.fill_bytes(&mut entropy);
+    let mnemonic = Mnemonic::from_entropy(&entropy, Language::English)?;
+    Ok(mnemonic.into_phrase())
+}
+
+/// Returns the seed of an English `mnemonic`, whose checksum is checked, with an optional
+/// `passphrase`
+pub fn mnemonic_to_seed(mnemonic: &str, passphrase: &str) -> Result<Vec<u8>> {
+    let mnemonic = Mnemonic::from_phrase(mnemonic.trim(), Language::English)?;
+    Ok(Seed::new(&mnemonic, passphrase).as_bytes().to_vec())
+}
+
+/// Derives the private key at `path` from `seed` with SLIP-0010
+pub fn derive_private_key(seed: &[u8], path: &DerivationPath) -> Result<Ed25519PrivateKey> {
+    let (mut key, mut chain_code) = split_hmac(ED25519_SEED_KEY, &[seed])?;
+    for index in path.indexes() {
+        let hardened_index = (index | HARDENED_OFFSET).to_be_bytes();
+        let (child_key, child_chain_code) =
+            split_hmac(&chain_code, &[&[0u8], &key, &hardened_index])?;
+        key = child_key;
+        chain_code = child_chain_code;
+    }
+    Ed25519PrivateKey::try_from(&key[..]).map_err(|err| format_err!("Invalid key: {}", err))
+}
+
+/// Derives the private key at `path` from an English `mnemonic` without passphrase
+pub fn derive_private_key_from_mnemonic(
+    mnemonic: &str,
+    path: &DerivationPath,
+) -> Result<Ed25519PrivateKey> {
+    derive_private_key(&mnemonic_to_seed(mnemonic, "")?, path)
+}
+
+/// Returns the halves of the HMAC-SHA512 of `data` with `key`: the key and the chain code
+fn split_hmac(key: &[u8], data: &[&[u8]]) -> Result<([u8; 32], [u8; 32])> {
+    let mut mac = Hmac::<Sha512>::new_varkey(key).map_err(|err| format_err!("{}", err))?;
+    for bytes in data {
+        mac.update(bytes);
+    }
+    let output = mac.finalize().into_bytes();
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    Ok((left, right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::ValidCryptoMaterial;
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_slip10_vector() {
+        // Test vector 1 for ed25519 of SLIP-0010
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let vectors = [
+            (
+                "m",
+                "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+            ),
+            (
+                "m/0'",
+                "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+            ),
+            (
+                "m/0'/1'/2'/2'/1000000000'",
+                "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+            ),
+        ];
+        for (path, expected_key) in vectors {
+            let key = derive_private_key(&seed, &path.parse().unwrap()).unwrap();
+            assert_eq!(hex::encode(key.to_bytes()), expected_key);
+        }
+    }
+
+    #[test]
+    fn test_derive_from_mnemonic() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let key = derive_private_key_from_mnemonic(mnemonic, &DerivationPath::default()).unwrap();
+        assert_eq!(
+            hex::encode(key.to_bytes()),
+            "cc92c0eaf80206d817f150e21917f797e49cf644a33ac514de3c316baa2f1bf5"
+        );
+
+        // the checksum is checked
+        let invalid = mnemonic.replace("about", "abandon");
+        assert!(derive_private_key_from_mnemonic(&invalid, &DerivationPath::default()).is_err());
+    }
+
+    #[test]
+    fn test_generate_mnemonic() {
+        let mnemonic = generate_mnemonic(&mut OsRng, 24).unwrap();
+        assert_eq!(mnemonic.split_whitespace().count(), 24);
+        assert!(mnemonic_to_seed(&mnemonic, "").is_ok());
+        assert!(generate_mnemonic(&mut OsRng, 13).is_err());
+    }
+
+    #[test]
+    fn test_derivation_path() {
+        let path = DerivationPath::aptos(3).unwrap();
+        assert_eq!(path.to_string(), "m/44'/637'/3'/0'/0'");
+        assert_eq!(path.to_string().parse::<DerivationPath>().unwrap(), path);
+        assert!("m/44'/637'/0'/0/0".parse::<DerivationPath>().is_err());
+        assert!("44'/637'".parse::<DerivationPath>().is_err());
+    }
+}
@@ -7,7 +7,11 @@
 //!
 //! This SDK provides all the necessary components for building on top of the Aptos Blockchain. Some of the important modules are:
 //!
+//! * `coin_client` - Helpers for transferring coins and reading balances
 //! * `crypto` - Types used for signing and verifying
+//! * `key_derivation` - Derivation of account keys from a BIP-39 mnemonic
+//! * `rest_client` - Client for the Aptos REST API
+//! * `token_client` - Helpers for creating, transferring and reading tokens
 //! * `transaction_builder` - Includes helpers for constructing transactions
 //! * `types` - Includes types for Aptos on-chain data structures
 //!
@@ -17,10 +21,20 @@
 //! todo(davidiw) bring back example using rest
 //!
 
+pub mod coin_client;
+
 pub mod crypto {
     pub use aptos_crypto::*;
 }
 
+pub mod key_derivation;
+
+pub mod rest_client {
+    pub use aptos_rest_client::*;
+}
+
+pub mod token_client;
+
 pub mod transaction_builder;
 
 pub mod types;
@@ -0,0 +1,472 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers for the `0x1::Token` and `0x1::TokenTransfers` modules: creating collections and
+//! tokens, transferring tokens and reading their metadata and balances.
+
+use crate::{
+    coin_client::MoveOption,
+    move_types::{
+        account_address::AccountAddress, ident_str, identifier::IdentStr,
+        language_storage::ModuleId,
+    },
+    rest_client::{
+        aptos_api_types::{Address, U128, U64},
+        Client, Response, Transaction,
+    },
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{ScriptFunction, TransactionPayload},
+        LocalAccount,
+    },
+};
+use anyhow::{anyhow, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The `0x1::Token::TokenId` identifying a token by its creator, collection and name
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TokenId {
+    pub creator: Address,
+    pub collection: String,
+    pub name: String,
+}
+
+impl TokenId {
+    pub fn new(creator: AccountAddress, collection: &str, name: &str) -> Self {
+        Self {
+            creator: creator.into(),
+            collection: collection.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
+
+/// The `0x1::Token::Token` held in a token store
+#[derive(Clone, Debug, Deserialize)]
+pub struct Token {
+    pub id: TokenId,
+    pub value: U64,
+}
+
+/// The `0x1::Token::Collection` metadata of a collection
+#[derive(Clone, Debug, Deserialize)]
+pub struct Collection {
+    pub description: String,
+    pub name: String,
+    pub uri: String,
+    pub count: U64,
+    pub maximum: MoveOption<U64>,
+}
+
+/// The `0x1::Token::TokenData` metadata of a token
+#[derive(Clone, Debug, Deserialize)]
+pub struct TokenData {
+    pub collection: String,
+    pub description: String,
+    pub name: String,
+    pub maximum: MoveOption<U64>,
+    pub supply: MoveOption<U64>,
+    pub uri: String,
+}
+
+/// A Move `0x1::Table::Table`, only its handle is needed to look up items
+#[derive(Clone, Debug, Deserialize)]
+struct Table {
+    handle: U128,
+}
+
+/// The tables of the `0x1::Token::Collections` resource of a creator
+#[derive(Clone, Debug, Deserialize)]
+struct Collections {
+    collections: Table,
+    token_data: Table,
+}
+
+/// The tables of the `0x1::Token::TokenStore` resource of an owner
+#[derive(Clone, Debug, Deserialize)]
+struct TokenStore {
+    tokens: Table,
+}
+
+/// Builds, signs and submits `0x1::Token` and `0x1::TokenTransfers` transactions, and reads
+/// token resources
+pub struct TokenClient {
+    rest_client: Client,
+    transaction_factory: TransactionFactory,
+}
+
+impl TokenClient {
+    pub fn new(rest_client: Client, transaction_factory: TransactionFactory) -> Self {
+        Self {
+            rest_client,
+            transaction_factory,
+        }
+    }
+
+    /// Creates the collection `name` on `creator`, limited to `maximum` tokens if given
+    pub async fn create_collection(
+        &self,
+        creator: &mut LocalAccount,
+        name: &str,
+        description: &str,
+        uri: &str,
+        maximum: Option<u64>,
+    ) -> Result<Transaction> {
+        let payload = create_collection_payload(name, description, uri, maximum);
+        self.submit(creator, payload).await
+    }
+
+    /// Creates the token `name` in `creator`'s `collection` and mints `initial_balance` of it to
+    /// `creator`, the supply of the token is limited to `maximum` if given
+    pub async fn create_token(
+        &self,
+        creator: &mut LocalAccount,
+        collection: &str,
+        name: &str,
+        description: &str,
+        initial_balance: u64,
+        uri: &str,
+        maximum: Option<u64>,
+    ) -> Result<Transaction> {
+        let payload =
+            create_token_payload(collection, name, description, initial_balance, uri, maximum);
+        self.submit(creator, payload).await
+    }
+
+    /// Publishes an empty `TokenStore` on `account`
+    pub async fn initialize_token_store(&self, account: &mut LocalAccount) -> Result<Transaction> {
+        self.submit(
+            account,
+            token_payload(ident_str!("initialize_token_script"), vec![]),
+        )
+        .await
+    }
+
+    /// Offers `amount` of a token to `receiver`, who can then claim it with [`Self::claim_token`]
+    pub async fn offer_token(
+        &self,
+        sender: &mut LocalAccount,
+        receiver: AccountAddress,
+        token_id: &TokenId,
+        amount: u64,
+    ) -> Result<Transaction> {
+        let payload = token_transfers_payload(
+            ident_str!("offer_script"),
+            receiver,
+            token_id,
+            vec![bcs::to_bytes(&amount).unwrap()],
+        );
+        self.submit(sender, payload).await
+    }
+
+    /// Claims a token previously offered by `sender`
+    pub async fn claim_token(
+        &self,
+        receiver: &mut LocalAccount,
+        sender: AccountAddress,
+        token_id: &TokenId,
+    ) -> Result<Transaction> {
+        let payload = token_transfers_payload(ident_str!("claim_script"), sender, token_id, vec![]);
+        self.submit(receiver, payload).await
+    }
+
+    /// Cancels a pending offer of a token to `receiver`
+    pub async fn cancel_token_offer(
+        &self,
+        sender: &mut LocalAccount,
+        receiver: AccountAddress,
+        token_id: &TokenId,
+    ) -> Result<Transaction> {
+        let payload = token_transfers_payload(
+            ident_str!("cancel_offer_script"),
+            receiver,
+            token_id,
+            vec![],
+        );
+        self.submit(sender, payload).await
+    }
+
+    /// Transfers `amount` of a token in a single transaction signed by both accounts
+    pub async fn direct_transfer(
+        &self,
+        sender: &mut LocalAccount,
+        receiver: &LocalAccount,
+        token_id: &TokenId,
+        amount: u64,
+    ) -> Result<Transaction> {
+        let mut args = token_id_args(token_id);
+        args.push(bcs::to_bytes(&amount).unwrap());
+        let payload = token_payload(ident_str!("direct_transfer_script"), args);
+        let txn = sender.sign_multi_agent_with_transaction_builder(
+            vec![receiver],
+            self.transaction_factory.payload(payload),
+        );
+        self.rest_client
+            .submit_and_wait(&txn)
+            .await
+            .map(Response::into_inner)
+    }
+
+    /// Returns the metadata of `creator`'s collection `name`
+    pub async fn get_collection(&self, creator: AccountAddress, name: &str) -> Result<Collection> {
+        let collections = self.get_collections(creator).await?;
+        self.get_table_item(
+            &collections.collections,
+            "0x1::ASCII::String",
+            "0x1::Token::Collection",
+            name,
+        )
+        .await
+    }
+
+    /// Returns the metadata of a token
+    pub async fn get_token_data(&self, token_id: &TokenId) -> Result<TokenData> {
+        let collections = self.get_collections(token_id.creator.into()).await?;
+        self.get_table_item(
+            &collections.token_data,
+            "0x1::Token::TokenId",
+            "0x1::Token::TokenData",
+            token_id,
+        )
+        .await
+    }
+
+    /// Returns how much of a token `owner` holds
+    pub async fn get_token_balance(
+        &self,
+        owner: AccountAddress,
+        token_id: &TokenId,
+    ) -> Result<u64> {
+        let token_store: TokenStore = self
+            .rest_client
+            .get_resource(owner, "0x1::Token::TokenStore")
+            .await?
+            .into_inner();
+        let token: Token = self
+            .get_table_item(
+                &token_store.tokens,
+                "0x1::Token::TokenId",
+                "0x1::Token::Token",
+                token_id,
+            )
+            .await?;
+        Ok(*token.value.inner())
+    }
+
+    async fn get_collections(&self, creator: AccountAddress) -> Result<Collections> {
+        self.rest_client
+            .get_resource(creator, "0x1::Token::Collections")
+            .await
+            .map(Response::into_inner)
+    }
+
+    async fn get_table_item<K: Serialize, V: DeserializeOwned>(
+        &self,
+        table: &Table,
+        key_type: &str,
+        value_type: &str,
+        key: K,
+    ) -> Result<V> {
+        let value = self
+            .rest_client
+            .get_table_item(*table.handle.inner(), key_type, value_type, key)
+            .await?
+            .into_inner();
+        serde_json::from_value(value)
+            .map_err(|err| anyhow!("deserialize {} failed: {}", value_type, err))
+    }
+
+    async fn submit(
+        &self,
+        account: &mut LocalAccount,
+        payload: TransactionPayload,
+    ) -> Result<Transaction> {
+        let txn = account.sign_with_transaction_builder(self.transaction_factory.payload(payload));
+        self.rest_client
+            .submit_and_wait(&txn)
+            .await
+            .map(Response::into_inner)
+    }
+}
+
+fn script_function(
+    module: &IdentStr,
+    function: &IdentStr,
+    args: Vec<Vec<u8>>,
+) -> TransactionPayload {
+    TransactionPayload::ScriptFunction(ScriptFunction::new(
+        ModuleId::new(AccountAddress::ONE, module.to_owned()),
+        function.to_owned(),
+        vec![],
+        args,
+    ))
+}
+
+fn token_payload(function: &IdentStr, args: Vec<Vec<u8>>) -> TransactionPayload {
+    script_function(ident_str!("Token"), function, args)
+}
+
+/// The `creator`, `collection` and `name` arguments identifying a token in script functions
+fn token_id_args(token_id: &TokenId) -> Vec<Vec<u8>> {
+    let creator: AccountAddress = token_id.creator.into();
+    vec![
+        bcs::to_bytes(&creator).unwrap(),
+        bcs::to_bytes(token_id.collection.as_bytes()).unwrap(),
+        bcs::to_bytes(token_id.name.as_bytes()).unwrap(),
+    ]
+}
+
+/// Payload of a `0x1::TokenTransfers` script function on a token, with the other party first
+fn token_transfers_payload(
+    function: &IdentStr,
+    counterparty: AccountAddress,
+    token_id: &TokenId,
+    extra_args: Vec<Vec<u8>>,
+) -> TransactionPayload {
+    let mut args = vec![bcs::to_bytes(&counterparty).unwrap()];
+    args.extend(token_id_args(token_id));
+    args.extend(extra_args);
+    script_function(ident_str!("TokenTransfers"), function, args)
+}
+
+/// Payload of `0x1::Token::create_limited_collection_script` if `maximum` is given, or else of
+/// `0x1::Token::create_unlimited_collection_script`
+pub fn create_collection_payload(
+    name: &str,
+    description: &str,
+    uri: &str,
+    maximum: Option<u64>,
+) -> TransactionPayload {
+    let mut args = vec![
+        bcs::to_bytes(name.as_bytes()).unwrap(),
+        bcs::to_bytes(description.as_bytes()).unwrap(),
+        bcs::to_bytes(uri.as_bytes()).unwrap(),
+    ];
+    match maximum {
+        Some(maximum) => {
+            args.push(bcs::to_bytes(&maximum).unwrap());
+            token_payload(ident_str!("create_limited_collection_script"), args)
+        }
+        None => token_payload(ident_str!("create_unlimited_collection_script"), args),
+    }
+}
+
+/// Payload of `0x1::Token::create_limited_token_script` if `maximum` is given, or else of
+/// `0x1::Token::create_unlimited_token_script`, with the token's supply monitored
+pub fn create_token_payload(
+    collection: &str,
+    name: &str,
+    description: &str,
+    initial_balance: u64,
+    uri: &str,
+    maximum: Option<u64>,
+) -> TransactionPayload {
+    let mut args = vec![
+        bcs::to_bytes(collection.as_bytes()).unwrap(),
+        bcs::to_bytes(name.as_bytes()).unwrap(),
+        bcs::to_bytes(description.as_bytes()).unwrap(),
+        bcs::to_bytes(&true).unwrap(),
+        bcs::to_bytes(&initial_balance).unwrap(),
+    ];
+    let function = match maximum {
+        Some(maximum) => {
+            args.push(bcs::to_bytes(&maximum).unwrap());
+            ident_str!("create_limited_token_script")
+        }
+        None => ident_str!("create_unlimited_token_script"),
+    };
+    args.push(bcs::to_bytes(uri.as_bytes()).unwrap());
+    token_payload(function, args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_id_table_key() {
+        let token_id = TokenId::new(AccountAddress::ONE, "collection", "token");
+        assert_eq!(
+            serde_json::to_value(&token_id).unwrap(),
+            serde_json::json!({
+                "creator": "0x1",
+                "collection": "collection",
+                "name": "token",
+            })
+        );
+    }
+
+    #[test]
+    fn test_create_token_payload_arguments() {
+        let limited = create_token_payload("collection", "token", "description", 1, "uri", Some(2));
+        let unlimited = create_token_payload("collection", "token", "description", 1, "uri", None);
+        match (limited, unlimited) {
+            (
+                TransactionPayload::ScriptFunction(limited),
+                TransactionPayload::ScriptFunction(unlimited),
+            ) => {
+                assert_eq!(limited.function().as_str(), "create_limited_token_script");
+                assert_eq!(limited.args().len(), 7);
+                assert_eq!(
+                    unlimited.function().as_str(),
+                    "create_unlimited_token_script"
+                );
+                assert_eq!(unlimited.args().len(), 6);
+                assert_eq!(limited.args()[6], bcs::to_bytes("uri".as_bytes()).unwrap());
+            }
+            _ => panic!("expected script function payloads"),
+        }
+    }
+
+    #[test]
+    fn test_create_collection_payload_arguments() {
+        match create_collection_payload("collection", "description", "uri", Some(3)) {
+            TransactionPayload::ScriptFunction(limited) => {
+                assert_eq!(limited.module().name().as_str(), "Token");
+                assert_eq!(
+                    limited.function().as_str(),
+                    "create_limited_collection_script"
+                );
+                assert_eq!(
+                    limited.args(),
+                    &[
+                        bcs::to_bytes("collection".as_bytes()).unwrap(),
+                        bcs::to_bytes("description".as_bytes()).unwrap(),
+                        bcs::to_bytes("uri".as_bytes()).unwrap(),
+                        bcs::to_bytes(&3u64).unwrap(),
+                    ]
+                );
+            }
+            _ => panic!("expected a script function payload"),
+        }
+    }
+
+    #[test]
+    fn test_token_transfers_payload_arguments() {
+        let receiver = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let token_id = TokenId::new(AccountAddress::ONE, "collection", "token");
+        let payload = token_transfers_payload(
+            ident_str!("offer_script"),
+            receiver,
+            &token_id,
+            vec![bcs::to_bytes(&5u64).unwrap()],
+        );
+        match payload {
+            TransactionPayload::ScriptFunction(offer) => {
+                assert_eq!(offer.module().name().as_str(), "TokenTransfers");
+                assert_eq!(offer.function().as_str(), "offer_script");
+                assert_eq!(
+                    offer.args(),
+                    &[
+                        bcs::to_bytes(&receiver).unwrap(),
+                        bcs::to_bytes(&AccountAddress::ONE).unwrap(),
+                        bcs::to_bytes("collection".as_bytes()).unwrap(),
+                        bcs::to_bytes("token".as_bytes()).unwrap(),
+                        bcs::to_bytes(&5u64).unwrap(),
+                    ]
+                );
+            }
+            _ => panic!("expected a script function payload"),
+        }
+    }
+}
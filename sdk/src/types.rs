@@ -6,12 +6,14 @@ use crate::{
         ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
         traits::Uniform,
     },
+    key_derivation::{derive_private_key_from_mnemonic, DerivationPath},
     transaction_builder::TransactionBuilder,
     types::{
         account_address::AccountAddress,
         transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
     },
 };
+use anyhow::Result;
 
 pub use aptos_types::*;
 
@@ -44,6 +46,25 @@ impl LocalAccount {
         Self::new(address, key, 0)
     }
 
+    /// Recovers the account whose key is derived from a BIP-39 `mnemonic` at `path`
+    ///
+    /// The address is derived from the key, so the key must not have been rotated.
+    pub fn from_mnemonic(
+        mnemonic: &str,
+        path: &DerivationPath,
+        sequence_number: u64,
+    ) -> Result<Self> {
+        let key = AccountKey::from_mnemonic(mnemonic, path)?;
+        let address = key.authentication_key().derived_address();
+
+        Ok(Self::new(address, key, sequence_number))
+    }
+
+    /// Derives the account `index` from a BIP-39 `mnemonic`, at `m/44'/637'/{index}'/0'/0'`
+    pub fn derive(mnemonic: &str, index: u32) -> Result<Self> {
+        Self::from_mnemonic(mnemonic, &DerivationPath::aptos(index)?, 0)
+    }
+
     pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
         txn.sign(self.private_key(), self.public_key().clone())
             .expect("Signing a txn can't fail")
@@ -135,6 +156,11 @@ impl AccountKey {
         Self::from_private_key(private_key)
     }
 
+    /// Derives the key at `path` from a BIP-39 `mnemonic`
+    pub fn from_mnemonic(mnemonic: &str, path: &DerivationPath) -> Result<Self> {
+        derive_private_key_from_mnemonic(mnemonic, path).map(Self::from_private_key)
+    }
+
     pub fn from_private_key(private_key: Ed25519PrivateKey) -> Self {
         let public_key = Ed25519PublicKey::from(&private_key);
         let authentication_key = AuthenticationKey::ed25519(&public_key);
@@ -94,6 +94,11 @@ pub fn save_transactions(
     for (idx, txn) in txns.iter().enumerate() {
         transaction_store.put_transaction(first_version + idx as Version, txn, &mut cs)?;
     }
+    transaction_store.put_block_index(
+        first_version,
+        txns.iter().zip(events.iter().map(Vec::as_slice)),
+        &mut cs,
+    )?;
     ledger_store.put_transaction_infos(first_version, txn_infos, &mut cs)?;
     event_store.put_events_multiple_versions(first_version, events, &mut cs)?;
 
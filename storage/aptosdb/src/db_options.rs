@@ -14,6 +14,9 @@ const VERSION_SIZE: usize = std::mem::size_of::<Version>();
 pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
     vec![
         /* empty cf */ DEFAULT_COLUMN_FAMILY_NAME,
+        BLOCK_BY_VERSION_CF_NAME,
+        BLOCK_HEIGHT_BY_HASH_CF_NAME,
+        DB_METADATA_CF_NAME,
         EPOCH_BY_VERSION_CF_NAME,
         EVENT_ACCUMULATOR_CF_NAME,
         EVENT_BY_KEY_CF_NAME,
@@ -28,6 +31,8 @@ pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
         TRANSACTION_BY_ACCOUNT_CF_NAME,
         TRANSACTION_BY_HASH_CF_NAME,
         TRANSACTION_INFO_CF_NAME,
+        VERSION_BY_BLOCK_HEIGHT_CF_NAME,
+        VERSION_BY_STATE_KEY_CF_NAME,
         WRITE_SET_CF_NAME,
     ]
 }
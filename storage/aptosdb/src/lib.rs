@@ -52,7 +52,7 @@ use crate::{
     system_store::SystemStore,
     transaction_store::TransactionStore,
 };
-use anyhow::{ensure, Result};
+use anyhow::{ensure, format_err, Result};
 use aptos_config::config::{RocksdbConfig, StoragePrunerConfig, NO_OP_STORAGE_PRUNER_CONFIG};
 use aptos_crypto::hash::{HashValue, SPARSE_MERKLE_PLACEHOLDER_HASH};
 use aptos_infallible::Mutex;
@@ -79,7 +79,7 @@ use aptos_types::{
         TransactionOutput, TransactionOutputListWithProof, TransactionToCommit,
         TransactionWithProof, Version,
     },
-    write_set::WriteSet,
+    write_set::{WriteOp, WriteSet},
 };
 use itertools::zip_eq;
 use once_cell::sync::Lazy;
@@ -103,6 +103,9 @@ pub const STATE_MERKLE_DB_NAME: &str = "state_merkle_db";
 
 const MAX_LIMIT: u64 = 5000;
 
+// The number of write sets indexed per batch when backfilling the state key index.
+const STATE_KEY_INDEX_BACKFILL_BATCH_SIZE: u64 = 10000;
+
 // TODO: Either implement an iteration API to allow a very old client to loop through a long history
 // or guarantee that there is always a recent enough waypoint and client knows to boot from there.
 const MAX_NUM_EPOCH_ENDING_LEDGER_INFO: usize = 100;
@@ -343,6 +346,9 @@ impl AptosDB {
         };
 
         let ret = Self::new_with_dbs(ledger_db, state_merkle_db, storage_pruner_config);
+        if !readonly {
+            ret.backfill_state_key_index()?;
+        }
         info!(
             ledger_db_path = ledger_db_path,
             state_merkle_db_path = state_merkle_db_path,
@@ -386,6 +392,13 @@ impl AptosDB {
         ))
     }
 
+    /// Catches up a DB opened with `open_as_secondary` with the writes of its primary, which is
+    /// still being written to by a running node.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.ledger_db.try_catch_up_with_primary()?;
+        self.state_merkle_db.try_catch_up_with_primary()
+    }
+
     /// This opens db in non-readonly mode, without the pruner.
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn new_for_test<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
@@ -398,6 +411,50 @@ impl AptosDB {
         .expect("Unable to open AptosDB")
     }
 
+    /// Indexes the state keys of the write sets committed before the state key index existed,
+    /// newest first, so that the index always covers the versions from its recorded start on and
+    /// an interrupted backfill resumes where it stopped.
+    fn backfill_state_key_index(&self) -> Result<()> {
+        let mut end = match self.transaction_store.get_state_key_index_start_version()? {
+            Some(start_version) => start_version,
+            None => {
+                // Everything committed from now on is indexed as part of the commit.
+                let next_version = self
+                    .ledger_store
+                    .get_latest_transaction_info_option()?
+                    .map_or(0, |(version, _)| version + 1);
+                let mut batch = SchemaBatch::new();
+                self.transaction_store
+                    .put_state_key_index_start_version(next_version, &mut batch)?;
+                self.ledger_db.write_schemas(batch)?;
+                next_version
+            }
+        };
+        let first_version = match self.transaction_store.get_first_write_set_version()? {
+            Some(first_version) if first_version < end => first_version,
+            _ => return Ok(()),
+        };
+
+        info!(
+            first_version = first_version,
+            end_version = end,
+            "Backfilling the state key index."
+        );
+        while end > first_version {
+            let begin = std::cmp::max(
+                first_version,
+                end.saturating_sub(STATE_KEY_INDEX_BACKFILL_BATCH_SIZE),
+            );
+            let mut batch = SchemaBatch::new();
+            self.transaction_store
+                .backfill_state_key_index(begin, end, &mut batch)?;
+            self.ledger_db.write_schemas(batch)?;
+            end = begin;
+        }
+        info!("Backfilled the state key index.");
+        Ok(())
+    }
+
     /// This force the db to update rocksdb properties immediately.
     pub fn update_rocksdb_properties(&self) -> Result<()> {
         update_rocksdb_properties(&self.ledger_db, &self.state_merkle_db)
@@ -688,6 +745,11 @@ impl AptosDB {
                         .put_write_set(ver, txn_to_commit.write_set(), cs)
                 },
             )?;
+            self.transaction_store.put_block_index(
+                first_version,
+                txns_to_commit.iter().map(|t| (t.transaction(), t.events())),
+                cs,
+            )?;
             // Transaction accumulator updates. Get result root hash.
             let txn_infos: Vec<_> = txns_to_commit
                 .iter()
@@ -1139,6 +1201,130 @@ impl DbReader for AptosDB {
         })
     }
 
+    fn get_block_height_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<Option<u64>> {
+        gauged_api("get_block_height_by_version", || {
+            if version > ledger_version {
+                return Ok(None);
+            }
+            Ok(self
+                .transaction_store
+                .get_block_by_version(version)?
+                .map(|(block_height, _first_version)| block_height))
+        })
+    }
+
+    fn get_block_version_range_by_height(
+        &self,
+        block_height: u64,
+        ledger_version: Version,
+    ) -> Result<Option<(Version, Version)>> {
+        gauged_api("get_block_version_range_by_height", || {
+            let first_version = match self
+                .transaction_store
+                .get_block_first_version(block_height)?
+            {
+                Some(version) if version <= ledger_version => version,
+                _ => return Ok(None),
+            };
+            let last_version = match self
+                .transaction_store
+                .get_block_first_version(block_height + 1)?
+            {
+                Some(next_first_version) if next_first_version <= ledger_version => {
+                    next_first_version - 1
+                }
+                _ => ledger_version,
+            };
+            Ok(Some((first_version, last_version)))
+        })
+    }
+
+    fn get_block_height_by_hash(
+        &self,
+        block_hash: HashValue,
+        ledger_version: Version,
+    ) -> Result<Option<u64>> {
+        gauged_api("get_block_height_by_hash", || {
+            let block_height = match self
+                .transaction_store
+                .get_block_height_by_hash(block_hash)?
+            {
+                Some(block_height) => block_height,
+                None => return Ok(None),
+            };
+            match self
+                .transaction_store
+                .get_block_first_version(block_height)?
+            {
+                Some(first_version) if first_version <= ledger_version => Ok(Some(block_height)),
+                _ => Ok(None),
+            }
+        })
+    }
+
+    fn get_first_state_key_history_version(&self) -> Result<Option<Version>> {
+        gauged_api("get_first_state_key_history_version", || {
+            let index_start_version = self
+                .transaction_store
+                .get_state_key_index_start_version()?
+                .ok_or_else(|| {
+                    format_err!("The state key index hasn't been started, open the DB for writing.")
+                })?;
+            let first_write_set_version = match self.get_first_write_set_version()? {
+                Some(version) => version,
+                None => return Ok(None),
+            };
+            // The value before a change is read from the state at the version before it.
+            let first_state_version = match self.pruner.as_ref().map(|pruner| {
+                pruner.get_min_readable_version_by_pruner_index(PrunerIndex::StateStorePrunerIndex)
+            }) {
+                Some(version) if version > 0 => version + 1,
+                _ => 0,
+            };
+            Ok(Some(
+                index_start_version
+                    .max(first_write_set_version)
+                    .max(first_state_version),
+            ))
+        })
+    }
+
+    fn get_state_key_history(
+        &self,
+        state_key: &StateKey,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, WriteOp)>> {
+        gauged_api("get_state_key_history", || {
+            error_if_too_many_requested(limit, MAX_LIMIT)?;
+
+            self.transaction_store
+                .get_state_key_versions(state_key, start_version, limit, ledger_version)?
+                .into_iter()
+                .map(|version| {
+                    let write_set = self.transaction_store.get_write_set(version)?;
+                    let write_op = write_set
+                        .iter()
+                        .find(|(key, _)| key == state_key)
+                        .map(|(_, write_op)| write_op.clone())
+                        .ok_or_else(|| {
+                            format_err!(
+                                "State key {:?} not found in the write set at version {}",
+                                state_key,
+                                version
+                            )
+                        })?;
+                    Ok((version, write_op))
+                })
+                .collect()
+        })
+    }
+
     fn get_last_version_before_timestamp(
         &self,
         timestamp: u64,
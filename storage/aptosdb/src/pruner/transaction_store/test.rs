@@ -78,11 +78,25 @@ fn verify_write_set_pruner(write_sets: Vec<WriteSet>) {
         // ensure that all transaction up to i * 2 has been pruned
         for j in 0..i {
             assert!(transaction_store.get_write_set(j as u64).is_err());
+            for (state_key, _) in write_sets.get(j).unwrap() {
+                assert!(transaction_store
+                    .get_state_key_versions(state_key, j as u64, 1, j as u64)
+                    .unwrap()
+                    .is_empty());
+            }
         }
         // ensure all other are valid in DB
         for j in i..num_write_sets {
             let write_set_from_db = transaction_store.get_write_set(j as u64).unwrap();
             assert_eq!(write_set_from_db, *write_sets.get(j).unwrap());
+            for (state_key, _) in write_sets.get(j).unwrap() {
+                assert_eq!(
+                    transaction_store
+                        .get_state_key_versions(state_key, j as u64, 1, j as u64)
+                        .unwrap(),
+                    vec![j as u64]
+                );
+            }
         }
     }
 }
@@ -25,6 +25,8 @@ impl DBSubPruner for TransactionStorePruner {
             .prune_transaction_by_hash(&candidate_transactions, db_batch)?;
         self.transaction_store
             .prune_transaction_by_account(&candidate_transactions, db_batch)?;
+        self.transaction_store
+            .prune_block_height_by_hash(&candidate_transactions, db_batch)?;
         self.transaction_store.prune_transaction_schema(
             min_readable_version,
             target_version,
@@ -40,6 +42,8 @@ impl DBSubPruner for TransactionStorePruner {
             target_version,
             db_batch,
         )?;
+        self.transaction_store
+            .prune_block_index(target_version, db_batch)?;
         Ok(())
     }
 }
@@ -15,6 +15,11 @@ impl DBSubPruner for WriteSetPruner {
         min_readable_version: u64,
         target_version: u64,
     ) -> anyhow::Result<()> {
+        self.transaction_store.prune_state_key_index(
+            min_readable_version,
+            target_version,
+            db_batch,
+        )?;
         self.transaction_store
             .prune_write_set(min_readable_version, target_version, db_batch)?;
         Ok(())
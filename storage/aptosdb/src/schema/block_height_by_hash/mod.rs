@@ -0,0 +1,53 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an index via which the height of a block can
+//! be found by the block hash, i.e. the id carried on the `BlockMetadata` transaction starting the
+//! block. The genesis block isn't started by a `BlockMetadata` transaction, so it isn't indexed.
+//!
+//! ```text
+//! |<---key---->|<----value----->|
+//! | block_hash |  block_height  |
+//! ```
+
+use crate::schema::{ensure_slice_len_eq, BLOCK_HEIGHT_BY_HASH_CF_NAME};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use byteorder::{BigEndian, ReadBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(
+    BlockHeightByHashSchema,
+    HashValue,
+    u64, // block_height
+    BLOCK_HEIGHT_BY_HASH_CF_NAME
+);
+
+impl KeyCodec<BlockHeightByHashSchema> for HashValue {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_vec())
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        Ok(HashValue::from_slice(data)?)
+    }
+}
+
+impl ValueCodec<BlockHeightByHashSchema> for u64 {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
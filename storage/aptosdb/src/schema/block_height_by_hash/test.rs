@@ -0,0 +1,18 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        block_hash in any::<HashValue>(),
+        block_height in any::<u64>(),
+    ) {
+        assert_encode_decode::<BlockHeightByHashSchema>(&block_hash, &block_height);
+    }
+}
+
+test_no_panic_decoding!(BlockHeightByHashSchema);
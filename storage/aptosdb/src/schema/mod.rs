@@ -6,6 +6,9 @@
 //!
 //! All schemas are `pub(crate)` so not shown in rustdoc, refer to the source code to see details.
 
+pub(crate) mod block_by_version;
+pub(crate) mod block_height_by_hash;
+pub(crate) mod db_metadata;
 pub(crate) mod epoch_by_version;
 pub(crate) mod event;
 pub(crate) mod event_accumulator;
@@ -21,11 +24,16 @@ pub(crate) mod transaction_accumulator;
 pub(crate) mod transaction_by_account;
 pub(crate) mod transaction_by_hash;
 pub(crate) mod transaction_info;
+pub(crate) mod version_by_block_height;
+pub(crate) mod version_by_state_key;
 pub(crate) mod write_set;
 
 use anyhow::{ensure, Result};
 use schemadb::ColumnFamilyName;
 
+pub const BLOCK_BY_VERSION_CF_NAME: ColumnFamilyName = "block_by_version";
+pub const BLOCK_HEIGHT_BY_HASH_CF_NAME: ColumnFamilyName = "block_height_by_hash";
+pub const DB_METADATA_CF_NAME: ColumnFamilyName = "db_metadata";
 pub const EPOCH_BY_VERSION_CF_NAME: ColumnFamilyName = "epoch_by_version";
 pub const EVENT_ACCUMULATOR_CF_NAME: ColumnFamilyName = "event_accumulator";
 pub const EVENT_BY_KEY_CF_NAME: ColumnFamilyName = "event_by_key";
@@ -41,6 +49,8 @@ pub const TRANSACTION_ACCUMULATOR_CF_NAME: ColumnFamilyName = "transaction_accum
 pub const TRANSACTION_BY_ACCOUNT_CF_NAME: ColumnFamilyName = "transaction_by_account";
 pub const TRANSACTION_BY_HASH_CF_NAME: ColumnFamilyName = "transaction_by_hash";
 pub const TRANSACTION_INFO_CF_NAME: ColumnFamilyName = "transaction_info";
+pub const VERSION_BY_BLOCK_HEIGHT_CF_NAME: ColumnFamilyName = "version_by_block_height";
+pub const VERSION_BY_STATE_KEY_CF_NAME: ColumnFamilyName = "version_by_state_key";
 pub const WRITE_SET_CF_NAME: ColumnFamilyName = "write_set";
 
 fn ensure_slice_len_eq(data: &[u8], len: usize) -> Result<()> {
@@ -70,6 +80,9 @@ pub mod fuzzing {
     pub fn fuzz_decode(data: &[u8]) {
         #[allow(unused_must_use)]
         {
+            assert_no_panic_decoding::<super::block_by_version::BlockByVersionSchema>(data);
+            assert_no_panic_decoding::<super::block_height_by_hash::BlockHeightByHashSchema>(data);
+            assert_no_panic_decoding::<super::db_metadata::DbMetadataSchema>(data);
             assert_no_panic_decoding::<super::epoch_by_version::EpochByVersionSchema>(data);
             assert_no_panic_decoding::<super::event::EventSchema>(data);
             assert_no_panic_decoding::<super::event_accumulator::EventAccumulatorSchema>(data);
@@ -91,6 +104,10 @@ pub mod fuzzing {
             );
             assert_no_panic_decoding::<super::transaction_by_hash::TransactionByHashSchema>(data);
             assert_no_panic_decoding::<super::transaction_info::TransactionInfoSchema>(data);
+            assert_no_panic_decoding::<super::version_by_block_height::VersionByBlockHeightSchema>(
+                data,
+            );
+            assert_no_panic_decoding::<super::version_by_state_key::VersionByStateKeySchema>(data);
             assert_no_panic_decoding::<super::write_set::WriteSetSchema>(data);
         }
     }
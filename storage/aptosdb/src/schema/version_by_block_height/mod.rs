@@ -0,0 +1,57 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an index via which the first version of a
+//! block can be found by the block height. Block 0 starts with the genesis transaction and every
+//! following block starts with a `BlockMetadata` transaction, so the last version of a block is
+//! right before the first version of the next one.
+//!
+//! ```text
+//! |<----key----->|<-value->|
+//! | block_height | version |
+//! ```
+//!
+//! `block_height` is serialized in big endian so that records in RocksDB will be in order of their
+//! numeric value.
+
+use crate::schema::{ensure_slice_len_eq, VERSION_BY_BLOCK_HEIGHT_CF_NAME};
+use anyhow::Result;
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(
+    VersionByBlockHeightSchema,
+    u64, // block_height
+    Version,
+    VERSION_BY_BLOCK_HEIGHT_CF_NAME
+);
+
+impl KeyCodec<VersionByBlockHeightSchema> for u64 {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+impl ValueCodec<VersionByBlockHeightSchema> for Version {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Self>())?;
+        Ok(data.read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,18 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        block_height in any::<u64>(),
+        version in any::<Version>(),
+    ) {
+        assert_encode_decode::<VersionByBlockHeightSchema>(&block_height, &version);
+    }
+}
+
+test_no_panic_decoding!(VersionByBlockHeightSchema);
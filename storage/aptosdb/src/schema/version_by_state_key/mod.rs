@@ -0,0 +1,69 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an index via which the versions whose write
+//! sets touched a state key can be found. With the version one can resort to `WriteSetSchema` for
+//! the write op.
+//!
+//! ```text
+//! |<-----------key----------->|
+//! | state_key_hash | txn_ver |
+//! ```
+//!
+//! The state key is identified by its hash, so that the entries of one state key are never
+//! interleaved with the ones of another state key whose encoding it prefixes. `txn_ver` is
+//! serialized in big endian so that the entries of a state key are in order of version.
+
+use crate::schema::{ensure_slice_len_eq, VERSION_BY_STATE_KEY_CF_NAME};
+use anyhow::Result;
+use aptos_crypto::HashValue;
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(
+    VersionByStateKeySchema,
+    Key,
+    (),
+    VERSION_BY_STATE_KEY_CF_NAME
+);
+
+type Key = (HashValue, Version);
+
+impl KeyCodec<VersionByStateKeySchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref state_key_hash, version) = *self;
+
+        let mut encoded = state_key_hash.to_vec();
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, HashValue::LENGTH + size_of::<Version>())?;
+
+        let state_key_hash = HashValue::from_slice(&data[..HashValue::LENGTH])?;
+        let version = (&data[HashValue::LENGTH..]).read_u64::<BigEndian>()?;
+
+        Ok((state_key_hash, version))
+    }
+}
+
+impl ValueCodec<VersionByStateKeySchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -7,8 +7,15 @@ use crate::{
     change_set::ChangeSet,
     errors::AptosDbError,
     schema::{
-        transaction::TransactionSchema, transaction_by_account::TransactionByAccountSchema,
-        transaction_by_hash::TransactionByHashSchema, write_set::WriteSetSchema,
+        block_by_version::BlockByVersionSchema,
+        block_height_by_hash::BlockHeightByHashSchema,
+        db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
+        transaction::TransactionSchema,
+        transaction_by_account::TransactionByAccountSchema,
+        transaction_by_hash::TransactionByHashSchema,
+        version_by_block_height::VersionByBlockHeightSchema,
+        version_by_state_key::VersionByStateKeySchema,
+        write_set::WriteSetSchema,
     },
     transaction_accumulator::TransactionAccumulatorSchema,
     transaction_info::TransactionInfoSchema,
@@ -17,8 +24,10 @@ use anyhow::{ensure, format_err, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_types::{
     account_address::AccountAddress,
-    block_metadata::BlockMetadata,
+    block_metadata::{new_block_event_key, BlockMetadata},
+    contract_event::ContractEvent,
     proof::position::Position,
+    state_store::state_key::StateKey,
     transaction::{Transaction, Version},
     write_set::WriteSet,
 };
@@ -151,6 +160,81 @@ impl TransactionStore {
         Err(AptosDbError::NotFound(format!("BlockMetadata preceding version {}", version)).into())
     }
 
+    /// Gets the first version of the block at `block_height`.
+    pub fn get_block_first_version(&self, block_height: u64) -> Result<Option<Version>> {
+        self.db.get::<VersionByBlockHeightSchema>(&block_height)
+    }
+
+    /// Gets the height and the first version of the block that `version` is in.
+    pub fn get_block_by_version(&self, version: Version) -> Result<Option<(u64, Version)>> {
+        let mut iter = self
+            .db
+            .iter::<BlockByVersionSchema>(ReadOptions::default())?;
+        // Search for the start of the block, which is at or before `version`.
+        iter.seek_for_prev(&version)?;
+        Ok(iter
+            .next()
+            .transpose()?
+            .map(|(first_version, block_height)| (block_height, first_version)))
+    }
+
+    /// Gets the height of the block with hash `block_hash`, i.e. the id carried on the
+    /// `BlockMetadata` transaction starting it.
+    pub fn get_block_height_by_hash(&self, block_hash: HashValue) -> Result<Option<u64>> {
+        self.db.get::<BlockHeightByHashSchema>(&block_hash)
+    }
+
+    /// Indexes the blocks started by `transactions`, the first of which is at `first_version`.
+    /// Block 0 starts with the genesis transaction and each `BlockMetadata` transaction starts a
+    /// new block.
+    ///
+    /// The block height carries over from the block right before `first_version`. If that block
+    /// isn't indexed, e.g. the DB was bootstrapped from a state snapshot instead of synced from
+    /// genesis, the height is taken from the `NewBlockEvent` emitted by the `BlockMetadata`
+    /// transaction: genesis doesn't emit one, so block `n` emits the event with sequence number
+    /// `n - 1`.
+    pub fn put_block_index<'a>(
+        &self,
+        first_version: Version,
+        transactions: impl IntoIterator<Item = (&'a Transaction, &'a [ContractEvent])>,
+        cs: &mut ChangeSet,
+    ) -> Result<()> {
+        let mut next_block_height = if first_version == 0 {
+            Some(0)
+        } else {
+            self.get_block_by_version(first_version - 1)?
+                .map(|(block_height, _first_version)| block_height + 1)
+        };
+
+        for (version, (transaction, events)) in (first_version..).zip(transactions) {
+            if version != 0 && !matches!(transaction, Transaction::BlockMetadata(_)) {
+                continue;
+            }
+            let block_height = match next_block_height {
+                Some(block_height) => block_height,
+                None => {
+                    let new_block_event_key = new_block_event_key();
+                    match events.iter().find(|e| *e.key() == new_block_event_key) {
+                        Some(event) => event.sequence_number() + 1,
+                        // Transactions before the first block with a known height stay unindexed.
+                        None => continue,
+                    }
+                }
+            };
+            cs.batch
+                .put::<VersionByBlockHeightSchema>(&block_height, &version)?;
+            cs.batch
+                .put::<BlockByVersionSchema>(&version, &block_height)?;
+            if let Transaction::BlockMetadata(block_metadata) = transaction {
+                cs.batch
+                    .put::<BlockHeightByHashSchema>(&block_metadata.id(), &block_height)?;
+            }
+            next_block_height = Some(block_height + 1);
+        }
+
+        Ok(())
+    }
+
     /// Save signed transaction at `version`
     pub fn put_transaction(
         &self,
@@ -224,13 +308,84 @@ impl TransactionStore {
         iter.next().map(|res| res.map(|(v, _)| v)).transpose()
     }
 
-    /// Save executed transaction vm output given `version`
+    /// Gets up to `limit` versions in `[start_version, ledger_version]` whose write sets touched
+    /// `state_key`, in ascending order.
+    pub fn get_state_key_versions(
+        &self,
+        state_key: &StateKey,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let state_key_hash = state_key.hash();
+        let mut iter = self
+            .db
+            .iter::<VersionByStateKeySchema>(ReadOptions::default())?;
+        iter.seek(&(state_key_hash, start_version))?;
+
+        let mut versions = vec![];
+        for res in iter {
+            let ((key_hash, version), ()) = res?;
+            if key_hash != state_key_hash
+                || version > ledger_version
+                || versions.len() as u64 >= limit
+            {
+                break;
+            }
+            versions.push(version);
+        }
+        Ok(versions)
+    }
+
+    /// Gets the first version whose write set is in the state key index, or `None` if the index
+    /// hasn't been started on this DB yet.
+    pub fn get_state_key_index_start_version(&self) -> Result<Option<Version>> {
+        Ok(self
+            .db
+            .get::<DbMetadataSchema>(&DbMetadataKey::StateKeyIndexStartVersion)?
+            .map(|DbMetadataValue::Version(version)| version))
+    }
+
+    /// Records `version` as the first version whose write set is in the state key index.
+    pub fn put_state_key_index_start_version(
+        &self,
+        version: Version,
+        db_batch: &mut SchemaBatch,
+    ) -> Result<()> {
+        db_batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::StateKeyIndexStartVersion,
+            &DbMetadataValue::Version(version),
+        )
+    }
+
+    /// Indexes the state keys of the write sets in [begin, end), which were committed before the
+    /// index existed, and moves the start of the index to `begin`.
+    pub fn backfill_state_key_index(
+        &self,
+        begin: Version,
+        end: Version,
+        db_batch: &mut SchemaBatch,
+    ) -> Result<()> {
+        for (version, write_set) in (begin..end).zip(self.get_write_sets(begin, end)?) {
+            for (state_key, _write_op) in &write_set {
+                db_batch.put::<VersionByStateKeySchema>(&(state_key.hash(), version), &())?;
+            }
+        }
+        self.put_state_key_index_start_version(begin, db_batch)
+    }
+
+    /// Save executed transaction vm output given `version`, along with the index of the state
+    /// keys it touched.
     pub fn put_write_set(
         &self,
         version: Version,
         write_set: &WriteSet,
         cs: &mut ChangeSet,
     ) -> Result<()> {
+        for (state_key, _write_op) in write_set {
+            cs.batch
+                .put::<VersionByStateKeySchema>(&(state_key.hash(), version), &())?;
+        }
         cs.batch.put::<WriteSetSchema>(&version, write_set)
     }
 
@@ -246,6 +401,20 @@ impl TransactionStore {
         Ok(())
     }
 
+    /// Prune the block height by hash store given a list of transaction
+    pub fn prune_block_height_by_hash(
+        &self,
+        transactions: &[Transaction],
+        db_batch: &mut SchemaBatch,
+    ) -> anyhow::Result<()> {
+        for transaction in transactions {
+            if let Transaction::BlockMetadata(block_metadata) = transaction {
+                db_batch.delete::<BlockHeightByHashSchema>(&block_metadata.id())?;
+            }
+        }
+        Ok(())
+    }
+
     /// Prune the transaction by account store given a list of transaction
     pub fn prune_transaction_by_account(
         &self,
@@ -300,6 +469,42 @@ impl TransactionStore {
         Ok(())
     }
 
+    /// Prune the state key index of the write sets between a range of version in [begin, end)
+    pub fn prune_state_key_index(
+        &self,
+        begin: Version,
+        end: Version,
+        db_batch: &mut SchemaBatch,
+    ) -> anyhow::Result<()> {
+        for (version, write_set) in (begin..end).zip(self.get_write_sets(begin, end)?) {
+            for (state_key, _write_op) in &write_set {
+                db_batch.delete::<VersionByStateKeySchema>(&(state_key.hash(), version))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prune the block index of the blocks ending before `end`. The block `end` is in is kept, so
+    /// that the version range of the block stays known.
+    pub fn prune_block_index(&self, end: Version, db_batch: &mut SchemaBatch) -> Result<()> {
+        let keep_from = match self.get_block_by_version(end)? {
+            Some((_block_height, first_version)) => first_version,
+            None => return Ok(()),
+        };
+
+        let mut iter = self.db.iter::<BlockByVersionSchema>(Default::default())?;
+        iter.seek_to_first();
+        for res in iter {
+            let (first_version, block_height) = res?;
+            if first_version >= keep_from {
+                break;
+            }
+            db_batch.delete::<BlockByVersionSchema>(&first_version)?;
+            db_batch.delete::<VersionByBlockHeightSchema>(&block_height)?;
+        }
+        Ok(())
+    }
+
     /// Prune the transaction schema store between a range of version in [begin, end).
     pub fn prune_transaction_accumulator(
         &self,
@@ -6,10 +6,14 @@ use crate::AptosDB;
 use aptos_proptest_helpers::Index;
 use aptos_temppath::TempPath;
 use aptos_types::{
-    block_metadata::BlockMetadata,
+    account_config::NewBlockEvent,
+    block_metadata::{new_block_event_key, BlockMetadata},
+    contract_event::ContractEvent,
     proptest_types::{AccountInfoUniverse, SignatureCheckedTransactionGen},
     transaction::{SignedTransaction, Transaction},
+    write_set::{WriteOp, WriteSetMut},
 };
+use move_deps::move_core_types::{language_storage::TypeTag, move_resource::MoveStructType};
 use proptest::{collection::vec, prelude::*};
 use std::collections::BTreeMap;
 
@@ -166,6 +170,265 @@ proptest! {
         }
     }
 
+    #[test]
+    fn test_get_block_index(
+        txns in vec(
+            prop_oneof![
+                any::<BlockMetadata>().prop_map(Transaction::BlockMetadata),
+                any::<SignedTransaction>().prop_map(Transaction::UserTransaction),
+            ],
+            1..100,
+        ),
+        split in any::<Index>(),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+
+        // Index the transactions in two chunks, to make sure block heights carry over.
+        let split = split.index(txns.len());
+        for (first_version, chunk) in [(0, &txns[..split]), (split, &txns[split..])] {
+            let mut cs = ChangeSet::new();
+            store
+                .put_block_index(
+                    first_version as Version,
+                    chunk.iter().map(|txn| (txn, &[][..])),
+                    &mut cs,
+                )
+                .unwrap();
+            store.db.write_schemas(cs.batch).unwrap();
+        }
+
+        let mut block_height = 0;
+        let mut block_first_version = 0;
+        for (ver, txn) in txns.iter().enumerate() {
+            match txn {
+                Transaction::BlockMetadata(block_metadata) if ver > 0 => {
+                    block_height += 1;
+                    block_first_version = ver as Version;
+                    prop_assert_eq!(
+                        store.get_block_first_version(block_height).unwrap(),
+                        Some(block_first_version)
+                    );
+                    prop_assert_eq!(
+                        store.get_block_height_by_hash(block_metadata.id()).unwrap(),
+                        Some(block_height)
+                    );
+                }
+                _ => (),
+            }
+            prop_assert_eq!(
+                store.get_block_by_version(ver as Version).unwrap(),
+                Some((block_height, block_first_version))
+            );
+        }
+        prop_assert_eq!(store.get_block_first_version(0).unwrap(), Some(0));
+        prop_assert_eq!(store.get_block_first_version(block_height + 1).unwrap(), None);
+
+        // Pruning keeps the block that the target version is in.
+        let target_version = txns.len() as Version - 1;
+        let mut db_batch = SchemaBatch::new();
+        store.prune_block_index(target_version, &mut db_batch).unwrap();
+        store.db.write_schemas(db_batch).unwrap();
+        prop_assert_eq!(
+            store.get_block_by_version(target_version).unwrap(),
+            Some((block_height, block_first_version))
+        );
+        if block_height > 0 {
+            prop_assert_eq!(store.get_block_first_version(block_height - 1).unwrap(), None);
+        }
+
+        let mut db_batch = SchemaBatch::new();
+        store.prune_block_height_by_hash(&txns, &mut db_batch).unwrap();
+        store.db.write_schemas(db_batch).unwrap();
+        for txn in &txns {
+            if let Transaction::BlockMetadata(block_metadata) = txn {
+                prop_assert_eq!(store.get_block_height_by_hash(block_metadata.id()).unwrap(), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_put_block_index_without_genesis(
+        txns in vec(
+            prop_oneof![
+                any::<BlockMetadata>().prop_map(Transaction::BlockMetadata),
+                any::<SignedTransaction>().prop_map(Transaction::UserTransaction),
+            ],
+            1..100,
+        ),
+        first_version in 1_u64..1000,
+        first_block_height in 1_u64..1000,
+        split in any::<Index>(),
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+
+        // Block `n` emits the new block event with sequence number `n - 1`.
+        let mut next_seq_num = first_block_height - 1;
+        let events: Vec<Vec<ContractEvent>> = txns
+            .iter()
+            .map(|txn| match txn {
+                Transaction::BlockMetadata(_) => {
+                    let event = ContractEvent::new(
+                        new_block_event_key(),
+                        next_seq_num,
+                        TypeTag::Struct(NewBlockEvent::struct_tag()),
+                        vec![],
+                    );
+                    next_seq_num += 1;
+                    vec![event]
+                }
+                _ => vec![],
+            })
+            .collect();
+
+        // The ledger history before `first_version` isn't in the DB.
+        let split = split.index(txns.len());
+        for (offset, chunk) in [(0, 0..split), (split, split..txns.len())] {
+            let mut cs = ChangeSet::new();
+            store
+                .put_block_index(
+                    first_version + offset as Version,
+                    txns[chunk.clone()]
+                        .iter()
+                        .zip(events[chunk].iter().map(Vec::as_slice)),
+                    &mut cs,
+                )
+                .unwrap();
+            store.db.write_schemas(cs.batch).unwrap();
+        }
+
+        let mut block = None;
+        let mut block_height = first_block_height;
+        for (offset, txn) in txns.iter().enumerate() {
+            let version = first_version + offset as Version;
+            if matches!(txn, Transaction::BlockMetadata(_)) {
+                if block.is_some() {
+                    block_height += 1;
+                }
+                block = Some((block_height, version));
+                prop_assert_eq!(
+                    store.get_block_first_version(block_height).unwrap(),
+                    Some(version)
+                );
+            }
+            prop_assert_eq!(store.get_block_by_version(version).unwrap(), block);
+        }
+        prop_assert_eq!(store.get_block_first_version(0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_state_key_versions(
+        state_keys in vec(any::<StateKey>(), 1..5),
+        writes in vec(vec((any::<Index>(), any::<WriteOp>()), 0..5), 1..20),
+        ledger_version in 0_u64..20,
+        limit in 1_u64..20,
+    ) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+
+        let mut expected: BTreeMap<StateKey, Vec<Version>> = BTreeMap::new();
+        let mut cs = ChangeSet::new();
+        for (ver, write_ops) in writes.into_iter().enumerate() {
+            let write_set = WriteSetMut::new(
+                write_ops
+                    .into_iter()
+                    .map(|(index, write_op)| (index.get(&state_keys).clone(), write_op))
+                    .collect(),
+            )
+            .freeze()
+            .unwrap();
+            for (state_key, _) in &write_set {
+                let versions = expected.entry(state_key.clone()).or_default();
+                if versions.last() != Some(&(ver as Version)) {
+                    versions.push(ver as Version);
+                }
+            }
+            store.put_write_set(ver as Version, &write_set, &mut cs).unwrap();
+        }
+        store.db.write_schemas(cs.batch).unwrap();
+
+        for state_key in &state_keys {
+            let all_versions = expected.get(state_key).cloned().unwrap_or_default();
+            prop_assert_eq!(
+                store.get_state_key_versions(state_key, 0, u64::MAX, u64::MAX).unwrap(),
+                all_versions.clone()
+            );
+
+            let start_version = all_versions.get(1).cloned().unwrap_or(0);
+            let expected_versions: Vec<_> = all_versions
+                .into_iter()
+                .filter(|v| *v >= start_version && *v <= ledger_version)
+                .take(limit as usize)
+                .collect();
+            prop_assert_eq!(
+                store
+                    .get_state_key_versions(state_key, start_version, limit, ledger_version)
+                    .unwrap(),
+                expected_versions
+            );
+        }
+    }
+
+    #[test]
+    fn test_backfill_state_key_index(
+        state_keys in vec(any::<StateKey>(), 1..5),
+        writes in vec(vec((any::<Index>(), any::<WriteOp>()), 0..5), 1..20),
+        index_start_version in 0_u64..20,
+    ) {
+        let index_start_version = index_start_version.min(writes.len() as Version);
+        let tmp_dir = TempPath::new();
+        let mut expected: BTreeMap<StateKey, Vec<Version>> = BTreeMap::new();
+        {
+            let db = AptosDB::new_for_test(&tmp_dir);
+            let store = &db.transaction_store;
+            prop_assert_eq!(store.get_state_key_index_start_version().unwrap(), Some(0));
+
+            // The write sets before `index_start_version` were committed before the index existed.
+            let mut cs = ChangeSet::new();
+            for (ver, write_ops) in writes.into_iter().enumerate() {
+                let ver = ver as Version;
+                let write_set = WriteSetMut::new(
+                    write_ops
+                        .into_iter()
+                        .map(|(index, write_op)| (index.get(&state_keys).clone(), write_op))
+                        .collect(),
+                )
+                .freeze()
+                .unwrap();
+                for (state_key, _) in &write_set {
+                    let versions = expected.entry(state_key.clone()).or_default();
+                    if versions.last() != Some(&ver) {
+                        versions.push(ver);
+                    }
+                }
+                if ver < index_start_version {
+                    cs.batch.put::<WriteSetSchema>(&ver, &write_set).unwrap();
+                } else {
+                    store.put_write_set(ver, &write_set, &mut cs).unwrap();
+                }
+            }
+            store
+                .put_state_key_index_start_version(index_start_version, &mut cs.batch)
+                .unwrap();
+            store.db.write_schemas(cs.batch).unwrap();
+        }
+
+        // Reopening the DB backfills the index.
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let store = &db.transaction_store;
+        prop_assert_eq!(store.get_state_key_index_start_version().unwrap(), Some(0));
+        for state_key in &state_keys {
+            prop_assert_eq!(
+                store.get_state_key_versions(state_key, 0, u64::MAX, u64::MAX).unwrap(),
+                expected.get(state_key).cloned().unwrap_or_default()
+            );
+        }
+    }
+
     #[test]
     fn test_get_account_transaction_version_iter(
         universe in any_with::<AccountInfoUniverse>(5),
@@ -424,6 +424,11 @@ impl DB {
         Ok(self.inner.flush_cf(self.get_cf_handle(cf_name)?)?)
     }
 
+    /// Makes a DB opened with `open_cf_as_secondary` see the latest writes of the primary.
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        Ok(self.inner.try_catch_up_with_primary()?)
+    }
+
     pub fn get_property(&self, cf_name: &str, property_name: &str) -> Result<u64> {
         self.inner
             .property_int_value_cf(self.get_cf_handle(cf_name)?, property_name)?
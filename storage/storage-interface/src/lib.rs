@@ -32,7 +32,7 @@ use aptos_types::{
         AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
         TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
     },
-    write_set::WriteSet,
+    write_set::{WriteOp, WriteSet},
 };
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, sync::Arc};
@@ -319,6 +319,58 @@ pub trait DbReader: Send + Sync {
         unimplemented!()
     }
 
+    /// Returns the height of the block that `version` is in, or None if `version` is beyond
+    /// `ledger_version` or the block is not indexed.
+    fn get_block_height_by_version(
+        &self,
+        version: Version,
+        ledger_version: Version,
+    ) -> Result<Option<u64>> {
+        unimplemented!()
+    }
+
+    /// Returns the first and the last version of the block at `block_height`, or None if the
+    /// block is not committed as of `ledger_version` or not indexed. The last version is capped
+    /// at `ledger_version`.
+    fn get_block_version_range_by_height(
+        &self,
+        block_height: u64,
+        ledger_version: Version,
+    ) -> Result<Option<(Version, Version)>> {
+        unimplemented!()
+    }
+
+    /// Returns the height of the block with hash `block_hash`, or None if the block is not
+    /// committed as of `ledger_version` or not indexed. The genesis block has no hash to look it up
+    /// by.
+    fn get_block_height_by_hash(
+        &self,
+        block_hash: HashValue,
+        ledger_version: Version,
+    ) -> Result<Option<u64>> {
+        unimplemented!()
+    }
+
+    /// Returns the first version from which `get_state_key_history` returns the complete history of
+    /// a state key, with the state to decode the values of the history with, or `None` if there
+    /// is no ledger yet.
+    fn get_first_state_key_history_version(&self) -> Result<Option<Version>> {
+        unimplemented!()
+    }
+
+    /// Returns up to `limit` versions in `[start_version, ledger_version]` whose write sets wrote
+    /// or deleted `state_key`, along with the write op, in ascending order of version. The history
+    /// is only complete from `get_first_state_key_history_version` on.
+    fn get_state_key_history(
+        &self,
+        state_key: &StateKey,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<(Version, WriteOp)>> {
+        unimplemented!()
+    }
+
     /// Gets the version of the last transaction committed before timestamp,
     /// a committed block at or after the required timestamp must exist (otherwise it's possible
     /// the next block committed as a timestamp smaller than the one in the request).
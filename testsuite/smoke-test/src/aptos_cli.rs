@@ -1,13 +1,25 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::smoke_test_environment::new_local_swarm_with_aptos;
-use aptos::{account::create::DEFAULT_FUNDED_COINS, test::CliTestFramework};
-use aptos_config::keys::ConfigKey;
-use aptos_crypto::ed25519::Ed25519PrivateKey;
-use aptos_faucet::FaucetArgs;
-use aptos_types::{account_config::aptos_root_address, chain_id::ChainId};
+use crate::{smoke_test_environment::new_local_swarm_with_aptos, workspace_builder};
+use aptos::{
+    account::create::DEFAULT_FUNDED_COINS, common::types::CliConfig, op::key::GenerateKey,
+    test::CliTestFramework,
+};
+use aptos_config::{keys::ConfigKey, utils::get_available_port};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+use aptos_faucet::{funder_pool::FunderPoolConfig, rate_limit::RateLimitConfig, FaucetArgs};
+use aptos_rest_client::Client;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_address::AccountAddress, account_config::aptos_root_address, chain_id::ChainId,
+};
 use forge::{LocalSwarm, Node};
+use std::{
+    io::{BufRead, BufReader},
+    path::Path,
+    process::{Child, Command, Stdio},
+};
 use tokio::task::JoinHandle;
 
 pub async fn setup_test(num_nodes: usize) -> (LocalSwarm, CliTestFramework) {
@@ -43,6 +55,8 @@ pub fn launch_faucet(
         chain_id,
         maximum_amount: None,
         do_not_delegate: true,
+        rate_limit_config: RateLimitConfig::default(),
+        funder_pool_config: FunderPoolConfig::default(),
     };
     tokio::spawn(faucet.run())
 }
@@ -84,3 +98,194 @@ async fn test_account_flow() {
             .unwrap()
     );
 }
+
+#[tokio::test]
+async fn test_account_key_rotation() {
+    let (_swarm, cli) = setup_test(1).await;
+    let account = CliTestFramework::account_id(0);
+
+    let new_private_key = GenerateKey::generate_ed25519_in_memory();
+    cli.rotate_key(0, &new_private_key).await.unwrap();
+
+    // The profile keeps the account, and signs for it with the new key
+    assert_eq!(account, CliTestFramework::account_id(0));
+    let balance = cli.account_balance(0).await.unwrap();
+    let transfer_amount = 100;
+    let response = cli.transfer_coins(0, 1, transfer_amount).await.unwrap();
+    let expected_sender_amount = balance - response.gas_used.unwrap() - transfer_amount;
+    assert_eq!(
+        expected_sender_amount,
+        cli.wait_for_balance(0, expected_sender_amount)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_transfer() {
+    let (_swarm, cli) = setup_test(1).await;
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let multisig_file = dir.path().join("multisig.yaml");
+    let transaction_file = dir.path().join("transaction.bcs");
+
+    let private_keys: Vec<_> = (0..3)
+        .map(|_| GenerateKey::generate_ed25519_in_memory())
+        .collect();
+    let public_keys: Vec<_> = private_keys.iter().map(|key| key.public_key()).collect();
+    let multisig_account = cli
+        .create_multisig(&public_keys, 2, &multisig_file)
+        .await
+        .unwrap();
+    cli.fund_multisig(0, &multisig_account).await.unwrap();
+
+    let balance = cli.account_balance(0).await.unwrap();
+    let transfer_amount = 100;
+    cli.propose_multisig_transfer(&multisig_file, 0, transfer_amount, &transaction_file)
+        .await
+        .unwrap();
+
+    // Each signer signs offline, a single signature being below the threshold
+    let mut signature_files = vec![];
+    for (i, private_key) in private_keys.iter().take(2).enumerate() {
+        let signature_file = dir.path().join(format!("signature-{}.yaml", i));
+        cli.sign_multisig_transaction(
+            private_key,
+            &multisig_file,
+            &transaction_file,
+            &signature_file,
+        )
+        .await
+        .unwrap();
+        signature_files.push(signature_file);
+    }
+    assert!(cli
+        .submit_multisig_transaction(
+            &multisig_file,
+            &transaction_file,
+            signature_files[..1].to_vec()
+        )
+        .await
+        .is_err());
+
+    cli.submit_multisig_transaction(&multisig_file, &transaction_file, signature_files)
+        .await
+        .unwrap();
+    assert_eq!(
+        balance + transfer_amount,
+        cli.wait_for_balance(0, balance + transfer_amount)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_offline_transaction_signing() {
+    let (_swarm, cli) = setup_test(1).await;
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let unsigned_file = dir.path().join("unsigned.bcs");
+    let signed_file = dir.path().join("signed.bcs");
+
+    let receiver_balance = cli.account_balance(1).await.unwrap();
+    let transfer_amount = 100;
+    let raw_txn = cli
+        .transfer_coins_unsigned(0, 1, transfer_amount, &unsigned_file)
+        .await
+        .unwrap();
+    assert_eq!(raw_txn.sender(), CliTestFramework::account_id(0));
+
+    cli.sign_transaction(0, &unsigned_file, &signed_file)
+        .await
+        .unwrap();
+    cli.submit_transaction(&signed_file).await.unwrap();
+    let expected_receiver_amount = receiver_balance + transfer_amount;
+    assert_eq!(
+        expected_receiver_amount,
+        cli.wait_for_balance(1, expected_receiver_amount)
+            .await
+            .unwrap()
+    );
+}
+
+/// Runs `aptos node run-local-testnet` in `dir`, returning once the testnet is running
+fn start_local_testnet(dir: &Path, faucet_port: u16) -> Child {
+    let mut child = Command::new(workspace_builder::get_bin("aptos"))
+        .current_dir(dir)
+        .args(&["node", "run-local-testnet", "--faucet-port"])
+        .arg(faucet_port.to_string())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut lines = BufReader::new(child.stderr.take().unwrap()).lines();
+    for line in &mut lines {
+        if line.unwrap().contains("Local testnet is running") {
+            // Keep reading, so that the testnet doesn't fail writing to a closed pipe
+            std::thread::spawn(move || lines.for_each(drop));
+            return child;
+        }
+    }
+    panic!(
+        "The local testnet exited before running: {:?}",
+        child.wait()
+    );
+}
+
+/// The REST endpoint and the account of the `local` profile set up in `dir`
+fn local_profile(dir: &Path) -> (Client, AccountAddress) {
+    let bytes = std::fs::read(dir.join(".aptos/config.yaml")).unwrap();
+    let mut config: CliConfig = serde_yaml::from_slice(&bytes).unwrap();
+    let profile = config.profiles.as_mut().unwrap().remove("local").unwrap();
+    let client = Client::new(profile.rest_url.unwrap().parse().unwrap());
+    (client, profile.account.unwrap())
+}
+
+#[tokio::test]
+async fn test_run_local_testnet() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+    let faucet_port = get_available_port();
+
+    let mut testnet = start_local_testnet(dir.path(), faucet_port);
+    let (client, account) = local_profile(dir.path());
+    let balance = client
+        .get_account_balance(account)
+        .await
+        .unwrap()
+        .into_inner()
+        .get();
+    assert!(balance > 0);
+    let version = client
+        .get_ledger_information()
+        .await
+        .unwrap()
+        .into_inner()
+        .version;
+    testnet.kill().unwrap();
+    testnet.wait().unwrap();
+
+    // Without --force-restart, the testnet resumes with the same chain and profile
+    let mut testnet = start_local_testnet(dir.path(), faucet_port);
+    let (client, restarted_account) = local_profile(dir.path());
+    assert_eq!(restarted_account, account);
+    assert_eq!(
+        client
+            .get_account_balance(account)
+            .await
+            .unwrap()
+            .into_inner()
+            .get(),
+        balance
+    );
+    assert!(
+        client
+            .get_ledger_information()
+            .await
+            .unwrap()
+            .into_inner()
+            .version
+            >= version
+    );
+    testnet.kill().unwrap();
+    testnet.wait().unwrap();
+}
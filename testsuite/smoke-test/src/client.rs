@@ -7,6 +7,12 @@ use crate::{
         assert_balance, check_create_mint_transfer, create_and_fund_account, transfer_coins,
     },
 };
+use aptos_sdk::{
+    coin_client::CoinClient,
+    move_types::language_storage::TypeTag,
+    token_client::{TokenClient, TokenId},
+    types::utility_coin::TEST_COIN_TYPE,
+};
 use aptos_transaction_builder::aptos_stdlib;
 use forge::{NodeExt, Swarm};
 use std::time::{Duration, Instant};
@@ -106,3 +112,170 @@ async fn test_concurrent_transfers_single_node() {
     // assert_balance(&client, &account_0, 79).await;
     assert_balance(&client, &account_1, 31).await;
 }
+
+#[tokio::test]
+async fn test_coin_client_register_transfer_balance() {
+    let mut swarm = new_local_swarm_with_aptos(1).await;
+    let client = swarm.validators().next().unwrap().rest_client();
+    let coin_client = CoinClient::new(client.clone(), swarm.chain_info().transaction_factory());
+
+    let mut account_0 = create_and_fund_account(&mut swarm, 100).await;
+    let mut account_1 = create_and_fund_account(&mut swarm, 10).await;
+
+    // Creating an account registers the test coin, so registering again must abort
+    let coin_store = coin_client
+        .get_coin_store(account_1.address(), &TEST_COIN_TYPE)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(*coin_store.coin.value.inner(), 10);
+    assert!(coin_client
+        .register(&mut account_1, TEST_COIN_TYPE.clone())
+        .await
+        .is_err());
+
+    coin_client
+        .transfer(&mut account_0, account_1.address(), 10)
+        .await
+        .unwrap();
+    coin_client
+        .transfer_coin(
+            &mut account_0,
+            account_1.address(),
+            5,
+            TEST_COIN_TYPE.clone(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        coin_client
+            .wait_for_balance(
+                account_1.address(),
+                &TEST_COIN_TYPE,
+                25,
+                Duration::from_secs(10)
+            )
+            .await
+            .unwrap(),
+        25
+    );
+    assert_eq!(
+        coin_client
+            .get_balance(account_0.address(), &TEST_COIN_TYPE)
+            .await
+            .unwrap(),
+        85
+    );
+    assert_balance(&client, &account_0, 85).await;
+    assert_balance(&client, &account_1, 25).await;
+
+    let coin_store = coin_client
+        .get_coin_store(account_1.address(), &TEST_COIN_TYPE)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(*coin_store.deposit_events.counter.inner(), 3);
+    assert!(coin_client
+        .wait_for_balance(
+            account_1.address(),
+            &TEST_COIN_TYPE,
+            26,
+            Duration::from_secs(1)
+        )
+        .await
+        .is_err());
+
+    let coin_info = coin_client.get_coin_info(&TEST_COIN_TYPE).await.unwrap();
+    assert_eq!(coin_info.symbol, "TC");
+    assert!(coin_client.get_coin_info(&TypeTag::U64).await.is_err());
+}
+
+#[tokio::test]
+async fn test_token_client_create_offer_claim_balance() {
+    let mut swarm = new_local_swarm_with_aptos(1).await;
+    let client = swarm.validators().next().unwrap().rest_client();
+    let token_client = TokenClient::new(client, swarm.chain_info().transaction_factory());
+
+    let mut creator = create_and_fund_account(&mut swarm, 10_000).await;
+    let mut owner = create_and_fund_account(&mut swarm, 10_000).await;
+
+    token_client
+        .create_collection(&mut creator, "collection", "description", "uri", Some(2))
+        .await
+        .unwrap();
+    token_client
+        .create_token(
+            &mut creator,
+            "collection",
+            "token",
+            "token description",
+            3,
+            "token uri",
+            None,
+        )
+        .await
+        .unwrap();
+
+    let collection = token_client
+        .get_collection(creator.address(), "collection")
+        .await
+        .unwrap();
+    assert_eq!(collection.name, "collection");
+    assert_eq!(*collection.count.inner(), 1);
+    assert_eq!(
+        collection
+            .maximum
+            .into_option()
+            .map(|maximum| *maximum.inner()),
+        Some(2)
+    );
+
+    let token_id = TokenId::new(creator.address(), "collection", "token");
+    let token_data = token_client.get_token_data(&token_id).await.unwrap();
+    assert_eq!(token_data.name, "token");
+    assert_eq!(token_data.uri, "token uri");
+    assert_eq!(
+        token_data
+            .supply
+            .into_option()
+            .map(|supply| *supply.inner()),
+        Some(3)
+    );
+    assert_eq!(
+        token_client
+            .get_token_balance(creator.address(), &token_id)
+            .await
+            .unwrap(),
+        3
+    );
+
+    // The owner has no token store until the offered token is claimed
+    token_client
+        .offer_token(&mut creator, owner.address(), &token_id, 2)
+        .await
+        .unwrap();
+    assert!(token_client
+        .get_token_balance(owner.address(), &token_id)
+        .await
+        .is_err());
+    token_client
+        .claim_token(&mut owner, creator.address(), &token_id)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        token_client
+            .get_token_balance(creator.address(), &token_id)
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        token_client
+            .get_token_balance(owner.address(), &token_id)
+            .await
+            .unwrap(),
+        2
+    );
+}
@@ -4,10 +4,30 @@
 use crate::{aptos_cli::launch_faucet, smoke_test_environment::new_local_swarm_with_aptos};
 use aptos::{account::create::DEFAULT_FUNDED_COINS, test::CliTestFramework};
 use aptos_config::config::ApiConfig;
-use aptos_rosetta::{client::RosettaClient, types::AccountBalanceResponse, CURRENCY, NUM_DECIMALS};
-use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
+use aptos_crypto::{
+    ed25519::Ed25519PrivateKey, HashValue, PrivateKey, Uniform, ValidCryptoMaterialStringExt,
+};
+use aptos_rosetta::{
+    client::RosettaClient,
+    types::{
+        AccountBalanceResponse, BlockRequest, BlockTransactionRequest, ConstructionDeriveRequest,
+        ConstructionParseRequest, CurveType, MempoolRequest, MempoolTransactionRequest,
+        NetworkRequest, PartialBlockIdentifier, PublicKey, TransactionIdentifier,
+    },
+    CURRENCY, NUM_DECIMALS,
+};
+use aptos_sdk::transaction_builder::TransactionFactory;
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    transaction::{authenticator::AuthenticationKey, ScriptFunction, TransactionPayload},
+};
 use forge::{LocalSwarm, Node};
-use std::{str::FromStr, time::Duration};
+use move_deps::move_core_types::{
+    ident_str,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
+use std::{collections::BTreeMap, str::FromStr, time::Duration};
 
 pub async fn setup_test(num_nodes: usize) -> (LocalSwarm, CliTestFramework, RosettaClient) {
     let swarm = new_local_swarm_with_aptos(num_nodes).await;
@@ -35,6 +55,8 @@ pub async fn setup_test(num_nodes: usize) -> (LocalSwarm, CliTestFramework, Rose
         tls_cert_path: None,
         tls_key_path: None,
         content_length_limit: None,
+        max_view_function_gas: None,
+        max_submit_transaction_batch_size: None,
     };
 
     // Start the server
@@ -68,6 +90,349 @@ async fn test_account_balance() {
     assert_eq!(DEFAULT_FUNDED_COINS, u64::from_str(&balance.value).unwrap());
 }
 
+#[tokio::test]
+#[ignore]
+async fn test_transfer() {
+    let (_swarm, cli, rosetta_client) = setup_test(1).await;
+    let chain_id = ChainId::test();
+
+    cli.create_account_with_faucet(0).await.unwrap();
+    let sender = CliTestFramework::account_id(0);
+    let sender_key = CliTestFramework::private_key(0);
+    get_account_balance_once_ready(&rosetta_client, sender)
+        .await
+        .unwrap();
+
+    // The derived address must match the account the CLI created
+    let derived = rosetta_client
+        .construction_derive(&ConstructionDeriveRequest {
+            network_identifier: chain_id.into(),
+            public_key: PublicKey {
+                hex_bytes: sender_key.public_key().to_encoded_string().unwrap(),
+                curve_type: CurveType::Edwards25519,
+            },
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        sender,
+        derived
+            .account_identifier
+            .unwrap()
+            .account_address()
+            .unwrap()
+    );
+
+    // Create the receiver, then transfer to it
+    let receiver_key = Ed25519PrivateKey::generate(&mut rand::rngs::OsRng);
+    let receiver = AuthenticationKey::ed25519(&receiver_key.public_key()).derived_address();
+    rosetta_client
+        .create_account(chain_id, sender, receiver, &sender_key)
+        .await
+        .unwrap();
+    let response = get_account_balance_once_ready(&rosetta_client, receiver)
+        .await
+        .unwrap();
+    assert_eq!("0", response.balances.first().unwrap().value);
+
+    let amount = 100;
+    rosetta_client
+        .transfer(chain_id, sender, receiver, amount, &sender_key)
+        .await
+        .unwrap();
+    let mut balance = 0;
+    for _ in 1..10 {
+        let response = get_account_balance_once_ready(&rosetta_client, receiver)
+            .await
+            .unwrap();
+        balance = u64::from_str(&response.balances.first().unwrap().value).unwrap();
+        if balance == amount {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    assert_eq!(amount, balance);
+
+    // Only transfers of the native coin are parsed as transfer operations
+    let other_coin = TypeTag::Struct(StructTag {
+        address: AccountAddress::ONE,
+        module: ident_str!("TestCoin").to_owned(),
+        name: ident_str!("OtherCoin").to_owned(),
+        type_params: vec![],
+    });
+    let raw_txn = TransactionFactory::new(chain_id)
+        .payload(TransactionPayload::ScriptFunction(ScriptFunction::new(
+            ModuleId::new(AccountAddress::ONE, ident_str!("Coin").to_owned()),
+            ident_str!("transfer").to_owned(),
+            vec![other_coin],
+            vec![
+                bcs::to_bytes(&receiver).unwrap(),
+                bcs::to_bytes(&amount).unwrap(),
+            ],
+        )))
+        .sender(sender)
+        .sequence_number(0)
+        .build();
+    assert!(rosetta_client
+        .construction_parse(&ConstructionParseRequest {
+            network_identifier: chain_id.into(),
+            signed: false,
+            transaction: hex::encode(bcs::to_bytes(&raw_txn).unwrap()),
+        })
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_block_operations_match_balances() {
+    let (swarm, cli, rosetta_client) = setup_test(1).await;
+    let chain_id = ChainId::test();
+    let rest_client =
+        aptos_rest_client::Client::new(swarm.validators().next().unwrap().rest_api_endpoint());
+
+    cli.create_account_with_faucet(0).await.unwrap();
+    cli.create_account_with_faucet(1).await.unwrap();
+    let sender = CliTestFramework::account_id(0);
+    let sender_key = CliTestFramework::private_key(0);
+    let receiver = CliTestFramework::account_id(1);
+    get_account_balance_once_ready(&rosetta_client, sender)
+        .await
+        .unwrap();
+    get_account_balance_once_ready(&rosetta_client, receiver)
+        .await
+        .unwrap();
+
+    // A successful transfer, and one which fails but still pays for gas
+    let transfer = rosetta_client
+        .transfer(chain_id, sender, receiver, 100, &sender_key)
+        .await
+        .unwrap();
+    let transfer = wait_for_transaction(&rest_client, &transfer.hash).await;
+    assert!(transfer.success());
+    let failed_transfer = rosetta_client
+        .transfer(chain_id, sender, receiver, u64::MAX, &sender_key)
+        .await
+        .unwrap();
+    let failed_transfer = wait_for_transaction(&rest_client, &failed_transfer.hash).await;
+    assert!(!failed_transfer.success());
+
+    // Replay every block, summing the successful operations of each account
+    let status = rosetta_client
+        .network_status(&NetworkRequest {
+            network_identifier: chain_id.into(),
+        })
+        .await
+        .unwrap();
+    let mut balances: BTreeMap<AccountAddress, i128> = BTreeMap::new();
+    let mut operation_types = BTreeMap::new();
+    for height in 0..=status.current_block_identifier.index {
+        let block = rosetta_client
+            .block(&BlockRequest {
+                network_identifier: chain_id.into(),
+                block_identifier: PartialBlockIdentifier {
+                    index: Some(height),
+                    hash: None,
+                },
+            })
+            .await
+            .unwrap()
+            .block
+            .unwrap();
+        // The block can also be looked up by its hash alone
+        let block_by_hash = rosetta_client
+            .block(&BlockRequest {
+                network_identifier: chain_id.into(),
+                block_identifier: PartialBlockIdentifier {
+                    index: None,
+                    hash: Some(block.block_identifier.hash.clone()),
+                },
+            })
+            .await
+            .unwrap()
+            .block
+            .unwrap();
+        assert_eq!(block, block_by_hash);
+
+        for txn in block.transactions {
+            // Each transaction can also be looked up on its own
+            let block_transaction = rosetta_client
+                .block_transaction(&BlockTransactionRequest {
+                    network_identifier: chain_id.into(),
+                    block_identifier: block.block_identifier.clone(),
+                    transaction_identifier: txn.transaction_identifier.clone(),
+                })
+                .await
+                .unwrap();
+            assert_eq!(txn, block_transaction.transaction);
+
+            for operation in txn.operations {
+                operation_types.insert(
+                    (
+                        txn.transaction_identifier.hash.clone(),
+                        operation.type_.clone(),
+                    ),
+                    operation.status.clone().unwrap(),
+                );
+                if operation.status.as_deref() != Some("success") {
+                    continue;
+                }
+                let amount = operation.amount.unwrap();
+                assert_eq!(CURRENCY, amount.currency.symbol);
+                let address = operation.account.unwrap().account_address().unwrap();
+                *balances.entry(address).or_default() += i128::from_str(&amount.value).unwrap();
+            }
+        }
+    }
+
+    // Without an identifier, the latest block is returned
+    let latest_block = rosetta_client
+        .block(&BlockRequest {
+            network_identifier: chain_id.into(),
+            block_identifier: PartialBlockIdentifier {
+                index: None,
+                hash: None,
+            },
+        })
+        .await
+        .unwrap()
+        .block
+        .unwrap();
+    assert!(latest_block.block_identifier.index >= status.current_block_identifier.index);
+
+    let transfer_hash = transfer.transaction_info().unwrap().hash.to_string();
+    for type_ in ["withdraw", "deposit", "fee"] {
+        assert_eq!(
+            Some(&"success".to_string()),
+            operation_types.get(&(transfer_hash.clone(), type_.to_string()))
+        );
+    }
+    let failed_hash = failed_transfer.transaction_info().unwrap().hash.to_string();
+    assert_eq!(
+        Some(&"success".to_string()),
+        operation_types.get(&(failed_hash.clone(), "fee".to_string()))
+    );
+    assert!(!operation_types.contains_key(&(failed_hash, "deposit".to_string())));
+
+    assert!(balances.contains_key(&sender));
+    assert!(balances.contains_key(&receiver));
+    for (address, balance) in balances {
+        let expected = rest_client
+            .get_account_balance(address)
+            .await
+            .unwrap()
+            .into_inner()
+            .get();
+        assert_eq!(i128::from(expected), balance, "balance of {}", address);
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_mempool() {
+    let (swarm, cli, rosetta_client) = setup_test(1).await;
+    let chain_id = ChainId::test();
+    let rest_client =
+        aptos_rest_client::Client::new(swarm.validators().next().unwrap().rest_api_endpoint());
+
+    // The node can't list its mempool
+    assert!(rosetta_client
+        .mempool(&MempoolRequest {
+            network_identifier: chain_id.into(),
+        })
+        .await
+        .is_err());
+
+    cli.create_account_with_faucet(0).await.unwrap();
+    cli.create_account_with_faucet(1).await.unwrap();
+    let sender = CliTestFramework::account_id(0);
+    let sender_key = CliTestFramework::private_key(0);
+    let receiver = CliTestFramework::account_id(1);
+    get_account_balance_once_ready(&rosetta_client, sender)
+        .await
+        .unwrap();
+
+    // A sequence number gap keeps the transfer pending in mempool
+    let sequence_number = rest_client
+        .get_account(sender)
+        .await
+        .unwrap()
+        .into_inner()
+        .sequence_number;
+    let pending_txn = TransactionFactory::new(chain_id)
+        .transfer(receiver, 100)
+        .sender(sender)
+        .sequence_number(sequence_number + 1)
+        .build()
+        .sign(&sender_key, sender_key.public_key())
+        .unwrap()
+        .into_inner();
+    let hash = rest_client
+        .submit(&pending_txn)
+        .await
+        .unwrap()
+        .into_inner()
+        .hash
+        .to_string();
+    assert!(rest_client
+        .get_transaction(HashValue::from_str(hash.strip_prefix("0x").unwrap_or(&hash)).unwrap())
+        .await
+        .unwrap()
+        .into_inner()
+        .is_pending());
+
+    let transaction = rosetta_client
+        .mempool_transaction(&MempoolTransactionRequest {
+            network_identifier: chain_id.into(),
+            transaction_identifier: TransactionIdentifier { hash: hash.clone() },
+        })
+        .await
+        .unwrap()
+        .transaction;
+    assert_eq!(hash, transaction.transaction_identifier.hash);
+    let operations: Vec<_> = transaction
+        .operations
+        .iter()
+        .map(|operation| {
+            (
+                operation.type_.clone(),
+                operation
+                    .account
+                    .clone()
+                    .unwrap()
+                    .account_address()
+                    .unwrap(),
+                operation.amount.clone().unwrap().value,
+                operation.status.clone(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("withdraw".to_string(), sender, "-100".to_string(), None),
+            ("deposit".to_string(), receiver, "100".to_string(), None),
+        ],
+        operations
+    );
+}
+
+async fn wait_for_transaction(
+    rest_client: &aptos_rest_client::Client,
+    hash: &str,
+) -> aptos_rest_client::Transaction {
+    let hash = HashValue::from_str(hash.strip_prefix("0x").unwrap_or(hash)).unwrap();
+    for _ in 1..20 {
+        if let Ok(response) = rest_client.get_transaction(hash).await {
+            let txn = response.into_inner();
+            if !txn.is_pending() {
+                return txn;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    panic!("Transaction {} wasn't committed", hash);
+}
+
 async fn get_account_balance_once_ready(
     rosetta_client: &RosettaClient,
     account: AccountAddress,
@@ -490,6 +490,12 @@ pub struct SignedTransaction {
 pub struct SignatureCheckedTransaction(SignedTransaction);
 
 impl SignatureCheckedTransaction {
+    /// Wraps a `SignedTransaction` without verifying its signature. This must only be used for
+    /// transaction simulation, whose output is never committed.
+    pub fn new_unchecked_for_simulation(txn: SignedTransaction) -> Self {
+        Self(txn)
+    }
+
     /// Returns the `SignedTransaction` within.
     pub fn into_inner(self) -> SignedTransaction {
         self.0
@@ -714,6 +720,13 @@ impl TransactionWithProof {
             sequence_number,
         );
 
+        self.verify(ledger_info)
+    }
+
+    /// Verifies the transaction with the proof, both carried by `self`, ensuring that the
+    /// transaction, and its events if present, exist at `self.version` in the ledger represented
+    /// by `ledger_info`.
+    pub fn verify(&self, ledger_info: &LedgerInfo) -> Result<()> {
         let txn_hash = self.transaction.hash();
         ensure!(
             txn_hash == self.proof.transaction_info().transaction_hash(),
@@ -735,7 +748,7 @@ impl TransactionWithProof {
             );
         }
 
-        self.proof.verify(ledger_info, version)
+        self.proof.verify(ledger_info, self.version)
     }
 }
 